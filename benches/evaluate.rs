@@ -0,0 +1,88 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use splines::{random_points, Memo, Point, Spline, SplineType, Track};
+
+// Run with and without `--features simd` to compare the two batch paths.
+fn evaluate(c: &mut Criterion) {
+    let points = random_points(1, 32, (-400.0, 400.0), 200.0);
+    let mut group = c.benchmark_group("evaluate 4096 sorted queries");
+
+    for spline_type in [SplineType::Linear, SplineType::Cubic] {
+        let spline = Spline::new(&points, spline_type);
+        let xs: Vec<f32> = spline.iter_samples(4096).map(|p| p.x).collect();
+        let mut out = vec![0.0; xs.len()];
+
+        group.bench_function(BenchmarkId::new("evaluate", format!("{:?}", spline_type)), |b| {
+            b.iter(|| {
+                for (&x, y) in xs.iter().zip(out.iter_mut()) {
+                    *y = spline.evaluate(black_box(x));
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("evaluate_many", format!("{:?}", spline_type)), |b| {
+            b.iter(|| spline.evaluate_many(black_box(&xs), &mut out))
+        });
+        let baked = spline.bake();
+        group.bench_function(BenchmarkId::new("baked", format!("{:?}", spline_type)), |b| {
+            b.iter(|| {
+                for (&x, y) in xs.iter().zip(out.iter_mut()) {
+                    *y = baked.evaluate(black_box(x));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+// What a frame of playback costs: the curve's polyline, the eased track the
+// marker follows and the marker itself, with everything rebuilt each frame,
+// kept between edits, or kept but invalidated by an edit every frame.
+fn playback(c: &mut Criterion) {
+    let points = random_points(1, 32, (-400.0, 400.0), 200.0);
+    let ease_points = [
+        Point::new(0.0, 0.0),
+        Point::new(0.3, 0.1),
+        Point::new(0.7, 0.9),
+        Point::new(1.0, 1.0),
+    ];
+    let build = || {
+        let spline = Spline::new(&points, SplineType::Cubic);
+        let polyline = spline.samples(401);
+        let (min, max) = spline.domain();
+        let scaled: Vec<Point> = ease_points
+            .iter()
+            .map(|p| Point::new(min + p.x * (max - min), min + p.y * (max - min)))
+            .collect();
+        let mut track = Track::new(spline);
+        track.set_ease(Some(Spline::new(&scaled, SplineType::Cubic)));
+        (polyline, track)
+    };
+    // The view copies the polyline out and places the marker at the playhead.
+    let frame = |plan: &(Vec<Point>, Track), phase: f32| {
+        let (polyline, track) = plan;
+        let drawn: Vec<(f32, f32)> = polyline.iter().map(|p| (p.x, p.y)).collect();
+        let (min, max) = track.curve().domain();
+        let t = min + phase * (max - min);
+        black_box((drawn, track.remap(t), track.evaluate(t)));
+    };
+
+    let mut group = c.benchmark_group("playback frame");
+    let mut phase = 0.0;
+    let mut advance = move || {
+        phase = (phase + 1.0 / 60.0) % 1.0;
+        phase
+    };
+    group.bench_function("rebuilt", |b| b.iter(|| frame(&build(), advance())));
+    let plan = Memo::new();
+    group.bench_function("cached", |b| b.iter(|| frame(&plan.get(0u64, build), advance())));
+    let mut revision = 0u64;
+    group.bench_function("edited every frame", |b| {
+        b.iter(|| {
+            revision += 1;
+            frame(&plan.get(revision, build), advance())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, evaluate, playback);
+criterion_main!(benches);
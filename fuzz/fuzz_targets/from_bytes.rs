@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use splines::{Precision, Spline};
+
+fuzz_target!(|data: &[u8]| {
+    let spline = match Spline::from_bytes(data) {
+        Ok(spline) => spline,
+        Err(_) => return,
+    };
+
+    let (min_x, max_x) = spline.domain();
+    for x in [min_x, max_x, (min_x + max_x) / 2.0, min_x - 1.0, max_x + 1.0] {
+        spline.evaluate(x);
+    }
+
+    // Full-precision encodings must round-trip byte for byte.
+    if data[6] == 0 {
+        assert_eq!(spline.to_bytes(Precision::Float32), data);
+    }
+});
@@ -0,0 +1,55 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use splines::{Point, Spline, SplineType};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    points: Vec<(f32, f32)>,
+    spline_type: u8,
+    queries: Vec<f32>,
+}
+
+fuzz_target!(|input: Input| {
+    let spline_type = match input.spline_type % 3 {
+        0 => SplineType::Linear,
+        1 => SplineType::Quadratic,
+        _ => SplineType::Cubic,
+    };
+    let points: Vec<Point> = input.points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+
+    let spline = match Spline::try_new(&points, spline_type) {
+        Ok(spline) => spline,
+        Err(_) => return,
+    };
+
+    for &x in &input.queries {
+        spline.evaluate(x);
+    }
+    spline.iter_samples(input.queries.len().min(64)).for_each(drop);
+
+    // Past that, only well-conditioned knots are held to accuracy: with
+    // spacings far apart in scale the fit cancels away every significant
+    // bit of an f32. Each knot after the first is evaluated at the end of
+    // the segment before it, so it must come back up to rounding in that
+    // segment's terms.
+    if !well_conditioned(spline.points()) {
+        return;
+    }
+    for (segment, p) in spline.segments().zip(&spline.points()[1..]) {
+        let h = segment.x1 - segment.x0;
+        let scale: f32 = (0..segment.coeffs.len())
+            .map(|k| (segment.coefficient(k) * h.powi(k as i32)).abs())
+            .sum();
+        let error = (spline.evaluate(p.x) - p.y).abs();
+        assert!(error <= 1e-4 * scale, "knot at x = {} missed by {}", p.x, error);
+    }
+});
+
+fn well_conditioned(points: &[Point]) -> bool {
+    let in_range = points.iter().all(|p| p.x.abs() <= 1e6 && p.y.abs() <= 1e6);
+    let spacings = points.windows(2).map(|pair| pair[1].x - pair[0].x);
+    let (min_h, max_h) = spacings.fold((f32::INFINITY, 0.0f32), |(lo, hi), h| (lo.min(h), hi.max(h)));
+    in_range && max_h <= 1e3 * min_h
+}
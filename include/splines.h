@@ -0,0 +1,67 @@
+#ifndef SPLINES_H
+#define SPLINES_H
+
+/* Generated by cbindgen from src/capi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define SPLINE_LINEAR 0
+
+#define SPLINE_QUADRATIC 1
+
+#define SPLINE_CUBIC 2
+
+typedef struct Spline Spline;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Fits a spline through `len` points given as parallel `xs` and `ys`
+// arrays, in any order. `spline_type` is one of `SPLINE_LINEAR`,
+// `SPLINE_QUADRATIC` or `SPLINE_CUBIC`.
+//
+// Returns NULL for an unknown type, NULL arrays, fewer than 2 points,
+// repeated x values or NaN/infinite coordinates. A non-NULL result must be
+// released with `spline_destroy`.
+//
+// # Safety
+//
+// `xs` and `ys` must each point to `len` readable floats.
+Spline *spline_create(const float *xs, const float *ys, uintptr_t len, uint32_t spline_type);
+
+// Evaluates the spline at `x`, or returns NaN if `spline` is NULL.
+//
+// # Safety
+//
+// `spline` must be NULL or a pointer returned by `spline_create` that has
+// not been destroyed.
+float spline_evaluate(const Spline *spline, float x);
+
+// Evaluates the spline at each of the `len` values in `xs`, writing the
+// results to `out`. Ascending `xs` are fastest. Does nothing if any pointer
+// is NULL.
+//
+// # Safety
+//
+// `spline` must be NULL or a live pointer from `spline_create`; `xs` must
+// point to `len` readable floats and `out` to `len` writable floats that
+// don't overlap `xs`.
+void spline_evaluate_many(const Spline *spline, const float *xs, float *out, uintptr_t len);
+
+// Releases a spline. Passing NULL is a no-op.
+//
+// # Safety
+//
+// `spline` must be NULL or a pointer from `spline_create` that has not
+// already been destroyed.
+void spline_destroy(Spline *spline);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* SPLINES_H */
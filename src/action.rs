@@ -0,0 +1,503 @@
+use nannou::prelude::Key;
+use splines::Preset;
+
+use crate::config::Binding;
+
+/// Everything the main canvas does from the keyboard. Keys are looked up in
+/// the shortcuts to find an action, and the command palette runs them by
+/// name, so both go through the same handler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    CommandPalette,
+    ToggleControlPoints,
+    ResetPoints,
+    RandomPoints,
+    ClearPoints,
+    LoadPreset(Preset),
+    FewerSamplePoints,
+    MoreSamplePoints,
+    Linear,
+    Quadratic,
+    Cubic,
+    /// Through every registered interpolation method, plugins included.
+    NextInterpolator,
+    CopyShareString,
+    PasteShareString,
+    SaveSession,
+    Export,
+    ExportPoints,
+    ExportSteps,
+    Bake,
+    KeyboardEditing,
+    NextLanguage,
+    RungeDemo,
+    AdaptiveFit,
+    Smooth,
+    RobustSmooth,
+    AddDataNoise,
+    ConfidenceBand,
+    LiveSignal,
+    /// Up and Down: whatever the active mode adjusts.
+    Increase,
+    Decrease,
+    /// Shift+Up and Shift+Down: quantizer steps, or else like Up and Down.
+    IncreaseSteps,
+    DecreaseSteps,
+    LedOutput,
+    LogScaleX,
+    LogScaleY,
+    ZoomIn,
+    ZoomOut,
+    AngleMode,
+    OutputLimits,
+    ShapeConstraint,
+    MarkConstraintEdge,
+    ApplyShapeConstraint,
+    MasterEditing,
+    AddDetailLayer,
+    /// Numbered from 1.
+    ToggleDetailLayer(usize),
+    ClearDetailLayers,
+    NewGroup,
+    NextGroup,
+    HideGroup,
+    LockGroup,
+    Annotate,
+    RemoveAnnotation,
+    TagPoint,
+    ClearTags,
+    Ruler,
+    RgbChannels,
+    GradientDesigner,
+    CameraEditor,
+    GlyphSketcher,
+    Tiling,
+    MirrorTiles,
+    Spectrum,
+    Easing,
+    Terrain,
+    Quantize,
+    Noise,
+    ReseedNoise,
+    NoiseToOutputs,
+    NoiseAmplitudeUp,
+    NoiseAmplitudeDown,
+    NoiseFrequencyUp,
+    NoiseFrequencyDown,
+    Quit,
+    /// Escape in the gradient designer, camera editor and glyph sketcher.
+    LeaveMode,
+    NextColorSpace,
+    SetStopColor,
+    /// The selected gradient stop or camera keyframe.
+    DeleteSelected,
+    PreviewCamera,
+    CloseContour,
+    UndoGlyphPoint,
+    RemoveContour,
+    UnionContours,
+    IntersectContours,
+    SubtractContours,
+    ApplyBoolean,
+    NarrowerGlyph,
+    WiderGlyph,
+}
+
+/// Where an action's shortcut works: on the canvas, or in one of the modes
+/// that take the keyboard over. Actions that share a scope need different
+/// keys; actions that don't can share one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    Canvas,
+    Gradient,
+    Camera,
+    Glyph,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [Scope::Canvas, Scope::Gradient, Scope::Camera, Scope::Glyph];
+}
+
+const DIGITS: [Key; 10] = [
+    Key::Key0,
+    Key::Key1,
+    Key::Key2,
+    Key::Key3,
+    Key::Key4,
+    Key::Key5,
+    Key::Key6,
+    Key::Key7,
+    Key::Key8,
+    Key::Key9,
+];
+
+const DETAIL_LAYER_IDS: [&str; 9] = [
+    "toggle_detail_layer_1",
+    "toggle_detail_layer_2",
+    "toggle_detail_layer_3",
+    "toggle_detail_layer_4",
+    "toggle_detail_layer_5",
+    "toggle_detail_layer_6",
+    "toggle_detail_layer_7",
+    "toggle_detail_layer_8",
+    "toggle_detail_layer_9",
+];
+
+impl Action {
+    /// In the order the command palette lists them.
+    pub const ALL: [Action; 99] = [
+        Action::ToggleControlPoints,
+        Action::ResetPoints,
+        Action::RandomPoints,
+        Action::ClearPoints,
+        Action::LoadPreset(Preset::Sine),
+        Action::LoadPreset(Preset::DampedOscillation),
+        Action::LoadPreset(Preset::StepResponse),
+        Action::LoadPreset(Preset::Sigmoid),
+        Action::LoadPreset(Preset::HeartRate),
+        Action::FewerSamplePoints,
+        Action::MoreSamplePoints,
+        Action::Linear,
+        Action::Quadratic,
+        Action::Cubic,
+        Action::NextInterpolator,
+        Action::CopyShareString,
+        Action::PasteShareString,
+        Action::SaveSession,
+        Action::Export,
+        Action::ExportPoints,
+        Action::ExportSteps,
+        Action::Bake,
+        Action::KeyboardEditing,
+        Action::NextLanguage,
+        Action::RungeDemo,
+        Action::AdaptiveFit,
+        Action::Smooth,
+        Action::RobustSmooth,
+        Action::AddDataNoise,
+        Action::ConfidenceBand,
+        Action::LiveSignal,
+        Action::Increase,
+        Action::Decrease,
+        Action::IncreaseSteps,
+        Action::DecreaseSteps,
+        Action::LedOutput,
+        Action::LogScaleX,
+        Action::LogScaleY,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::AngleMode,
+        Action::OutputLimits,
+        Action::ShapeConstraint,
+        Action::MarkConstraintEdge,
+        Action::ApplyShapeConstraint,
+        Action::MasterEditing,
+        Action::AddDetailLayer,
+        Action::ToggleDetailLayer(1),
+        Action::ToggleDetailLayer(2),
+        Action::ToggleDetailLayer(3),
+        Action::ToggleDetailLayer(4),
+        Action::ToggleDetailLayer(5),
+        Action::ToggleDetailLayer(6),
+        Action::ToggleDetailLayer(7),
+        Action::ToggleDetailLayer(8),
+        Action::ToggleDetailLayer(9),
+        Action::ClearDetailLayers,
+        Action::NewGroup,
+        Action::NextGroup,
+        Action::HideGroup,
+        Action::LockGroup,
+        Action::Annotate,
+        Action::RemoveAnnotation,
+        Action::TagPoint,
+        Action::ClearTags,
+        Action::Ruler,
+        Action::RgbChannels,
+        Action::GradientDesigner,
+        Action::CameraEditor,
+        Action::GlyphSketcher,
+        Action::Tiling,
+        Action::MirrorTiles,
+        Action::Spectrum,
+        Action::Easing,
+        Action::Terrain,
+        Action::Quantize,
+        Action::Noise,
+        Action::ReseedNoise,
+        Action::NoiseToOutputs,
+        Action::NoiseAmplitudeUp,
+        Action::NoiseAmplitudeDown,
+        Action::NoiseFrequencyUp,
+        Action::NoiseFrequencyDown,
+        Action::NextColorSpace,
+        Action::SetStopColor,
+        Action::DeleteSelected,
+        Action::PreviewCamera,
+        Action::CloseContour,
+        Action::UndoGlyphPoint,
+        Action::RemoveContour,
+        Action::UnionContours,
+        Action::IntersectContours,
+        Action::SubtractContours,
+        Action::ApplyBoolean,
+        Action::NarrowerGlyph,
+        Action::WiderGlyph,
+        Action::LeaveMode,
+        Action::CommandPalette,
+        Action::Quit,
+    ];
+
+    /// The action's name in the config file's `[shortcuts]` table, and its
+    /// locale key under `actions`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::CommandPalette => "command_palette",
+            Action::ToggleControlPoints => "toggle_control_points",
+            Action::ResetPoints => "reset_points",
+            Action::RandomPoints => "random_points",
+            Action::ClearPoints => "clear_points",
+            Action::LoadPreset(Preset::Sine) => "preset_sine",
+            Action::LoadPreset(Preset::DampedOscillation) => "preset_damped_oscillation",
+            Action::LoadPreset(Preset::StepResponse) => "preset_step_response",
+            Action::LoadPreset(Preset::Sigmoid) => "preset_sigmoid",
+            Action::LoadPreset(Preset::HeartRate) => "preset_heart_rate",
+            Action::FewerSamplePoints => "fewer_sample_points",
+            Action::MoreSamplePoints => "more_sample_points",
+            Action::Linear => "linear",
+            Action::Quadratic => "quadratic",
+            Action::Cubic => "cubic",
+            Action::NextInterpolator => "next_interpolator",
+            Action::CopyShareString => "copy_share_string",
+            Action::PasteShareString => "paste_share_string",
+            Action::SaveSession => "save_session",
+            Action::Export => "export",
+            Action::ExportPoints => "export_points",
+            Action::ExportSteps => "export_steps",
+            Action::Bake => "bake",
+            Action::KeyboardEditing => "keyboard_editing",
+            Action::NextLanguage => "next_language",
+            Action::RungeDemo => "runge_demo",
+            Action::AdaptiveFit => "adaptive_fit",
+            Action::Smooth => "smooth",
+            Action::RobustSmooth => "robust_smooth",
+            Action::AddDataNoise => "add_data_noise",
+            Action::ConfidenceBand => "confidence_band",
+            Action::LiveSignal => "live_signal",
+            Action::Increase => "increase",
+            Action::Decrease => "decrease",
+            Action::IncreaseSteps => "increase_steps",
+            Action::DecreaseSteps => "decrease_steps",
+            Action::LedOutput => "led_output",
+            Action::LogScaleX => "log_scale_x",
+            Action::LogScaleY => "log_scale_y",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::AngleMode => "angle_mode",
+            Action::OutputLimits => "output_limits",
+            Action::ShapeConstraint => "shape_constraint",
+            Action::MarkConstraintEdge => "mark_constraint_edge",
+            Action::ApplyShapeConstraint => "apply_shape_constraint",
+            Action::MasterEditing => "master_editing",
+            Action::AddDetailLayer => "add_detail_layer",
+            Action::ToggleDetailLayer(n) => DETAIL_LAYER_IDS[(*n).clamp(1, 9) - 1],
+            Action::ClearDetailLayers => "clear_detail_layers",
+            Action::NewGroup => "new_group",
+            Action::NextGroup => "next_group",
+            Action::HideGroup => "hide_group",
+            Action::LockGroup => "lock_group",
+            Action::Annotate => "annotate",
+            Action::RemoveAnnotation => "remove_annotation",
+            Action::TagPoint => "tag_point",
+            Action::ClearTags => "clear_tags",
+            Action::Ruler => "ruler",
+            Action::RgbChannels => "rgb_channels",
+            Action::GradientDesigner => "gradient_designer",
+            Action::CameraEditor => "camera_editor",
+            Action::GlyphSketcher => "glyph_sketcher",
+            Action::Tiling => "tiling",
+            Action::MirrorTiles => "mirror_tiles",
+            Action::Spectrum => "spectrum",
+            Action::Easing => "easing",
+            Action::Terrain => "terrain",
+            Action::Quantize => "quantize",
+            Action::Noise => "noise",
+            Action::ReseedNoise => "reseed_noise",
+            Action::NoiseToOutputs => "noise_to_outputs",
+            Action::NoiseAmplitudeUp => "noise_amplitude_up",
+            Action::NoiseAmplitudeDown => "noise_amplitude_down",
+            Action::NoiseFrequencyUp => "noise_frequency_up",
+            Action::NoiseFrequencyDown => "noise_frequency_down",
+            Action::Quit => "quit",
+            Action::LeaveMode => "leave_mode",
+            Action::NextColorSpace => "next_color_space",
+            Action::SetStopColor => "set_stop_color",
+            Action::DeleteSelected => "delete_selected",
+            Action::PreviewCamera => "preview_camera",
+            Action::CloseContour => "close_contour",
+            Action::UndoGlyphPoint => "undo_glyph_point",
+            Action::RemoveContour => "remove_contour",
+            Action::UnionContours => "union_contours",
+            Action::IntersectContours => "intersect_contours",
+            Action::SubtractContours => "subtract_contours",
+            Action::ApplyBoolean => "apply_boolean",
+            Action::NarrowerGlyph => "narrower_glyph",
+            Action::WiderGlyph => "wider_glyph",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    pub fn default_binding(&self) -> Binding {
+        let ctrl = |key| Binding { key, shift: false, ctrl: true };
+        let shift = |key| Binding { key, shift: true, ctrl: false };
+        let plain = |key| Binding { key, shift: false, ctrl: false };
+        match *self {
+            Action::CommandPalette => ctrl(Key::K),
+            Action::ToggleControlPoints => plain(Key::H),
+            Action::ResetPoints => plain(Key::R),
+            Action::RandomPoints => shift(Key::R),
+            Action::ClearPoints => plain(Key::C),
+            Action::LoadPreset(Preset::Sine) => plain(Key::F1),
+            Action::LoadPreset(Preset::DampedOscillation) => plain(Key::F2),
+            Action::LoadPreset(Preset::StepResponse) => plain(Key::F3),
+            Action::LoadPreset(Preset::Sigmoid) => plain(Key::F4),
+            Action::LoadPreset(Preset::HeartRate) => plain(Key::F5),
+            Action::FewerSamplePoints => plain(Key::LBracket),
+            Action::MoreSamplePoints => plain(Key::RBracket),
+            Action::Linear => plain(Key::Key1),
+            Action::Quadratic => plain(Key::Key2),
+            Action::Cubic => plain(Key::Key3),
+            Action::NextInterpolator => plain(Key::Key4),
+            Action::CopyShareString => ctrl(Key::C),
+            Action::PasteShareString => ctrl(Key::V),
+            Action::SaveSession => ctrl(Key::S),
+            Action::Export => ctrl(Key::E),
+            Action::ExportPoints => ctrl(Key::P),
+            Action::ExportSteps => ctrl(Key::F),
+            Action::Bake => ctrl(Key::B),
+            Action::KeyboardEditing => plain(Key::Insert),
+            Action::NextLanguage => ctrl(Key::L),
+            Action::RungeDemo => plain(Key::G),
+            Action::AdaptiveFit => plain(Key::A),
+            Action::Smooth => plain(Key::S),
+            Action::RobustSmooth => shift(Key::S),
+            Action::AddDataNoise => plain(Key::N),
+            Action::ConfidenceBand => plain(Key::B),
+            Action::LiveSignal => plain(Key::L),
+            Action::Increase => plain(Key::Up),
+            Action::Decrease => plain(Key::Down),
+            Action::IncreaseSteps => shift(Key::Up),
+            Action::DecreaseSteps => shift(Key::Down),
+            Action::LedOutput => plain(Key::O),
+            Action::LogScaleX => plain(Key::X),
+            Action::LogScaleY => plain(Key::Y),
+            Action::ZoomIn => plain(Key::Equals),
+            Action::ZoomOut => plain(Key::Minus),
+            Action::AngleMode => plain(Key::W),
+            Action::OutputLimits => plain(Key::K),
+            Action::ShapeConstraint => plain(Key::P),
+            Action::MarkConstraintEdge => plain(Key::E),
+            Action::ApplyShapeConstraint => plain(Key::Return),
+            Action::MasterEditing => plain(Key::M),
+            Action::AddDetailLayer => plain(Key::D),
+            Action::ToggleDetailLayer(n) => shift(DIGITS[n.clamp(1, 9)]),
+            Action::ClearDetailLayers => shift(Key::D),
+            Action::NewGroup => plain(Key::T),
+            Action::NextGroup => plain(Key::Tab),
+            Action::HideGroup => plain(Key::I),
+            Action::LockGroup => plain(Key::Q),
+            Action::Annotate => plain(Key::J),
+            Action::RemoveAnnotation => shift(Key::J),
+            Action::TagPoint => ctrl(Key::T),
+            Action::ClearTags => Binding { key: Key::T, shift: true, ctrl: true },
+            Action::Ruler => plain(Key::U),
+            Action::RgbChannels => plain(Key::V),
+            Action::GradientDesigner => plain(Key::F6),
+            Action::CameraEditor => plain(Key::F7),
+            Action::GlyphSketcher => plain(Key::F8),
+            Action::Tiling => plain(Key::F9),
+            Action::MirrorTiles => shift(Key::F9),
+            Action::Spectrum => plain(Key::F10),
+            Action::Easing => plain(Key::F11),
+            Action::Terrain => plain(Key::Z),
+            Action::Quantize => plain(Key::F),
+            Action::Noise => plain(Key::F12),
+            Action::ReseedNoise => shift(Key::F12),
+            Action::NoiseToOutputs => ctrl(Key::F12),
+            Action::NoiseAmplitudeUp => plain(Key::Period),
+            Action::NoiseAmplitudeDown => plain(Key::Comma),
+            Action::NoiseFrequencyUp => plain(Key::Right),
+            Action::NoiseFrequencyDown => plain(Key::Left),
+            Action::Quit => plain(Key::Escape),
+            Action::LeaveMode => plain(Key::Escape),
+            Action::NextColorSpace => plain(Key::Tab),
+            Action::SetStopColor => plain(Key::Return),
+            Action::DeleteSelected => plain(Key::Delete),
+            Action::PreviewCamera => plain(Key::Space),
+            Action::CloseContour => plain(Key::Return),
+            Action::UndoGlyphPoint => plain(Key::Back),
+            Action::RemoveContour => plain(Key::Delete),
+            Action::UnionContours => ctrl(Key::U),
+            Action::IntersectContours => ctrl(Key::I),
+            Action::SubtractContours => ctrl(Key::D),
+            Action::ApplyBoolean => plain(Key::Tab),
+            Action::NarrowerGlyph => plain(Key::LBracket),
+            Action::WiderGlyph => plain(Key::RBracket),
+        }
+    }
+
+    /// Each mode's own actions work only in it, and a mode's key opens it
+    /// from the canvas and leaves it again.
+    pub fn works_in(&self, scope: Scope) -> bool {
+        match self {
+            Action::CommandPalette | Action::Export => true,
+            Action::Linear | Action::Quadratic | Action::Cubic => scope != Scope::Glyph,
+            Action::LeaveMode => scope != Scope::Canvas,
+            Action::GradientDesigner => matches!(scope, Scope::Canvas | Scope::Gradient),
+            Action::CameraEditor => matches!(scope, Scope::Canvas | Scope::Camera),
+            Action::GlyphSketcher => matches!(scope, Scope::Canvas | Scope::Glyph),
+            // The camera editor raises and lowers the selected handle.
+            Action::Increase | Action::Decrease => matches!(scope, Scope::Canvas | Scope::Camera),
+            Action::NextColorSpace | Action::SetStopColor => scope == Scope::Gradient,
+            Action::DeleteSelected => matches!(scope, Scope::Gradient | Scope::Camera),
+            Action::PreviewCamera => scope == Scope::Camera,
+            Action::CloseContour
+            | Action::UndoGlyphPoint
+            | Action::RemoveContour
+            | Action::UnionContours
+            | Action::IntersectContours
+            | Action::SubtractContours
+            | Action::ApplyBoolean
+            | Action::NarrowerGlyph
+            | Action::WiderGlyph => scope == Scope::Glyph,
+            _ => scope == Scope::Canvas,
+        }
+    }
+
+    /// Whether some scope has both actions, so they can't share a key.
+    pub fn shares_scope(&self, other: Action) -> bool {
+        Scope::ALL.iter().any(|&scope| self.works_in(scope) && other.works_in(scope))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_differ_within_each_scope() {
+        for (i, a) in Action::ALL.iter().enumerate() {
+            for b in &Action::ALL[i + 1..] {
+                assert!(
+                    !a.shares_scope(*b) || a.default_binding() != b.default_binding(),
+                    "{:?} and {:?} share {}",
+                    a,
+                    b,
+                    a.default_binding().name()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::{Point, Spline, SplineError, SplineType};
+
+/// A spline through angles in radians, for heading or phase keyframes.
+///
+/// Consecutive knots are joined along the shorter way round the circle, by
+/// unwrapping each angle to within π of the one before it, so interpolating
+/// from 350° to 10° passes through 0° instead of sweeping back through 180°.
+pub struct AngleSpline {
+    spline: Spline,
+}
+
+impl AngleSpline {
+    /// Panics where [`AngleSpline::try_new`] would return an error.
+    pub fn new(points: &[Point], spline_type: SplineType) -> Self {
+        AngleSpline::try_new(points, spline_type).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fits through `points`, whose y values are angles in any range. A step
+    /// of exactly π between neighbours goes backwards.
+    pub fn try_new(points: &[Point], spline_type: SplineType) -> Result<Self, SplineError> {
+        if let Some(i) = points.iter().position(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return Err(SplineError::NonFinite(i));
+        }
+
+        let mut unwrapped = points.to_vec();
+        unwrapped.sort_by(|a, b| a.x.total_cmp(&b.x));
+        for i in 1..unwrapped.len() {
+            let previous = unwrapped[i - 1].y;
+            unwrapped[i].y = previous + wrap_angle(unwrapped[i].y - previous);
+        }
+
+        Ok(AngleSpline {
+            spline: Spline::try_new(&unwrapped, spline_type)?,
+        })
+    }
+
+    /// The interpolated angle, wrapped into `[-π, π)`.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        wrap_angle(self.spline.evaluate(x))
+    }
+
+    /// The interpolated angle without wrapping, continuous across turns.
+    pub fn evaluate_unwrapped(&self, x: f32) -> f32 {
+        self.spline.evaluate(x)
+    }
+
+    /// The spline through the unwrapped angles.
+    pub fn spline(&self) -> &Spline {
+        &self.spline
+    }
+}
+
+/// Wraps an angle in radians into `[-π, π)`.
+pub fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    // `rem_euclid` may round up to τ itself, which would land on π.
+    if wrapped >= PI {
+        -PI
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_angle_stays_below_pi() {
+        let below = f32::from_bits((-PI).to_bits() + 1);
+        for angle in [below, -PI, PI, 3.0 * PI, -3.0 * PI, 0.0, 1.0, -7.0] {
+            let wrapped = wrap_angle(angle);
+            assert!((-PI..PI).contains(&wrapped), "{} wrapped to {}", angle, wrapped);
+        }
+        assert_eq!(wrap_angle(PI), -PI);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::Point;
+
+/// What an annotation's leader line points at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    /// A control point, by index in ascending x order; the label moves with it.
+    Point(usize),
+    /// A fixed position on the canvas.
+    Position(Point),
+}
+
+/// A text label on the canvas, drawn with a leader line to its anchor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub text: String,
+    pub anchor: Anchor,
+    /// Where the label sits relative to the anchor.
+    pub offset: Point,
+}
+
+impl Annotation {
+    /// Where the anchor is, or `None` for a point index past the end of
+    /// `points`.
+    pub fn anchor_position(&self, points: &[Point]) -> Option<Point> {
+        match self.anchor {
+            Anchor::Point(i) => points.get(i).copied(),
+            Anchor::Position(p) => Some(p),
+        }
+    }
+
+    /// Where the label is, or `None` where the anchor is missing.
+    pub fn label_position(&self, points: &[Point]) -> Option<Point> {
+        self.anchor_position(points)
+            .map(|p| Point::new(p.x + self.offset.x, p.y + self.offset.y))
+    }
+}
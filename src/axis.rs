@@ -0,0 +1,133 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scale {
+    Linear,
+    Log,
+}
+
+impl Scale {
+    /// Maps a value into the space the scale is linear in: `ln` for log.
+    pub fn forward(&self, value: f32) -> f32 {
+        match self {
+            Scale::Linear => value,
+            Scale::Log => value.ln(),
+        }
+    }
+
+    pub fn inverse(&self, value: f32) -> f32 {
+        match self {
+            Scale::Linear => value,
+            Scale::Log => value.exp(),
+        }
+    }
+}
+
+/// A labelled data range, mapped onto the unit interval for drawing.
+#[derive(Clone, Debug)]
+pub struct Axis {
+    pub min: f32,
+    pub max: f32,
+    pub scale: Scale,
+    pub title: Option<String>,
+    pub unit: Option<String>,
+}
+
+impl Axis {
+    pub fn new(min: f32, max: f32) -> Self {
+        Axis {
+            min,
+            max,
+            scale: Scale::Linear,
+            title: None,
+            unit: None,
+        }
+    }
+
+    /// Log scale needs the whole range to be positive.
+    pub fn supports_log(&self) -> bool {
+        self.min > 0.0 && self.max > 0.0
+    }
+
+    /// Where `value` falls along the axis: 0 at `min`, 1 at `max`.
+    pub fn fraction(&self, value: f32) -> f32 {
+        let (lo, hi) = (self.scale.forward(self.min), self.scale.forward(self.max));
+        (self.scale.forward(value) - lo) / (hi - lo)
+    }
+
+    /// The inverse of [`Axis::fraction`].
+    pub fn value_at(&self, fraction: f32) -> f32 {
+        let (lo, hi) = (self.scale.forward(self.min), self.scale.forward(self.max));
+        self.scale.inverse(lo + (hi - lo) * fraction)
+    }
+
+    /// `"title (unit)"`, or whichever of the two is set.
+    pub fn label(&self) -> Option<String> {
+        match (&self.title, &self.unit) {
+            (Some(title), Some(unit)) => Some(format!("{} ({})", title, unit)),
+            (Some(title), None) => Some(title.clone()),
+            (None, Some(unit)) => Some(format!("({})", unit)),
+            (None, None) => None,
+        }
+    }
+
+    /// Tick positions with their labels: steps of 1, 2 or 5 times a power of
+    /// ten giving at most about `target` ticks on a linear axis, and decades
+    /// (with 2 and 5 in between when there are few, and thinned out when
+    /// there are many) on a log axis.
+    pub fn ticks(&self, target: usize) -> Vec<(f32, String)> {
+        let (lo, hi) = (self.min.min(self.max), self.min.max(self.max));
+        if !lo.is_finite() || !hi.is_finite() || lo >= hi {
+            return Vec::new();
+        }
+
+        match self.scale {
+            Scale::Linear => {
+                let step = nice_step((hi - lo) / target.max(1) as f32);
+                let decimals = (-step.log10().floor()).max(0.0) as usize;
+                let first = (lo / step).ceil() as i64;
+                let last = (hi / step).floor() as i64;
+                (first..=last)
+                    .map(|i| {
+                        let value = i as f32 * step;
+                        (value, format!("{:.*}", decimals, value))
+                    })
+                    .collect()
+            }
+            Scale::Log => {
+                let (first, last) = (lo.log10().floor() as i32, hi.log10().ceil() as i32);
+                let mantissas: &[f32] = if last - first <= 3 { &[1.0, 2.0, 5.0] } else { &[1.0] };
+                let stride = ((last - first) as usize).div_ceil(target.max(1)).max(1);
+                (first..=last)
+                    .step_by(stride)
+                    .flat_map(|exponent| mantissas.iter().map(move |&m| (m, exponent)))
+                    .map(|(m, exponent)| (m * 10f32.powi(exponent), m, exponent))
+                    .filter(|&(value, _, _)| value >= lo * (1.0 - 1e-6) && value <= hi * (1.0 + 1e-6))
+                    .map(|(value, m, exponent)| (value, format_power(m, exponent)))
+                    .collect()
+            }
+        }
+    }
+}
+
+fn nice_step(raw: f32) -> f32 {
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn format_power(mantissa: f32, exponent: i32) -> String {
+    if (-3..=4).contains(&exponent) {
+        let value = mantissa * 10f32.powi(exponent);
+        format!("{:.*}", (-exponent).max(0) as usize, value)
+    } else {
+        format!("{}e{}", mantissa, exponent)
+    }
+}
@@ -0,0 +1,174 @@
+use std::cell::Cell;
+
+use crate::{Spline, SplineType};
+
+/// A spline reduced to what evaluation needs, from [`Spline::bake`].
+///
+/// Every piece, including the two that extend the curve beyond its knots,
+/// is stored as a cubic about its own origin, so evaluating never branches
+/// on the spline type. The piece used last is remembered, which makes runs
+/// of increasing or decreasing queries cost a comparison or two each; that
+/// cache is a `Cell`, so a baked spline is not `Sync`.
+#[derive(Clone, Debug)]
+pub struct BakedSpline {
+    // The knot x values; piece `k` applies from `breaks[k - 1]` up to
+    // `breaks[k]`.
+    breaks: Vec<f32>,
+    // Per piece: origin, then a, b, c, d.
+    pieces: Vec<[f32; 5]>,
+    last: Cell<usize>,
+}
+
+impl Spline {
+    /// An evaluator that returns what [`Spline::evaluate`] does (to within
+    /// rounding at the knots) for any x.
+    pub fn bake(&self) -> BakedSpline {
+        let points = self.points();
+        let (first, last) = (points[0], points[points.len() - 1]);
+        let segments: Vec<_> = self.segments().collect();
+        let (start, end) = (segments[0], segments[segments.len() - 1]);
+        // Linear splines carry on along their end slopes; the others hold
+        // their end values.
+        let (slope_before, slope_after) = if self.spline_type() == SplineType::Linear {
+            (start.coefficient(1), end.coefficient(1))
+        } else {
+            (0.0, 0.0)
+        };
+
+        let mut pieces = vec![[first.x, first.y, slope_before, 0.0, 0.0]];
+        pieces.extend(
+            segments
+                .iter()
+                .map(|s| [s.x0, s.coefficient(0), s.coefficient(1), s.coefficient(2), s.coefficient(3)]),
+        );
+        pieces.push([last.x, last.y, slope_after, 0.0, 0.0]);
+        BakedSpline {
+            breaks: points.iter().map(|p| p.x).collect(),
+            pieces,
+            last: Cell::new(1),
+        }
+    }
+}
+
+impl BakedSpline {
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let k = self.piece(x);
+        self.last.set(k);
+        let [x0, a, b, c, d] = self.pieces[k];
+        let dx = x - x0;
+        a + dx * (b + dx * (c + dx * d))
+    }
+
+    // The cached piece or a neighbour when they hold `x`, else a binary
+    // search.
+    fn piece(&self, x: f32) -> usize {
+        let holds = |k: usize| {
+            (k == 0 || self.breaks[k - 1] <= x) && (k == self.breaks.len() || x < self.breaks[k])
+        };
+        let last = self.last.get();
+        for k in [last, last + 1, last.wrapping_sub(1)] {
+            if k <= self.breaks.len() && holds(k) {
+                return k;
+            }
+        }
+        self.breaks.partition_point(|&b| b <= x)
+    }
+
+    /// Rust source for `pub fn <name>(x: f32) -> f32` with the coefficients
+    /// inlined, for firmware with no allocator or for `no_std`.
+    pub fn to_rust(&self, name: &str) -> String {
+        format!(
+            "pub fn {name}(x: f32) -> f32 {{\n    \
+             const BREAKS: [f32; {}] = [{}];\n    \
+             // Origin, then the coefficients of 1, dx, dx², dx³.\n    \
+             const PIECES: [[f32; 5]; {}] = [\n{}    ];\n    \
+             let [x0, a, b, c, d] = PIECES[BREAKS.partition_point(|&b| b <= x)];\n    \
+             let dx = x - x0;\n    \
+             a + dx * (b + dx * (c + dx * d))\n}}\n",
+            self.breaks.len(),
+            literals(&self.breaks, ""),
+            self.pieces.len(),
+            self.pieces
+                .iter()
+                .map(|p| format!("        [{}],\n", literals(p, "")))
+                .collect::<String>(),
+        )
+    }
+
+    /// C source for `float <name>(float x)` with the coefficients inlined.
+    pub fn to_c(&self, name: &str) -> String {
+        format!(
+            "float {name}(float x) {{\n    \
+             static const float BREAKS[{}] = {{{}}};\n    \
+             /* Origin, then the coefficients of 1, dx, dx^2, dx^3. */\n    \
+             static const float PIECES[{}][5] = {{\n{}    }};\n    \
+             int lo = 0, hi = {};\n    \
+             while (lo < hi) {{\n        \
+             int mid = (lo + hi) / 2;\n        \
+             if (BREAKS[mid] <= x) lo = mid + 1; else hi = mid;\n    \
+             }}\n    \
+             const float *p = PIECES[lo];\n    \
+             float dx = x - p[0];\n    \
+             return p[1] + dx * (p[2] + dx * (p[3] + dx * p[4]));\n}}\n",
+            self.breaks.len(),
+            literals(&self.breaks, "f"),
+            self.pieces.len(),
+            self.pieces
+                .iter()
+                .map(|p| format!("        {{{}}},\n", literals(p, "f")))
+                .collect::<String>(),
+            self.breaks.len(),
+        )
+    }
+}
+
+// Debug formatting round-trips an f32 and always has a `.` or exponent, so
+// it is a valid float literal in both languages once C's suffix is added.
+fn literals(values: &[f32], suffix: &str) -> String {
+    values
+        .iter()
+        .map(|v| format!("{:?}{}", v, suffix))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, Rng};
+
+    #[test]
+    fn baked_matches_evaluate_in_any_order() {
+        let points = [
+            Point::new(-1.0, 0.5),
+            Point::new(0.0, 2.0),
+            Point::new(1.5, -1.0),
+            Point::new(2.0, 0.0),
+            Point::new(4.0, 3.0),
+        ];
+        let mut rng = Rng::new(3);
+        for spline_type in [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic] {
+            let spline = Spline::new(&points, spline_type);
+            let baked = spline.bake();
+            let sweep = (0..=140).map(|i| -2.0 + i as f32 * 0.05);
+            let shuffled = (0..140).map(|_| -2.0 + 7.0 * rng.next_f32());
+            for x in sweep.clone().chain(sweep.rev()).chain(shuffled).chain(points.iter().map(|p| p.x)) {
+                let expected = spline.evaluate(x);
+                assert!((baked.evaluate(x) - expected).abs() < 1e-4, "{:?} at {}", spline_type, x);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_source_holds_the_coefficients() {
+        let baked = Spline::new(&[Point::new(0.0, 1.0), Point::new(2.0, 1e-7)], SplineType::Linear).bake();
+        let rust = baked.to_rust("curve");
+        assert!(rust.starts_with("pub fn curve(x: f32) -> f32 {\n"));
+        assert!(rust.contains("const BREAKS: [f32; 2] = [0.0, 2.0];"));
+        assert!(rust.contains("[0.0, 1.0, -0.49999994, 0.0, 0.0],"), "{}", rust);
+        let c = baked.to_c("curve");
+        assert!(c.starts_with("float curve(float x) {\n"));
+        assert!(c.contains("static const float BREAKS[2] = {0.0f, 2.0f};"));
+        assert!(c.contains("{2.0f, 1e-7f, -0.49999994f, 0.0f, 0.0f},"), "{}", c);
+    }
+}
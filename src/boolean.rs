@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::{Contour, Point};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// The first shape with the second cut out of it.
+    Difference,
+}
+
+impl Contour {
+    /// Combines two closed curves, each flattened to within `tolerance`,
+    /// into the polygons bounding the result. Outer boundaries run
+    /// counter-clockwise and holes clockwise.
+    ///
+    /// Edges of each polygon are split where they cross the other, and the
+    /// pieces that lie inside or outside the other (by their midpoints, as
+    /// the operation requires) are joined back into loops. Edges that
+    /// overlap exactly are not handled and may leave a loop open, which is
+    /// then dropped.
+    pub fn boolean(&self, other: &Contour, op: BooleanOp, tolerance: f32) -> Vec<Vec<Point>> {
+        let counter_clockwise = |c: &Contour| {
+            let polygon = c.flatten(tolerance);
+            if polygon_area(&polygon) < 0.0 {
+                polygon.into_iter().rev().collect()
+            } else {
+                polygon
+            }
+        };
+        clip(&counter_clockwise(self), &counter_clockwise(other), op)
+    }
+
+    /// A closed curve through `points` positions spaced evenly by length
+    /// round `polygon`, for turning a boolean result back into something
+    /// editable. Sharp corners are rounded off.
+    ///
+    /// Panics if `points < 3` or the polygon has no length.
+    pub fn from_polygon(polygon: &[Point], points: usize) -> Contour {
+        assert!(points >= 3, "a closed contour needs at least 3 points");
+        let n = polygon.len();
+        let lengths: Vec<f32> = (0..n)
+            .map(|i| distance(polygon[i], polygon[(i + 1) % n]))
+            .collect();
+        let perimeter: f32 = lengths.iter().sum();
+        assert!(perimeter > 0.0, "cannot refit a polygon with no length");
+
+        let mut picked = Vec::with_capacity(points);
+        let (mut edge, mut walked) = (0, 0.0);
+        for k in 0..points {
+            let target = perimeter * k as f32 / points as f32;
+            while edge < n - 1 && walked + lengths[edge] < target {
+                walked += lengths[edge];
+                edge += 1;
+            }
+            let t = if lengths[edge] > 0.0 { ((target - walked) / lengths[edge]).min(1.0) } else { 0.0 };
+            let (a, b) = (polygon[edge], polygon[(edge + 1) % n]);
+            picked.push(Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t));
+        }
+        Contour::new(&picked)
+    }
+}
+
+// A directed polygon edge, start to end.
+type Edge = (Point, Point);
+
+fn clip(a: &[Point], b: &[Point], op: BooleanOp) -> Vec<Vec<Point>> {
+    let (a_pieces, b_pieces) = split_at_crossings(a, b);
+    let mut edges: Vec<Edge> = Vec::new();
+    for (p, q) in a_pieces {
+        let inside = inside_polygon(b, midpoint(p, q));
+        if inside == (op == BooleanOp::Intersection) {
+            edges.push((p, q));
+        }
+    }
+    for (p, q) in b_pieces {
+        let inside = inside_polygon(a, midpoint(p, q));
+        match op {
+            BooleanOp::Union if !inside => edges.push((p, q)),
+            BooleanOp::Intersection if inside => edges.push((p, q)),
+            BooleanOp::Difference if inside => edges.push((q, p)),
+            _ => {}
+        }
+    }
+    link(edges)
+}
+
+// Each polygon's edges cut at every crossing with the other's. A crossing
+// is computed once and shared, so the pieces meet at bit-identical points.
+fn split_at_crossings(a: &[Point], b: &[Point]) -> (Vec<Edge>, Vec<Edge>) {
+    let mut a_cuts: Vec<Vec<(f32, Point)>> = vec![Vec::new(); a.len()];
+    let mut b_cuts: Vec<Vec<(f32, Point)>> = vec![Vec::new(); b.len()];
+    for i in 0..a.len() {
+        let (p, p2) = (a[i], a[(i + 1) % a.len()]);
+        for j in 0..b.len() {
+            let (q, q2) = (b[j], b[(j + 1) % b.len()]);
+            if let Some((t, u)) = crossing(p, p2, q, q2) {
+                // On a vertex of `b` use the vertex itself, so the pieces
+                // either side of it still meet.
+                let at = if u == 0.0 { q } else { Point::new(p.x + (p2.x - p.x) * t, p.y + (p2.y - p.y) * t) };
+                a_cuts[i].push((t, at));
+                b_cuts[j].push((u, at));
+            }
+        }
+    }
+    (pieces(a, a_cuts), pieces(b, b_cuts))
+}
+
+fn pieces(polygon: &[Point], mut cuts: Vec<Vec<(f32, Point)>>) -> Vec<Edge> {
+    let mut out = Vec::new();
+    for (i, cuts) in cuts.iter_mut().enumerate() {
+        cuts.sort_by(|l, r| l.0.total_cmp(&r.0));
+        let mut start = polygon[i];
+        for &(_, at) in cuts.iter() {
+            out.push((start, at));
+            start = at;
+        }
+        out.push((start, polygon[(i + 1) % polygon.len()]));
+    }
+    out.retain(|(p, q)| p.x != q.x || p.y != q.y);
+    out
+}
+
+// Where segments p–p2 and q–q2 cross, as fractions along each; the end of
+// each segment is excluded so a crossing at a vertex is found only once.
+fn crossing(p: Point, p2: Point, q: Point, q2: Point) -> Option<(f32, f32)> {
+    let (r, s) = ((p2.x - p.x, p2.y - p.y), (q2.x - q.x, q2.y - q.y));
+    let denominator = r.0 * s.1 - r.1 * s.0;
+    if denominator == 0.0 {
+        return None;
+    }
+    let (dx, dy) = (q.x - p.x, q.y - p.y);
+    let t = (dx * s.1 - dy * s.0) / denominator;
+    let u = (dx * r.1 - dy * r.0) / denominator;
+    ((0.0..1.0).contains(&t) && (0.0..1.0).contains(&u)).then_some((t, u))
+}
+
+// Joins directed edges end to start into closed loops.
+fn link(edges: Vec<Edge>) -> Vec<Vec<Point>> {
+    let key = |p: Point| (p.x.to_bits(), p.y.to_bits());
+    let mut from: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, (p, _)) in edges.iter().enumerate() {
+        from.entry(key(*p)).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut loops = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        let mut polygon = vec![edges[first].0];
+        let mut current = first;
+        let closed = loop {
+            used[current] = true;
+            let end = edges[current].1;
+            if key(end) == key(edges[first].0) {
+                break true;
+            }
+            polygon.push(end);
+            match from.get(&key(end)).and_then(|next| next.iter().copied().find(|&k| !used[k])) {
+                Some(next) => current = next,
+                None => break false,
+            }
+        };
+        if closed && polygon.len() >= 3 {
+            loops.push(polygon);
+        }
+    }
+    loops
+}
+
+/// Whether `p` is inside `polygon`, by the even-odd rule.
+pub fn inside_polygon(polygon: &[Point], p: Point) -> bool {
+    let mut inside = false;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Shoelace area, positive for counter-clockwise polygons.
+pub fn polygon_area(polygon: &[Point]) -> f32 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x * b.y - a.y * b.x
+        })
+        .sum::<f32>()
+        / 2.0
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f32, f32)]) -> Vec<Point> {
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    fn area(loops: &[Vec<Point>]) -> f32 {
+        loops.iter().map(|l| polygon_area(l)).sum()
+    }
+
+    #[test]
+    fn area_and_inside() {
+        let square = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(polygon_area(&square), 4.0);
+        let reversed: Vec<Point> = square.iter().rev().copied().collect();
+        assert_eq!(polygon_area(&reversed), -4.0);
+        assert!(inside_polygon(&square, Point::new(1.0, 1.0)));
+        assert!(!inside_polygon(&square, Point::new(3.0, 1.0)));
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let b = polygon(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)]);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Union)), 7.0);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Intersection)), 1.0);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Difference)), 3.0);
+    }
+
+    #[test]
+    fn crossing_through_a_vertex() {
+        // The triangle's first edge runs through the square's corner (2, 2).
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let b = polygon(&[(1.0, 1.0), (3.0, 1.0), (3.0, 3.0)]);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Union)), 5.5);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Intersection)), 0.5);
+        assert_eq!(area(&clip(&a, &b, BooleanOp::Difference)), 3.5);
+        // The same with the roles swapped, so the vertex is on `b`.
+        assert_eq!(area(&clip(&b, &a, BooleanOp::Union)), 5.5);
+        assert_eq!(area(&clip(&b, &a, BooleanOp::Intersection)), 0.5);
+        assert_eq!(area(&clip(&b, &a, BooleanOp::Difference)), 1.5);
+    }
+
+    #[test]
+    fn shared_vertex() {
+        let a = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let b = polygon(&[(2.0, 2.0), (0.5, 1.0), (3.0, -1.0)]);
+        let union = clip(&a, &b, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 5.125).abs() < 1e-5);
+        assert!((area(&clip(&a, &b, BooleanOp::Intersection)) - 1.625).abs() < 1e-5);
+        assert!((area(&clip(&a, &b, BooleanOp::Difference)) - 2.375).abs() < 1e-5);
+    }
+
+    #[test]
+    fn from_polygon_spaces_points_evenly() {
+        let square = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        let contour = Contour::from_polygon(&square, 8);
+        let expected = polygon(&[
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (2.0, 2.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+        ]);
+        assert_eq!(contour.points(), &expected[..]);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Point, Spline, SplineError, SplineType};
+
+/// Where the camera is and what it looks at, at one moment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub position: [f32; 3],
+    pub target: [f32; 3],
+}
+
+/// A camera flight through keyframes: a parametric spline over time for the
+/// position and another for the look-at target, sharing the keyframe times
+/// so the two stay in step.
+pub struct CameraPath {
+    position: [Spline; 3],
+    target: [Spline; 3],
+}
+
+impl CameraPath {
+    /// Panics where [`CameraPath::try_new`] would return an error.
+    pub fn new(keyframes: &[Keyframe], spline_type: SplineType) -> Self {
+        CameraPath::try_new(keyframes, spline_type).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fails as [`Spline::try_new`] does on the keyframe times.
+    pub fn try_new(keyframes: &[Keyframe], spline_type: SplineType) -> Result<Self, SplineError> {
+        let track = |k: usize, target: bool| {
+            let points: Vec<Point> = keyframes
+                .iter()
+                .map(|f| Point::new(f.time, if target { f.target[k] } else { f.position[k] }))
+                .collect();
+            Spline::try_new(&points, spline_type)
+        };
+        Ok(CameraPath {
+            position: [track(0, false)?, track(1, false)?, track(2, false)?],
+            target: [track(0, true)?, track(1, true)?, track(2, true)?],
+        })
+    }
+
+    /// The first and last keyframe times.
+    pub fn duration(&self) -> (f32, f32) {
+        self.position[0].domain()
+    }
+
+    pub fn position(&self, time: f32) -> [f32; 3] {
+        [0, 1, 2].map(|k| self.position[k].evaluate(time))
+    }
+
+    pub fn target(&self, time: f32) -> [f32; 3] {
+        [0, 1, 2].map(|k| self.target[k].evaluate(time))
+    }
+
+    /// The keyframes back, with times from the splines' knots.
+    pub fn keyframes(&self) -> Vec<Keyframe> {
+        (0..self.position[0].points().len())
+            .map(|i| Keyframe {
+                time: self.position[0].points()[i].x,
+                position: [0, 1, 2].map(|k| self.position[k].points()[i].y),
+                target: [0, 1, 2].map(|k| self.target[k].points()[i].y),
+            })
+            .collect()
+    }
+
+    /// The flight sampled at `fps` frames per second from the first
+    /// keyframe to the last, as JSON with the keyframes alongside:
+    ///
+    /// `{"fps":30,"keyframes":[{"time":0,"position":[x,y,z],"target":[x,y,z]},…],"frames":[…]}`
+    ///
+    /// Frames carry the same fields, so engines that key every frame and
+    /// engines that interpolate their own curves can both read it.
+    pub fn to_json(&self, fps: f32) -> String {
+        let (start, end) = self.duration();
+        let count = ((end - start) * fps).floor() as usize + 1;
+        let frames: Vec<Keyframe> = (0..count)
+            .map(|i| {
+                let time = (start + i as f32 / fps).min(end);
+                Keyframe {
+                    time,
+                    position: self.position(time),
+                    target: self.target(time),
+                }
+            })
+            .collect();
+        let list = |frames: &[Keyframe]| frames.iter().map(frame_json).collect::<Vec<_>>().join(",");
+        format!(
+            "{{\"fps\":{},\"keyframes\":[{}],\"frames\":[{}]}}",
+            fps,
+            list(&self.keyframes()),
+            list(&frames)
+        )
+    }
+}
+
+fn frame_json(frame: &Keyframe) -> String {
+    let vector = |v: [f32; 3]| format!("[{},{},{}]", v[0], v[1], v[2]);
+    format!(
+        "{{\"time\":{},\"position\":{},\"target\":{}}}",
+        frame.time,
+        vector(frame.position),
+        vector(frame.target)
+    )
+}
@@ -0,0 +1,149 @@
+//! C bindings for building and evaluating splines, enabled by the `capi`
+//! feature. `cargo build --release --no-default-features --features capi`
+//! builds the shared library into `target/release`. Their declarations are
+//! in `include/splines.h`; after changing them, regenerate it with
+//! `cbindgen --config cbindgen.toml --output include/splines.h`.
+
+use std::ptr;
+use std::slice;
+
+use crate::{Point, Spline, SplineType};
+
+pub const SPLINE_LINEAR: u32 = 0;
+pub const SPLINE_QUADRATIC: u32 = 1;
+pub const SPLINE_CUBIC: u32 = 2;
+
+/// Fits a spline through `len` points given as parallel `xs` and `ys`
+/// arrays, in any order. `spline_type` is one of `SPLINE_LINEAR`,
+/// `SPLINE_QUADRATIC` or `SPLINE_CUBIC`.
+///
+/// Returns NULL for an unknown type, NULL arrays, fewer than 2 points,
+/// repeated x values or NaN/infinite coordinates. A non-NULL result must be
+/// released with `spline_destroy`.
+///
+/// # Safety
+///
+/// `xs` and `ys` must each point to `len` readable floats.
+#[no_mangle]
+pub unsafe extern "C" fn spline_create(xs: *const f32, ys: *const f32, len: usize, spline_type: u32) -> *mut Spline {
+    let spline_type = match spline_type {
+        SPLINE_LINEAR => SplineType::Linear,
+        SPLINE_QUADRATIC => SplineType::Quadratic,
+        SPLINE_CUBIC => SplineType::Cubic,
+        _ => return ptr::null_mut(),
+    };
+    if xs.is_null() || ys.is_null() {
+        return ptr::null_mut();
+    }
+
+    let (xs, ys) = (slice::from_raw_parts(xs, len), slice::from_raw_parts(ys, len));
+    let points: Vec<Point> = xs.iter().zip(ys).map(|(&x, &y)| Point::new(x, y)).collect();
+    match Spline::try_new(&points, spline_type) {
+        Ok(spline) => Box::into_raw(Box::new(spline)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Evaluates the spline at `x`, or returns NaN if `spline` is NULL.
+///
+/// # Safety
+///
+/// `spline` must be NULL or a pointer returned by `spline_create` that has
+/// not been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn spline_evaluate(spline: *const Spline, x: f32) -> f32 {
+    match spline.as_ref() {
+        Some(spline) => spline.evaluate(x),
+        None => f32::NAN,
+    }
+}
+
+/// Evaluates the spline at each of the `len` values in `xs`, writing the
+/// results to `out`. Ascending `xs` are fastest. Does nothing if any pointer
+/// is NULL.
+///
+/// # Safety
+///
+/// `spline` must be NULL or a live pointer from `spline_create`; `xs` must
+/// point to `len` readable floats and `out` to `len` writable floats that
+/// don't overlap `xs`.
+#[no_mangle]
+pub unsafe extern "C" fn spline_evaluate_many(spline: *const Spline, xs: *const f32, out: *mut f32, len: usize) {
+    let spline = match spline.as_ref() {
+        Some(spline) => spline,
+        None => return,
+    };
+    if xs.is_null() || out.is_null() {
+        return;
+    }
+    spline.evaluate_many(slice::from_raw_parts(xs, len), slice::from_raw_parts_mut(out, len));
+}
+
+/// Releases a spline. Passing NULL is a no-op.
+///
+/// # Safety
+///
+/// `spline` must be NULL or a pointer from `spline_create` that has not
+/// already been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn spline_destroy(spline: *mut Spline) {
+    if !spline.is_null() {
+        drop(Box::from_raw(spline));
+    }
+}
+
+#[cfg(all(test, feature = "capi"))]
+mod tests {
+    use super::*;
+
+    const XS: [f32; 4] = [2.0, 0.0, 1.0, 3.0];
+    const YS: [f32; 4] = [4.0, 0.0, 1.0, 9.0];
+
+    #[test]
+    fn create_evaluate_and_destroy() {
+        for spline_type in [SPLINE_LINEAR, SPLINE_QUADRATIC, SPLINE_CUBIC] {
+            unsafe {
+                let spline = spline_create(XS.as_ptr(), YS.as_ptr(), XS.len(), spline_type);
+                assert!(!spline.is_null());
+                for (&x, &y) in XS.iter().zip(&YS) {
+                    assert!((spline_evaluate(spline, x) - y).abs() < 1e-5);
+                }
+
+                let xs = [0.0, 0.5, 1.5, 2.25, 3.0];
+                let mut out = [0.0; 5];
+                spline_evaluate_many(spline, xs.as_ptr(), out.as_mut_ptr(), xs.len());
+                for (&x, &y) in xs.iter().zip(&out) {
+                    assert_eq!(y, spline_evaluate(spline, x));
+                }
+                spline_destroy(spline);
+            }
+        }
+    }
+
+    #[test]
+    fn unusable_input_gives_null() {
+        let nan_ys = [0.0, f32::NAN, 1.0];
+        let repeated_xs = [0.0, 1.0, 1.0];
+        unsafe {
+            assert!(spline_create(ptr::null(), YS.as_ptr(), 4, SPLINE_CUBIC).is_null());
+            assert!(spline_create(XS.as_ptr(), ptr::null(), 4, SPLINE_CUBIC).is_null());
+            assert!(spline_create(XS.as_ptr(), YS.as_ptr(), 1, SPLINE_CUBIC).is_null());
+            assert!(spline_create(XS.as_ptr(), YS.as_ptr(), 0, SPLINE_CUBIC).is_null());
+            assert!(spline_create(XS.as_ptr(), YS.as_ptr(), 4, 3).is_null());
+            assert!(spline_create(XS.as_ptr(), nan_ys.as_ptr(), 3, SPLINE_LINEAR).is_null());
+            assert!(spline_create(repeated_xs.as_ptr(), YS.as_ptr(), 3, SPLINE_LINEAR).is_null());
+        }
+    }
+
+    #[test]
+    fn null_spline_is_harmless() {
+        let xs = [1.0];
+        let mut out = [7.0];
+        unsafe {
+            assert!(spline_evaluate(ptr::null(), 1.0).is_nan());
+            spline_evaluate_many(ptr::null(), xs.as_ptr(), out.as_mut_ptr(), 1);
+            spline_destroy(ptr::null_mut());
+        }
+        assert_eq!(out, [7.0]);
+    }
+}
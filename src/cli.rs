@@ -0,0 +1,196 @@
+use std::process;
+
+const USAGE: &str = "\
+usage: splines [options]
+
+options:
+  --led-serial <path>      stream the curve to an Adalight/WLED serial device
+  --led-sacn <host>        stream the curve over sACN (E1.31); `multicast` for the universe group
+  --led-count <n>          number of LEDs to sample the curve at (default 60)
+  --sacn-universe <n>      sACN universe (default 1)
+  --data <file.csv>        load a dense x, y dataset and interpolate a subset of it; reloaded when the file changes
+  --fit-tolerance <y>      largest error, in data units, the adaptive fit (A) accepts (default 0.01)
+  --seed <n>               start from the random points generated by this seed
+  --share <string>         start from a shared design (`spline:...`, as copied with Ctrl+C)
+  --session <file>         session file Ctrl+S saves to, loaded at startup if it exists (default session.spline)
+  --config <file>          TOML settings such as key bindings, read at startup if it exists (default splines.toml)
+  --interpolator <name>    interpolation method to start with: linear, quadratic, cubic, polynomial
+                           or one a plugin registers (default cubic)
+  --compare <a> <b>        overlay two saved sessions and plot their difference
+  --serve <address>        accept WebSocket clients at e.g. 127.0.0.1:9001 that set and follow the curve as JSON
+  --x-range <min>,<max>    data range the canvas spans horizontally (default 0,1); likewise --y-range (default -1,1)
+  --x-title <text>         horizontal axis title; likewise --y-title
+  --x-unit <text>          horizontal axis unit, e.g. `s` or `dB`; likewise --y-unit
+  --x-log                  logarithmic horizontal axis; likewise --y-log
+  --limits <min>,<max>     output limits K applies, in y data units; either side may be empty,
+                           or `non-negative` / `unit` (default unit, i.e. 0,1)
+  --max-grade <percent>    steepest grade terrain mode (Z) allows before flagging a segment (default 8)
+  --random-points <n>      number of points Shift+R generates (default 8)
+  --sample-points <n>      number of points presets and loaded data are sampled at (default 12)
+  -h, --help               print this message";
+
+pub struct AxisOptions {
+    pub range: (f32, f32),
+    pub title: Option<String>,
+    pub unit: Option<String>,
+    pub log: bool,
+}
+
+impl AxisOptions {
+    fn new(range: (f32, f32)) -> Self {
+        AxisOptions {
+            range,
+            title: None,
+            unit: None,
+            log: false,
+        }
+    }
+}
+
+pub struct Options {
+    pub led_serial: Option<String>,
+    pub led_sacn: Option<String>,
+    pub led_count: usize,
+    pub sacn_universe: u16,
+    pub data: Option<String>,
+    pub fit_tolerance: f32,
+    pub seed: Option<u64>,
+    pub share: Option<String>,
+    pub session: String,
+    pub config: String,
+    pub interpolator: Option<String>,
+    pub compare: Option<(String, String)>,
+    pub serve: Option<String>,
+    pub random_points: usize,
+    pub sample_points: usize,
+    pub x_axis: AxisOptions,
+    pub y_axis: AxisOptions,
+    pub limits: (Option<f32>, Option<f32>),
+    pub max_grade: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            led_serial: None,
+            led_sacn: None,
+            led_count: 60,
+            sacn_universe: 1,
+            data: None,
+            fit_tolerance: 0.01,
+            seed: None,
+            share: None,
+            session: "session.spline".to_string(),
+            config: "splines.toml".to_string(),
+            interpolator: None,
+            compare: None,
+            serve: None,
+            random_points: 8,
+            sample_points: 12,
+            x_axis: AxisOptions::new((0.0, 1.0)),
+            y_axis: AxisOptions::new((-1.0, 1.0)),
+            limits: (Some(0.0), Some(1.0)),
+            max_grade: 8.0,
+        }
+    }
+}
+
+impl Options {
+    pub fn from_args() -> Self {
+        let mut options = Options::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--led-serial" => options.led_serial = Some(value(&mut args, &arg)),
+                "--led-sacn" => options.led_sacn = Some(value(&mut args, &arg)),
+                "--led-count" => options.led_count = parse(&value(&mut args, &arg), &arg),
+                "--sacn-universe" => options.sacn_universe = parse(&value(&mut args, &arg), &arg),
+                "--data" => options.data = Some(value(&mut args, &arg)),
+                "--fit-tolerance" => options.fit_tolerance = parse(&value(&mut args, &arg), &arg),
+                "--seed" => options.seed = Some(parse(&value(&mut args, &arg), &arg)),
+                "--share" => options.share = Some(value(&mut args, &arg)),
+                "--session" => options.session = value(&mut args, &arg),
+                "--config" => options.config = value(&mut args, &arg),
+                "--interpolator" => options.interpolator = Some(value(&mut args, &arg)),
+                "--compare" => {
+                    let a = value(&mut args, &arg);
+                    options.compare = Some((a, value(&mut args, &arg)));
+                }
+                "--serve" => options.serve = Some(value(&mut args, &arg)),
+                "--random-points" => options.random_points = parse(&value(&mut args, &arg), &arg),
+                "--sample-points" => options.sample_points = parse(&value(&mut args, &arg), &arg),
+                "--x-range" => options.x_axis.range = parse_range(&value(&mut args, &arg), &arg),
+                "--y-range" => options.y_axis.range = parse_range(&value(&mut args, &arg), &arg),
+                "--x-title" => options.x_axis.title = Some(value(&mut args, &arg)),
+                "--y-title" => options.y_axis.title = Some(value(&mut args, &arg)),
+                "--x-unit" => options.x_axis.unit = Some(value(&mut args, &arg)),
+                "--y-unit" => options.y_axis.unit = Some(value(&mut args, &arg)),
+                "--x-log" => options.x_axis.log = true,
+                "--y-log" => options.y_axis.log = true,
+                "--limits" => options.limits = parse_limits(&value(&mut args, &arg), &arg),
+                "--max-grade" => options.max_grade = parse(&value(&mut args, &arg), &arg),
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument `{}`", arg)),
+            }
+        }
+
+        options
+    }
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> String {
+    args.next()
+        .unwrap_or_else(|| usage_error(&format!("`{}` expects a value", flag)))
+}
+
+fn parse<T: std::str::FromStr>(value: &str, flag: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| usage_error(&format!("invalid value `{}` for `{}`", value, flag)))
+}
+
+fn parse_range(value: &str, flag: &str) -> (f32, f32) {
+    let (min, max) = value
+        .split_once(',')
+        .unwrap_or_else(|| usage_error(&format!("`{}` expects <min>,<max>", flag)));
+    let range = (parse(min.trim(), flag), parse(max.trim(), flag));
+    if range.0 == range.1 {
+        usage_error(&format!("`{}` needs two different values", flag));
+    }
+    range
+}
+
+fn parse_limits(value: &str, flag: &str) -> (Option<f32>, Option<f32>) {
+    let limits = match value {
+        "non-negative" => (Some(0.0), None),
+        "unit" => (Some(0.0), Some(1.0)),
+        _ => {
+            let (min, max) = value
+                .split_once(',')
+                .unwrap_or_else(|| usage_error(&format!("`{}` expects <min>,<max>, `non-negative` or `unit`", flag)));
+            let bound = |text: &str| {
+                let text = text.trim();
+                (!text.is_empty()).then(|| parse::<f32>(text, flag))
+            };
+            (bound(min), bound(max))
+        }
+    };
+    if limits.0.is_some_and(f32::is_nan) || limits.1.is_some_and(f32::is_nan) {
+        usage_error(&format!("invalid value `{}` for `{}`", value, flag));
+    }
+    if let (Some(min), Some(max)) = limits {
+        if min > max {
+            usage_error(&format!("`{}` has its minimum above its maximum", flag));
+        }
+    }
+    limits
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
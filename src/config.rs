@@ -0,0 +1,247 @@
+use std::fs;
+use std::path::Path;
+
+use nannou::prelude::Key;
+
+use crate::action::{Action, Scope};
+
+// The keys a binding can name, matched by nannou's name for them.
+#[rustfmt::skip]
+const NAMED_KEYS: &[Key] = &[
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8,
+    Key::Key9, Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10,
+    Key::F11, Key::F12, Key::Tab, Key::Left, Key::Right, Key::Up, Key::Down, Key::Return, Key::Escape,
+    Key::Space, Key::Back, Key::Delete, Key::Insert, Key::Home, Key::End, Key::PageUp, Key::PageDown,
+    Key::Comma, Key::Period, Key::Slash, Key::Minus, Key::Equals, Key::LBracket, Key::RBracket,
+    Key::Semicolon, Key::Apostrophe, Key::Backslash, Key::Grave,
+];
+
+/// A key and the modifiers held with it, written as e.g. `"Tab"`,
+/// `"Shift+Tab"` or `"Ctrl+Shift+T"` using nannou's key names (`A`, `Key1`,
+/// `F2`, `Left`, ...). Ctrl also stands for Cmd.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Binding {
+    pub key: Key,
+    pub shift: bool,
+    pub ctrl: bool,
+}
+
+impl Binding {
+    pub const fn new(key: Key) -> Self {
+        Binding {
+            key,
+            shift: false,
+            ctrl: false,
+        }
+    }
+
+    fn parse(text: &str) -> Option<Binding> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let name = parts.pop()?;
+        let key = NAMED_KEYS
+            .iter()
+            .copied()
+            .find(|k| format!("{:?}", k).eq_ignore_ascii_case(name))?;
+        let mut binding = Binding::new(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "shift" => binding.shift = true,
+                "ctrl" | "cmd" => binding.ctrl = true,
+                _ => return None,
+            }
+        }
+        Some(binding)
+    }
+
+    pub fn matches(&self, key: Key, shift: bool, ctrl: bool) -> bool {
+        self.key == key && self.shift == shift && self.ctrl == ctrl
+    }
+
+    pub fn name(&self) -> String {
+        format!(
+            "{}{}{:?}",
+            if self.ctrl { "Ctrl+" } else { "" },
+            if self.shift { "Shift+" } else { "" },
+            self.key
+        )
+    }
+}
+
+/// The keys of keyboard editing mode, so points can be added, picked,
+/// moved and deleted without a mouse.
+pub struct KeyboardBindings {
+    pub next: Binding,
+    pub previous: Binding,
+    /// Adds a point after the focused one and focuses it.
+    pub add: Binding,
+    pub delete: Binding,
+    pub left: Binding,
+    pub right: Binding,
+    pub up: Binding,
+    pub down: Binding,
+    /// Pixels a move key carries the focused point; 5 times as far with
+    /// Shift, for moves bound without it.
+    pub step: f32,
+}
+
+impl Default for KeyboardBindings {
+    fn default() -> Self {
+        KeyboardBindings {
+            next: Binding::new(Key::Tab),
+            previous: Binding {
+                shift: true,
+                ..Binding::new(Key::Tab)
+            },
+            add: Binding::new(Key::A),
+            delete: Binding::new(Key::Delete),
+            left: Binding::new(Key::Left),
+            right: Binding::new(Key::Right),
+            up: Binding::new(Key::Up),
+            down: Binding::new(Key::Down),
+            step: 5.0,
+        }
+    }
+}
+
+/// Settings read at startup from a TOML file, each one optional:
+///
+/// ```toml
+/// language = "de"      # the code of a locale file, shown from the start
+/// locales = "locales"  # where locale files are read from
+///
+/// [shortcuts]          # by action, as listed in the command palette
+/// save_session = "Ctrl+S"
+/// keyboard_editing = "Insert"
+/// quit = ""            # unbound
+///
+/// [keyboard]           # in keyboard editing mode
+/// next = "Tab"
+/// previous = "Shift+Tab"
+/// add = "A"
+/// delete = "Delete"
+/// left = "Left"   # likewise right, up and down
+/// step = 5.0
+/// ```
+///
+/// `keyboard.toggle`, from before keyboard editing was an action, still
+/// works as another name for `shortcuts.keyboard_editing`.
+pub struct Config {
+    pub language: Option<String>,
+    pub locales: String,
+    shortcuts: Vec<(Action, Binding)>,
+    pub keyboard: KeyboardBindings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            language: None,
+            locales: "locales".to_string(),
+            shortcuts: Action::ALL.iter().map(|&a| (a, a.default_binding())).collect(),
+            keyboard: KeyboardBindings::default(),
+        }
+    }
+}
+
+impl Config {
+    /// The defaults when there is no file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let mut config = Config::default();
+        let text_setting = |name: &str| match table.get(name) {
+            Some(value) => match value.as_str() {
+                Some(text) => Ok(Some(text.to_string())),
+                None => Err(format!("`{}` should be a string", name)),
+            },
+            None => Ok(None),
+        };
+        config.language = text_setting("language")?;
+        if let Some(locales) = text_setting("locales")? {
+            config.locales = locales;
+        }
+        if let Some(shortcuts) = table.get("shortcuts") {
+            let shortcuts = shortcuts.as_table().ok_or("`shortcuts` should be a table")?;
+            for (name, value) in shortcuts {
+                let action = Action::from_id(name).ok_or_else(|| format!("unknown action `shortcuts.{}`", name))?;
+                config.bind(action, &format!("shortcuts.{}", name), value)?;
+            }
+        }
+        if let Some(keyboard) = table.get("keyboard") {
+            let keyboard = keyboard.as_table().ok_or("`keyboard` should be a table")?;
+            for (name, value) in keyboard {
+                let bindings = &mut config.keyboard;
+                let binding = match name.as_str() {
+                    "next" => &mut bindings.next,
+                    "previous" => &mut bindings.previous,
+                    "add" => &mut bindings.add,
+                    "delete" => &mut bindings.delete,
+                    "left" => &mut bindings.left,
+                    "right" => &mut bindings.right,
+                    "up" => &mut bindings.up,
+                    "down" => &mut bindings.down,
+                    // The old name, which `shortcuts.keyboard_editing` overrides.
+                    "toggle" => {
+                        let renamed = table.get("shortcuts").and_then(|s| s.get("keyboard_editing"));
+                        if renamed.is_none() {
+                            config.bind(Action::KeyboardEditing, "keyboard.toggle", value)?;
+                        }
+                        continue;
+                    }
+                    "step" => {
+                        bindings.step = value
+                            .as_float()
+                            .or_else(|| value.as_integer().map(|n| n as f64))
+                            .filter(|&step| step > 0.0)
+                            .ok_or("`keyboard.step` should be a positive number")?
+                            as f32;
+                        continue;
+                    }
+                    _ => return Err(format!("unknown setting `keyboard.{}`", name)),
+                };
+                *binding = value
+                    .as_str()
+                    .and_then(Binding::parse)
+                    .ok_or_else(|| format!("`keyboard.{}` should name a key, like \"Tab\" or \"Shift+Tab\"", name))?;
+            }
+        }
+        Ok(config)
+    }
+
+    // Takes the key from any action that needs a different one.
+    fn bind(&mut self, action: Action, name: &str, value: &toml::Value) -> Result<(), String> {
+        let binding = match value.as_str() {
+            Some("") => None,
+            Some(text) => Some(
+                Binding::parse(text)
+                    .ok_or_else(|| format!("`{}` should name a key, like \"Ctrl+S\", or be \"\"", name))?,
+            ),
+            None => return Err(format!("`{}` should be a string", name)),
+        };
+        self.shortcuts
+            .retain(|&(a, b)| a != action && !(Some(b) == binding && a.shares_scope(action)));
+        if let Some(binding) = binding {
+            self.shortcuts.push((action, binding));
+        }
+        Ok(())
+    }
+
+    /// The action a key runs in `scope`, with Cmd counted as Ctrl.
+    pub fn action_for(&self, scope: Scope, key: Key, shift: bool, ctrl: bool) -> Option<Action> {
+        self.shortcuts
+            .iter()
+            .find(|(action, binding)| action.works_in(scope) && binding.matches(key, shift, ctrl))
+            .map(|&(action, _)| action)
+    }
+
+    pub fn shortcut(&self, action: Action) -> Option<Binding> {
+        self.shortcuts.iter().find(|&&(a, _)| a == action).map(|&(_, binding)| binding)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::Point;
+
+/// A closed parametric curve through points in order: a uniform Catmull–Rom
+/// spline that wraps from the last point back to the first, so it is smooth
+/// all the way round.
+///
+/// Piece `i` runs from point `i` to point `i + 1` as the parameter goes
+/// from `i` to `i + 1`.
+#[derive(Clone, Debug)]
+pub struct Contour {
+    points: Vec<Point>,
+}
+
+impl Contour {
+    /// Panics if there are fewer than 3 points.
+    pub fn new(points: &[Point]) -> Self {
+        assert!(points.len() >= 3, "a closed contour needs at least 3 points");
+        Contour {
+            points: points.to_vec(),
+        }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// The same curve traced the other way round.
+    pub fn reversed(&self) -> Contour {
+        Contour {
+            points: self.points.iter().rev().copied().collect(),
+        }
+    }
+
+    /// Each piece as a cubic Bézier: start, two control points, end.
+    pub fn beziers(&self) -> Vec<[Point; 4]> {
+        let n = self.points.len();
+        let p = |i: usize| self.points[i % n];
+        (0..n)
+            .map(|i| {
+                let (before, start, end, after) = (p(i + n - 1), p(i), p(i + 1), p(i + 2));
+                [
+                    start,
+                    Point::new(start.x + (end.x - before.x) / 6.0, start.y + (end.y - before.y) / 6.0),
+                    Point::new(end.x - (after.x - start.x) / 6.0, end.y - (after.y - start.y) / 6.0),
+                    end,
+                ]
+            })
+            .collect()
+    }
+
+    /// The point at parameter `t`, which wraps every `points().len()`.
+    pub fn evaluate(&self, t: f32) -> Point {
+        let n = self.points.len();
+        let t = t.rem_euclid(n as f32);
+        let i = (t.floor() as usize).min(n - 1);
+        bezier_point(&self.beziers()[i], t - i as f32)
+    }
+
+    /// A polygon within `tolerance` of the curve, each piece split in half
+    /// until its control points lie that close to its chord. The first point
+    /// is not repeated at the end.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Point> {
+        let mut polygon = vec![self.points[0]];
+        for bezier in self.beziers() {
+            flatten_bezier(bezier, tolerance.max(1e-6), 0, &mut polygon);
+        }
+        polygon.pop();
+        polygon
+    }
+
+    /// The enclosed area, positive when the curve runs counter-clockwise
+    /// (y up), exact for the Bézier pieces.
+    pub fn signed_area(&self) -> f32 {
+        // Green's theorem on each cubic: the area term of a Bézier in closed
+        // form from its control points.
+        self.beziers()
+            .iter()
+            .map(|[p0, p1, p2, p3]| {
+                let cross = |a: &Point, b: &Point| a.x * b.y - a.y * b.x;
+                let sum = 6.0 * cross(p0, p1) + 3.0 * cross(p0, p2) + cross(p0, p3)
+                    + 3.0 * cross(p1, p2)
+                    + 3.0 * cross(p1, p3)
+                    + 6.0 * cross(p2, p3);
+                sum / 20.0
+            })
+            .sum()
+    }
+}
+
+fn bezier_point(b: &[Point; 4], t: f32) -> Point {
+    let s = 1.0 - t;
+    let (w0, w1, w2, w3) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+    Point::new(
+        w0 * b[0].x + w1 * b[1].x + w2 * b[2].x + w3 * b[3].x,
+        w0 * b[0].y + w1 * b[1].y + w2 * b[2].y + w3 * b[3].y,
+    )
+}
+
+// Pushes everything after the piece's start point.
+fn flatten_bezier(b: [Point; 4], tolerance: f32, depth: usize, out: &mut Vec<Point>) {
+    let (start, end) = (b[0], b[3]);
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let chord = dx.hypot(dy);
+    let off_chord = |p: Point| {
+        if chord == 0.0 {
+            (p.x - start.x).hypot(p.y - start.y)
+        } else {
+            ((p.x - start.x) * dy - (p.y - start.y) * dx).abs() / chord
+        }
+    };
+    if depth >= 16 || off_chord(b[1]).max(off_chord(b[2])) <= tolerance {
+        out.push(end);
+        return;
+    }
+
+    // De Casteljau at t = 1/2.
+    let mid = |a: Point, b: Point| Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+    let (ab, bc, cd) = (mid(b[0], b[1]), mid(b[1], b[2]), mid(b[2], b[3]));
+    let (abc, bcd) = (mid(ab, bc), mid(bc, cd));
+    let centre = mid(abc, bcd);
+    flatten_bezier([b[0], ab, abc, centre], tolerance, depth + 1, out);
+    flatten_bezier([centre, bcd, cd, b[3]], tolerance, depth + 1, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon_area;
+
+    fn square() -> Contour {
+        Contour::new(&[
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 10.0),
+            Point::new(0.0, 10.0),
+        ])
+    }
+
+    #[test]
+    fn passes_through_its_points_and_wraps() {
+        let contour = square();
+        for (i, p) in contour.points().iter().enumerate() {
+            assert_eq!(contour.evaluate(i as f32), *p);
+        }
+        assert_eq!(contour.evaluate(4.0), contour.evaluate(0.0));
+        assert_eq!(contour.evaluate(-0.5), contour.evaluate(3.5));
+    }
+
+    #[test]
+    fn area_matches_the_flattened_polygon() {
+        let contour = square();
+        let area = contour.signed_area();
+        assert!(area > 0.0);
+        assert_eq!(contour.reversed().signed_area(), -area);
+
+        let polygon = contour.flatten(0.01);
+        assert_ne!(polygon.first(), polygon.last());
+        assert!((polygon_area(&polygon) - area).abs() < 1e-2 * area, "{} against {}", polygon_area(&polygon), area);
+        for i in 0..40 {
+            let p = contour.evaluate(i as f32 * 0.1);
+            let nearest = polygon.iter().map(|q| (p.x - q.x).hypot(p.y - q.y)).fold(f32::INFINITY, f32::min);
+            assert!(nearest < 1.0, "{:?} is {} from the polygon", p, nearest);
+        }
+    }
+}
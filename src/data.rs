@@ -0,0 +1,54 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::Point;
+
+pub struct CsvData {
+    /// Sorted by x with repeated x values dropped, ready to be interpolated.
+    pub points: Vec<Point>,
+    /// Rows left out because x or y was NaN or infinite.
+    pub non_finite_rows: usize,
+}
+
+/// Reads `x, y` rows from a CSV file. Fields may be separated by commas,
+/// semicolons or whitespace; rows that don't start with two numbers (headers,
+/// comments) are skipped, and rows holding `nan` or `inf` are counted and
+/// dropped.
+pub fn load_csv(path: impl AsRef<Path>) -> io::Result<CsvData> {
+    let text = fs::read_to_string(path)?;
+    let (mut points, non_finite): (Vec<Point>, Vec<Point>) = text
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line
+                .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty());
+            let x = fields.next()?.parse().ok()?;
+            let y = fields.next()?.parse().ok()?;
+            Some(Point::new(x, y))
+        })
+        .partition(|p| p.x.is_finite() && p.y.is_finite());
+
+    points.sort_by(|a, b| a.x.total_cmp(&b.x));
+    points.dedup_by(|a, b| a.x == b.x);
+
+    if points.len() < 2 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected at least two finite x, y rows"));
+    }
+    Ok(CsvData {
+        points,
+        non_finite_rows: non_finite.len(),
+    })
+}
+
+/// Picks `n` points spread evenly by index, always keeping the first and last.
+pub fn subsample(data: &[Point], n: usize) -> Vec<Point> {
+    if n >= data.len() {
+        return data.to_vec();
+    }
+    if n < 2 {
+        return data[..n].to_vec();
+    }
+    let last = data.len() - 1;
+    (0..n).map(|i| data[i * last / (n - 1)]).collect()
+}
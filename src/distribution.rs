@@ -0,0 +1,196 @@
+use crate::{Point, Rng, Segment, Spline, SplineError, SplineType};
+
+/// A continuous probability distribution whose CDF is a monotone spline,
+/// either smoothed from a data sample or taken from a designed curve.
+pub struct Distribution {
+    cdf: Spline,
+}
+
+impl Distribution {
+    /// Smooths the empirical CDF of `sample`. The CDF runs from 0 at the
+    /// smallest value to 1 at the largest, through the midpoint of each step
+    /// of the empirical CDF in between, and is interpolated with a monotone
+    /// cubic (Fritsch–Butland slopes), so it never overshoots between values.
+    ///
+    /// Needs at least 2 distinct values.
+    pub fn from_sample(sample: &[f32]) -> Result<Self, SplineError> {
+        if let Some(i) = sample.iter().position(|v| !v.is_finite()) {
+            return Err(SplineError::NonFinite(i));
+        }
+        let mut sorted = sample.to_vec();
+        sorted.sort_by(f32::total_cmp);
+
+        let n = sorted.len() as f32;
+        let mut steps: Vec<Point> = Vec::new();
+        let mut below = 0;
+        for group in sorted.chunk_by(|a, b| a == b) {
+            steps.push(Point::new(group[0], (below as f32 + group.len() as f32 / 2.0) / n));
+            below += group.len();
+        }
+        if steps.len() < 2 {
+            return Err(SplineError::TooFewPoints);
+        }
+        let last = steps.len() - 1;
+        steps[0].y = 0.0;
+        steps[last].y = 1.0;
+
+        Ok(Distribution {
+            cdf: monotone_cubic(&steps),
+        })
+    }
+
+    /// Uses a designed curve as the CDF, rescaled so it rises from 0 at the
+    /// start of its domain to 1 at the end. Fails if it ever falls or turns.
+    pub fn from_cdf(curve: &Spline) -> Result<Self, SplineError> {
+        if curve.monotone_direction()? < 0.0 {
+            return Err(SplineError::NotMonotone(curve.domain().0));
+        }
+        let points = curve.points();
+        let (start, end) = (points[0].y, points[points.len() - 1].y);
+        let scale = 1.0 / (end - start);
+
+        let cdf = Spline::from_pieces(
+            points.iter().map(|p| Point::new(p.x, (p.y - start) * scale)).collect(),
+            curve.spline_type(),
+            curve.pieces().iter().map(|piece| piece.iter().map(|c| c * scale).collect()).collect(),
+        );
+        Ok(Distribution { cdf })
+    }
+
+    pub fn cdf(&self, x: f32) -> f32 {
+        let (min, max) = self.cdf.domain();
+        if x <= min {
+            0.0
+        } else if x >= max {
+            1.0
+        } else {
+            self.cdf.evaluate(x).clamp(0.0, 1.0)
+        }
+    }
+
+    /// The probability density, the CDF's slope; zero outside the support.
+    pub fn density(&self, x: f32) -> f32 {
+        let (min, max) = self.cdf.domain();
+        if x < min || x > max {
+            return 0.0;
+        }
+        self.cdf
+            .segments()
+            .find(|s| x <= s.x1)
+            .map_or(0.0, |s| s.derivative(x).max(0.0))
+    }
+
+    /// The smallest x with `cdf(x) >= p`, found exactly by solving the cubic
+    /// piece that crosses `p`. `p` is clamped into `[0, 1]`.
+    pub fn quantile(&self, p: f32) -> f32 {
+        if p.is_nan() {
+            return f32::NAN;
+        }
+        let p = p.clamp(0.0, 1.0);
+        let points = self.cdf.points();
+        let i = points.partition_point(|knot| knot.y < p);
+        if i == 0 {
+            return points[0].x;
+        }
+        if i == points.len() {
+            return points[i - 1].x;
+        }
+
+        let segment = self.cdf.segment(i - 1);
+        let mut coeffs = segment.coeffs.to_vec();
+        coeffs[0] -= p;
+        let shifted = Segment {
+            coeffs: &coeffs,
+            ..segment
+        };
+        shifted.roots().first().copied().unwrap_or(points[i].x)
+    }
+
+    /// Inverse-transform sampling: the quantile of a uniform draw.
+    pub fn sample(&self, rng: &mut Rng) -> f32 {
+        self.quantile(rng.next_f32())
+    }
+
+    /// The smallest and largest values the distribution produces.
+    pub fn support(&self) -> (f32, f32) {
+        self.cdf.domain()
+    }
+
+    pub fn cdf_spline(&self) -> &Spline {
+        &self.cdf
+    }
+}
+
+// Piecewise cubic Hermite through increasing points, with each interior slope
+// the weighted harmonic mean of the secant slopes either side, which keeps
+// every piece monotone.
+fn monotone_cubic(points: &[Point]) -> Spline {
+    let n = points.len();
+    let h: Vec<f32> = points.windows(2).map(|w| w[1].x - w[0].x).collect();
+    let delta: Vec<f32> = points.windows(2).zip(&h).map(|(w, &h)| (w[1].y - w[0].y) / h).collect();
+
+    let mut slopes = vec![0.0; n];
+    for i in 1..n - 1 {
+        if delta[i - 1] * delta[i] > 0.0 {
+            let (w1, w2) = (2.0 * h[i] + h[i - 1], h[i] + 2.0 * h[i - 1]);
+            slopes[i] = (w1 + w2) / (w1 / delta[i - 1] + w2 / delta[i]);
+        }
+    }
+    slopes[0] = delta[0];
+    slopes[n - 1] = delta[n - 2];
+
+    let pieces = (0..n - 1)
+        .map(|i| {
+            let (m0, m1) = (slopes[i], slopes[i + 1]);
+            vec![
+                points[i].y,
+                m0,
+                (3.0 * delta[i] - 2.0 * m0 - m1) / h[i],
+                (m0 + m1 - 2.0 * delta[i]) / (h[i] * h[i]),
+            ]
+        })
+        .collect();
+    Spline::from_pieces(points.to_vec(), SplineType::Cubic, pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_cdf_is_monotone_and_inverted_by_the_quantile() {
+        let mut rng = Rng::new(7);
+        let sample: Vec<f32> = (0..200).map(|_| rng.gaussian()).collect();
+        let distribution = Distribution::from_sample(&sample).unwrap();
+        let (min, max) = distribution.support();
+        assert_eq!((distribution.cdf(min), distribution.cdf(max)), (0.0, 1.0));
+
+        let xs: Vec<f32> = (0..=500).map(|i| min + (max - min) * i as f32 / 500.0).collect();
+        assert!(xs.windows(2).all(|w| distribution.cdf(w[0]) <= distribution.cdf(w[1])));
+        assert!(xs.iter().all(|&x| distribution.density(x) >= 0.0));
+
+        for p in [0.0, 0.1, 0.5, 0.9, 1.0] {
+            assert!((distribution.cdf(distribution.quantile(p)) - p).abs() < 1e-4, "p {}", p);
+        }
+        assert!((0..100).all(|_| (min..=max).contains(&distribution.sample(&mut rng))));
+    }
+
+    #[test]
+    fn designed_cdf_is_rescaled() {
+        let line = Spline::new(&[Point::new(0.0, 2.0), Point::new(4.0, 6.0)], SplineType::Linear);
+        let distribution = Distribution::from_cdf(&line).unwrap();
+        assert_eq!(distribution.cdf(1.0), 0.25);
+        assert_eq!(distribution.quantile(0.5), 2.0);
+        assert_eq!(distribution.density(3.0), 0.25);
+        assert_eq!(distribution.density(5.0), 0.0);
+
+        let falling = Spline::new(&[Point::new(0.0, 1.0), Point::new(1.0, 0.0)], SplineType::Linear);
+        assert_eq!(Distribution::from_cdf(&falling).err(), Some(SplineError::NotMonotone(0.0)));
+    }
+
+    #[test]
+    fn unusable_samples_are_an_error() {
+        assert_eq!(Distribution::from_sample(&[3.0, 3.0]).err(), Some(SplineError::TooFewPoints));
+        assert_eq!(Distribution::from_sample(&[1.0, f32::NAN]).err(), Some(SplineError::NonFinite(1)));
+    }
+}
@@ -0,0 +1,301 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Point, Spline, SplineType};
+
+const MAGIC: &[u8; 4] = b"SPLN";
+const VERSION: u8 = 2;
+
+/// How knots and coefficients are stored by [`Spline::to_bytes`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Precision {
+    /// Little-endian `f32`, reproducing the spline exactly.
+    Float32,
+    /// Each array is stored as its `f32` range followed by one `u16` per
+    /// value, mapped linearly onto that range. Roughly halves the size at a
+    /// relative error of about `1 / 65535` of each array's spread.
+    Quantized16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownSplineType(u8),
+    UnknownPrecision(u8),
+    Truncated,
+    TrailingBytes,
+    /// A share string with a character outside the URL-safe base64 alphabet.
+    InvalidText,
+    /// Fewer than 2 knots, a non-finite value, or x values that are not
+    /// strictly increasing (which quantization can cause for knots closer
+    /// together than the 16-bit step).
+    InvalidKnots,
+    /// Annotation text that is not UTF-8, or an annotation anchored to a
+    /// point the design does not have.
+    InvalidAnnotation,
+    /// Tag text that is not UTF-8, or a tag on a point the design does not
+    /// have.
+    InvalidTag,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not an encoded spline"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported spline format version {}", v),
+            DecodeError::UnknownSplineType(t) => write!(f, "unknown spline type {}", t),
+            DecodeError::UnknownPrecision(p) => write!(f, "unknown precision {}", p),
+            DecodeError::Truncated => write!(f, "encoded spline is truncated"),
+            DecodeError::TrailingBytes => write!(f, "unexpected bytes after encoded spline"),
+            DecodeError::InvalidText => write!(f, "share string is not valid base64"),
+            DecodeError::InvalidKnots => write!(f, "encoded knots are not finite and strictly increasing"),
+            DecodeError::InvalidAnnotation => write!(f, "encoded annotation is not valid"),
+            DecodeError::InvalidTag => write!(f, "encoded point tag is not valid"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+// Layout, all little-endian:
+//
+//   "SPLN" | version u8 | spline type u8 | precision u8 | knot count u32
+//   x[n] | last y | coefficient k of each piece [n-1], for k in 0..=degree
+//
+// The constant terms are the other knots' y values. Coefficients are stored
+// rather than refitted on load so a decoder can evaluate the curve directly,
+// and so what was designed is what plays back.
+impl Spline {
+    /// Encodes the knots and fitted coefficients in a compact, versioned
+    /// binary format that [`Spline::from_bytes`] reads back.
+    pub fn to_bytes(&self, precision: Precision) -> Vec<u8> {
+        let points = self.points();
+
+        let mut arrays = vec![points.iter().map(|p| p.x).collect::<Vec<f32>>(), vec![points[points.len() - 1].y]];
+        for k in 0..=self.spline_type().degree() {
+            arrays.push(self.pieces().iter().map(|piece| piece[k]).collect());
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(type_code(self.spline_type()));
+        out.push(match precision {
+            Precision::Float32 => 0,
+            Precision::Quantized16 => 1,
+        });
+        out.extend_from_slice(&(points.len() as u32).to_le_bytes());
+
+        for values in &arrays {
+            match precision {
+                Precision::Float32 => {
+                    for v in values {
+                        out.extend_from_slice(&v.to_le_bytes());
+                    }
+                }
+                Precision::Quantized16 => write_quantized(&mut out, values),
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Spline, DecodeError> {
+        let mut reader = Reader::new(bytes);
+
+        if reader.take(4)? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let spline_type = type_from_code(reader.u8()?)?;
+        let precision = match reader.u8()? {
+            0 => Precision::Float32,
+            1 => Precision::Quantized16,
+            p => return Err(DecodeError::UnknownPrecision(p)),
+        };
+        let n = reader.u32()? as usize;
+        if n < 2 {
+            return Err(DecodeError::InvalidKnots);
+        }
+
+        let mut read_array = |len: usize| match precision {
+            Precision::Float32 => (0..len).map(|_| reader.f32()).collect::<Result<Vec<f32>, _>>(),
+            Precision::Quantized16 => read_quantized(&mut reader, len),
+        };
+        let xs = read_array(n)?;
+        let last_y = read_array(1)?;
+        let columns = (0..=spline_type.degree())
+            .map(|_| read_array(n - 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        reader.finish()?;
+
+        let all_finite = [&xs, &last_y]
+            .into_iter()
+            .chain(&columns)
+            .all(|values| values.iter().all(|v| v.is_finite()));
+        if !all_finite || xs.windows(2).any(|pair| pair[1] <= pair[0]) {
+            return Err(DecodeError::InvalidKnots);
+        }
+
+        let pieces: Vec<Vec<f32>> = (0..n - 1).map(|i| columns.iter().map(|c| c[i]).collect()).collect();
+        let points = xs
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| Point::new(x, pieces.get(i).map_or(last_y[0], |piece| piece[0])))
+            .collect();
+        Ok(Spline::from_pieces(points, spline_type, pieces))
+    }
+}
+
+pub(crate) fn type_code(spline_type: SplineType) -> u8 {
+    match spline_type {
+        SplineType::Linear => 0,
+        SplineType::Quadratic => 1,
+        SplineType::Cubic => 2,
+    }
+}
+
+pub(crate) fn type_from_code(code: u8) -> Result<SplineType, DecodeError> {
+    match code {
+        0 => Ok(SplineType::Linear),
+        1 => Ok(SplineType::Quadratic),
+        2 => Ok(SplineType::Cubic),
+        t => Err(DecodeError::UnknownSplineType(t)),
+    }
+}
+
+fn write_quantized(out: &mut Vec<u8>, values: &[f32]) {
+    let (min, max) = values
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let (min, max) = if min <= max { (min, max) } else { (0.0, 0.0) };
+    let scale = if max > min { u16::MAX as f32 / (max - min) } else { 0.0 };
+
+    out.extend_from_slice(&min.to_le_bytes());
+    out.extend_from_slice(&max.to_le_bytes());
+    for &v in values {
+        let q = ((v - min) * scale).round() as u16;
+        out.extend_from_slice(&q.to_le_bytes());
+    }
+}
+
+fn read_quantized(reader: &mut Reader, len: usize) -> Result<Vec<f32>, DecodeError> {
+    let min = reader.f32()?;
+    let max = reader.f32()?;
+    let step = (max - min) / u16::MAX as f32;
+    (0..len)
+        .map(|_| {
+            let q = reader.u16()?;
+            Ok(if q == u16::MAX { max } else { min + q as f32 * step })
+        })
+        .collect()
+}
+
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    pub(crate) fn finish(&self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::TrailingBytes)
+        }
+    }
+
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn f32(&mut self) -> Result<f32, DecodeError> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spline(spline_type: SplineType) -> Spline {
+        let points = [
+            Point::new(0.0, 1.0),
+            Point::new(0.5, -2.0),
+            Point::new(2.0, 0.25),
+            Point::new(3.5, 4.0),
+        ];
+        Spline::new(&points, spline_type)
+    }
+
+    #[test]
+    fn float32_round_trips_exactly() {
+        for spline_type in [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic] {
+            let spline = spline(spline_type);
+            let decoded = Spline::from_bytes(&spline.to_bytes(Precision::Float32)).unwrap();
+            assert_eq!(decoded.spline_type(), spline_type);
+            assert_eq!(decoded.points(), spline.points());
+            assert_eq!(decoded.pieces(), spline.pieces());
+        }
+    }
+
+    #[test]
+    fn quantized16_is_smaller_and_close() {
+        let points: Vec<Point> = (0..40).map(|i| Point::new(i as f32 * 0.1, (i as f32 * 0.3).sin())).collect();
+        let spline = Spline::new(&points, SplineType::Cubic);
+        let (exact, quantized) = (spline.to_bytes(Precision::Float32), spline.to_bytes(Precision::Quantized16));
+        assert!(quantized.len() < exact.len());
+
+        let decoded = Spline::from_bytes(&quantized).unwrap();
+        assert_eq!(decoded.domain(), spline.domain());
+        for i in 0..=78 {
+            let x = i as f32 * 0.05;
+            assert!((decoded.evaluate(x) - spline.evaluate(x)).abs() < 1e-3, "at {}", x);
+        }
+    }
+
+    #[test]
+    fn malformed_bytes_are_an_error() {
+        let bytes = spline(SplineType::Linear).to_bytes(Precision::Float32);
+        let with = |i: usize, b: u8| {
+            let mut bytes = bytes.clone();
+            bytes[i] = b;
+            Spline::from_bytes(&bytes).err()
+        };
+        assert_eq!(with(0, b'X'), Some(DecodeError::BadMagic));
+        assert_eq!(with(4, 1), Some(DecodeError::UnsupportedVersion(1)));
+        assert_eq!(with(5, 7), Some(DecodeError::UnknownSplineType(7)));
+        assert_eq!(with(6, 2), Some(DecodeError::UnknownPrecision(2)));
+        assert_eq!(with(7, 1), Some(DecodeError::InvalidKnots));
+        assert_eq!(Spline::from_bytes(&bytes[..bytes.len() - 1]).err(), Some(DecodeError::Truncated));
+        assert_eq!(Spline::from_bytes(&[&bytes[..], &[0]].concat()).err(), Some(DecodeError::TrailingBytes));
+
+        // The second knot's x, moved before the first.
+        let mut unordered = bytes.clone();
+        unordered[15..19].copy_from_slice(&(-1.0f32).to_le_bytes());
+        assert_eq!(Spline::from_bytes(&unordered).err(), Some(DecodeError::InvalidKnots));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::spline::is_finite;
+use crate::{Point, Spline, SplineError, SplineType};
+
+impl Spline {
+    /// Interpolates a subset of `data`, starting from its end points and
+    /// greedily adding the sample with the largest error as a knot until
+    /// every sample is within `tol` (or every sample is a knot). Samples
+    /// sharing an x value keep the first.
+    ///
+    /// Fails if a sample is NaN or infinite, or if fewer than 2 distinct x
+    /// values are given.
+    pub fn fit_adaptive(data: &[Point], tol: f32, spline_type: SplineType) -> Result<Spline, SplineError> {
+        if let Some(i) = data.iter().position(|p| !is_finite(p)) {
+            return Err(SplineError::NonFinite(i));
+        }
+        let mut data = data.to_vec();
+        data.sort_by(|a, b| a.x.total_cmp(&b.x));
+        data.dedup_by(|a, b| a.x == b.x);
+        if data.len() < 2 {
+            return Err(SplineError::TooFewPoints);
+        }
+
+        let last = data.len() - 1;
+        let mut spline = Spline::new(&[data[0], data[last]], spline_type);
+        let mut is_knot = vec![false; data.len()];
+        is_knot[0] = true;
+        is_knot[last] = true;
+
+        loop {
+            let worst = data
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| !is_knot[i])
+                .map(|(i, p)| (i, (spline.evaluate(p.x) - p.y).abs()))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            match worst {
+                Some((i, error)) if error > tol => {
+                    spline.insert_point(data[i])?;
+                    is_knot[i] = true;
+                }
+                _ => return Ok(spline),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_adaptive_stays_within_tolerance() {
+        let data: Vec<Point> = (0..200)
+            .map(|i| Point::new(i as f32 * 0.05, (i as f32 * 0.05).sin()))
+            .collect();
+        let spline = Spline::fit_adaptive(&data, 0.01, SplineType::Cubic).unwrap();
+
+        assert!(spline.points().len() < data.len());
+        assert_eq!(spline.domain(), (0.0, 199.0 * 0.05));
+        for p in &data {
+            assert!(
+                (spline.evaluate(p.x) - p.y).abs() <= 0.01,
+                "off by more than 0.01 at x = {}",
+                p.x
+            );
+        }
+    }
+
+    #[test]
+    fn fit_adaptive_rejects_unusable_data() {
+        let fit = |data: &[Point]| Spline::fit_adaptive(data, 0.1, SplineType::Linear).err();
+
+        assert_eq!(fit(&[]), Some(SplineError::TooFewPoints));
+        assert_eq!(
+            fit(&[Point::new(1.0, 0.0), Point::new(1.0, 2.0)]),
+            Some(SplineError::TooFewPoints)
+        );
+        assert_eq!(
+            fit(&[Point::new(0.0, 0.0), Point::new(f32::NAN, 1.0), Point::new(2.0, 0.0)]),
+            Some(SplineError::NonFinite(1))
+        );
+    }
+}
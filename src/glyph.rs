@@ -0,0 +1,137 @@
+use crate::{Contour, Point};
+
+/// A font's vertical metrics, in font units with the baseline at y = 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmSquare {
+    pub units_per_em: f32,
+    /// Below the baseline, so usually negative.
+    pub descender: f32,
+    pub x_height: f32,
+    pub cap_height: f32,
+}
+
+impl EmSquare {
+    /// The top of the em-square.
+    pub fn ascender(&self) -> f32 {
+        self.units_per_em + self.descender
+    }
+}
+
+impl Default for EmSquare {
+    fn default() -> Self {
+        EmSquare {
+            units_per_em: 1000.0,
+            descender: -200.0,
+            x_height: 500.0,
+            cap_height: 700.0,
+        }
+    }
+}
+
+/// A glyph outline of closed contours, in font units.
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    pub name: String,
+    pub unicode: Option<char>,
+    pub advance: f32,
+    pub contours: Vec<Contour>,
+}
+
+impl Glyph {
+    /// A UFO `.glif` (format 2), each contour as cubic curve segments with
+    /// smooth on-curve points.
+    pub fn to_glif(&self) -> String {
+        let mut glif = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        glif.push_str(&format!("<glyph name=\"{}\" format=\"2\">\n", escape(&self.name)));
+        glif.push_str(&format!("  <advance width=\"{}\"/>\n", round(self.advance)));
+        if let Some(c) = self.unicode {
+            glif.push_str(&format!("  <unicode hex=\"{:04X}\"/>\n", c as u32));
+        }
+        glif.push_str("  <outline>\n");
+        for contour in &self.contours {
+            glif.push_str("    <contour>\n");
+            // Each on-curve point follows the two off-curve points leading to
+            // it; the last piece ends on the first point, closing the loop.
+            for [_, c1, c2, end] in contour.beziers() {
+                for c in [c1, c2] {
+                    glif.push_str(&format!("      <point x=\"{}\" y=\"{}\"/>\n", round(c.x), round(c.y)));
+                }
+                glif.push_str(&format!(
+                    "      <point x=\"{}\" y=\"{}\" type=\"curve\" smooth=\"yes\"/>\n",
+                    round(end.x),
+                    round(end.y)
+                ));
+            }
+            glif.push_str("    </contour>\n");
+        }
+        glif.push_str("  </outline>\n</glyph>\n");
+        glif
+    }
+
+    /// A standalone SVG of the glyph over its em-square, y flipped to point
+    /// down, filled with the nonzero rule as fonts are.
+    pub fn to_svg(&self, em: &EmSquare) -> String {
+        let top = em.ascender();
+        let svg_point = |p: Point| format!("{} {}", round(p.x), round(top - p.y));
+        let mut path = String::new();
+        for contour in &self.contours {
+            let beziers = contour.beziers();
+            path.push_str(&format!("M{}", svg_point(beziers[0][0])));
+            for [_, c1, c2, end] in beziers {
+                path.push_str(&format!(" C{} {} {}", svg_point(c1), svg_point(c2), svg_point(end)));
+            }
+            path.push_str(" Z ");
+        }
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">\n  \
+             <path d=\"{}\" fill=\"black\" fill-rule=\"nonzero\"/>\n</svg>\n",
+            round(self.advance),
+            round(em.units_per_em),
+            path.trim_end()
+        )
+    }
+}
+
+// Hundredths of a font unit are plenty, and keep the files readable.
+fn round(v: f32) -> f32 {
+    (v * 100.0).round() / 100.0
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glyph() -> Glyph {
+        let triangle = [Point::new(100.0, 0.0), Point::new(500.0, 0.0), Point::new(300.0, 700.0)];
+        Glyph {
+            name: "A&\"b\"".into(),
+            unicode: Some('A'),
+            advance: 600.0,
+            contours: vec![Contour::new(&triangle)],
+        }
+    }
+
+    #[test]
+    fn glif_lists_each_piece_ending_on_curve() {
+        let glif = glyph().to_glif();
+        assert!(glif.contains("<glyph name=\"A&amp;&quot;b&quot;\" format=\"2\">"));
+        assert!(glif.contains("<advance width=\"600\"/>"));
+        assert!(glif.contains("<unicode hex=\"0041\"/>"));
+        assert_eq!(glif.matches("type=\"curve\" smooth=\"yes\"").count(), 3);
+        assert_eq!(glif.matches("<point ").count(), 9);
+        // The last piece closes the contour on its first point.
+        assert!(glif.contains("<point x=\"100\" y=\"0\" type=\"curve\" smooth=\"yes\"/>\n    </contour>"));
+    }
+
+    #[test]
+    fn svg_flips_y_below_the_ascender() {
+        let svg = glyph().to_svg(&EmSquare::default());
+        assert!(svg.contains("viewBox=\"0 0 600 1000\""));
+        assert!(svg.contains("d=\"M100 800 C"));
+        assert!(svg.contains(" 300 100 C") && svg.ends_with("Z\" fill=\"black\" fill-rule=\"nonzero\"/>\n</svg>\n"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Rasterizes the curve samples of known point sets with each spline type
+//! and compares them against reference images in `tests/golden`, so changes
+//! to how curves are sampled can't silently change what is drawn.
+//!
+//! This checks sampling, not rendering. The points go into an editor model
+//! and the curve comes from `curve_samples`, the samples the view draws, but
+//! the rasterizing is the test's own: a polyline through those samples with
+//! a dot on every knot. `view()`, nannou's `Draw` and its tessellation need a
+//! GPU device, which CI runners don't have, so line weights, knot markers
+//! and colours are not covered here.
+//!
+//! The model is built from the default config and the repository's locales,
+//! never the user's files. After a deliberate change, regenerate the images
+//! with `UPDATE_GOLDEN=1 cargo test golden` and look over the diff.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use nannou::prelude::Rect;
+use splines::{Point, SplineType};
+
+use crate::cli::Options;
+use crate::config::Config;
+use crate::{curve_samples, model_with_config, rebuild_spline};
+
+const WIDTH: usize = 192;
+const HEIGHT: usize = 96;
+const LINE_RADIUS: f32 = 1.0;
+const KNOT_RADIUS: f32 = 2.5;
+// A pixel counts as changed past this difference in grey level, and a
+// render fails once more than this fraction of its pixels has changed.
+const PIXEL_TOLERANCE: u8 = 48;
+const CHANGED_FRACTION: f32 = 0.002;
+
+// Maps the point sets, `x` in `[-1, 1]` and `y` in `[-1.5, 1.5]`, onto the
+// window, which has its origin in the middle and y pointing up.
+const SCALE: (f32, f32) = ((WIDTH as f32 - 16.0) / 2.0, (HEIGHT as f32 - 8.0) / 3.0);
+
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Canvas {
+            pixels: vec![0; WIDTH * HEIGHT],
+        }
+    }
+
+    fn to_pixels(&self, p: Point) -> (f32, f32) {
+        (WIDTH as f32 / 2.0 + p.x, HEIGHT as f32 / 2.0 - p.y)
+    }
+
+    // Covers every pixel whose centre is within `radius` of the segment from
+    // `a` to `b`, fading out over the last pixel for a little anti-aliasing.
+    fn stroke(&mut self, a: Point, b: Point, radius: f32) {
+        let (a, b) = (self.to_pixels(a), self.to_pixels(b));
+        let reach = radius + 1.0;
+        let x_range = (a.0.min(b.0) - reach).floor().max(0.0) as usize..=(a.0.max(b.0) + reach).ceil() as usize;
+        let y_range = (a.1.min(b.1) - reach).floor().max(0.0) as usize..=(a.1.max(b.1) + reach).ceil() as usize;
+        for y in y_range.filter(|&y| y < HEIGHT) {
+            for x in x_range.clone().filter(|&x| x < WIDTH) {
+                let distance = distance_to_segment((x as f32 + 0.5, y as f32 + 0.5), a, b);
+                let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+                let pixel = &mut self.pixels[y * WIDTH + x];
+                *pixel = (*pixel).max((coverage * 255.0).round() as u8);
+            }
+        }
+    }
+
+    // Binary PGM, which any image viewer opens.
+    fn to_pgm(&self) -> Vec<u8> {
+        let mut bytes = format!("P5\n{} {}\n255\n", WIDTH, HEIGHT).into_bytes();
+        bytes.extend(&self.pixels);
+        bytes
+    }
+
+    fn from_pgm(bytes: &[u8]) -> Option<Canvas> {
+        let header = format!("P5\n{} {}\n255\n", WIDTH, HEIGHT).into_bytes();
+        let pixels = bytes.strip_prefix(header.as_slice())?;
+        (pixels.len() == WIDTH * HEIGHT).then(|| Canvas {
+            pixels: pixels.to_vec(),
+        })
+    }
+}
+
+fn distance_to_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (p.0 - a.0 - t * dx).hypot(p.1 - a.1 - t * dy)
+}
+
+fn render(points: &[Point], spline_type: SplineType) -> Canvas {
+    let rect = Rect::from_w_h(WIDTH as f32, HEIGHT as f32);
+    let mut config = Config::default();
+    config.locales = concat!(env!("CARGO_MANIFEST_DIR"), "/locales").to_string();
+    let mut model = model_with_config(&Options::default(), config);
+    model.current_spline_type = spline_type;
+    model.control_points = points.iter().map(|p| Point::new(p.x * SCALE.0, p.y * SCALE.1)).collect();
+    rebuild_spline(&mut model);
+    let spline = model.spline.as_ref().expect("every point set fits");
+
+    let mut canvas = Canvas::new();
+    for pair in curve_samples(rect, &model, spline).windows(2) {
+        canvas.stroke(pair[0], pair[1], LINE_RADIUS);
+    }
+    for &knot in &model.control_points {
+        canvas.stroke(knot, knot, KNOT_RADIUS);
+    }
+    canvas
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name)
+}
+
+// Compares every render against its reference, reporting all that changed
+// rather than stopping at the first. A failed render is written next to its
+// reference as `<name>.actual.pgm`.
+fn check(point_sets: &[(&str, Vec<Point>)]) {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for (set, points) in point_sets {
+        for spline_type in [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic] {
+            let name = format!("{}-{:?}", set, spline_type).to_lowercase();
+            let actual = render(points, spline_type);
+            let path = golden_path(&format!("{}.pgm", name));
+            if update {
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, actual.to_pgm()).unwrap();
+                continue;
+            }
+
+            let expected = match fs::read(&path).ok().and_then(|bytes| Canvas::from_pgm(&bytes)) {
+                Some(expected) => expected,
+                None => {
+                    failures.push(format!("{}: no valid reference image at {}", name, path.display()));
+                    continue;
+                }
+            };
+            let changed = actual
+                .pixels
+                .iter()
+                .zip(&expected.pixels)
+                .filter(|(a, e)| a.abs_diff(**e) > PIXEL_TOLERANCE)
+                .count();
+            if changed as f32 > CHANGED_FRACTION * (WIDTH * HEIGHT) as f32 {
+                let actual_path = golden_path(&format!("{}.actual.pgm", name));
+                fs::write(&actual_path, actual.to_pgm()).unwrap();
+                failures.push(format!(
+                    "{}: {} pixels changed, see {}",
+                    name,
+                    changed,
+                    actual_path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "renders differ from tests/golden (rerun with UPDATE_GOLDEN=1 if the change is intended):\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn curve_samples_match_reference_rasters() {
+    check(&[
+        (
+            "wave",
+            vec![
+                Point::new(-1.0, 0.0),
+                Point::new(-0.5, 0.8),
+                Point::new(0.0, -0.8),
+                Point::new(0.5, 0.8),
+                Point::new(1.0, 0.0),
+            ],
+        ),
+        // Uneven spacing and a sharp step, where the types overshoot differently.
+        (
+            "step",
+            vec![
+                Point::new(-1.0, -0.6),
+                Point::new(-0.7, -0.6),
+                Point::new(-0.1, -0.5),
+                Point::new(0.05, 0.6),
+                Point::new(0.6, 0.7),
+                Point::new(1.0, 0.6),
+            ],
+        ),
+        // Given out of order, which the spline sorts.
+        (
+            "unsorted",
+            vec![
+                Point::new(0.9, -1.0),
+                Point::new(-0.9, 1.0),
+                Point::new(0.2, 0.1),
+                Point::new(-0.3, -0.4),
+            ],
+        ),
+    ]);
+}
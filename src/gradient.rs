@@ -0,0 +1,176 @@
+use crate::{Point, Spline, SplineError, SplineType};
+
+/// The space a [`Gradient`] interpolates its colours in. Linear RGB mixes
+/// light physically, so it avoids sRGB's dark midpoints; Oklab keeps
+/// perceived lightness and hue changing evenly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSpace {
+    Srgb,
+    LinearRgb,
+    Oklab,
+}
+
+impl ColorSpace {
+    /// From sRGB components in `[0, 1]`.
+    fn encode(self, rgb: [f32; 3]) -> [f32; 3] {
+        match self {
+            ColorSpace::Srgb => rgb,
+            ColorSpace::LinearRgb => rgb.map(srgb_to_linear),
+            ColorSpace::Oklab => linear_to_oklab(rgb.map(srgb_to_linear)),
+        }
+    }
+
+    /// Back to sRGB, clamped into `[0, 1]`.
+    fn decode(self, c: [f32; 3]) -> [f32; 3] {
+        let rgb = match self {
+            ColorSpace::Srgb => c,
+            ColorSpace::LinearRgb => c.map(linear_to_srgb),
+            ColorSpace::Oklab => oklab_to_linear(c).map(linear_to_srgb),
+        };
+        rgb.map(|v| v.clamp(0.0, 1.0))
+    }
+}
+
+/// A colour at a position along a gradient, as sRGB components in `[0, 1]`.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorStop {
+    pub position: f32,
+    pub color: [f32; 3],
+}
+
+/// A colour gradient through stops, with one spline per component of the
+/// chosen colour space.
+pub struct Gradient {
+    space: ColorSpace,
+    channels: [Spline; 3],
+}
+
+impl Gradient {
+    /// Panics where [`Gradient::try_new`] would return an error.
+    pub fn new(stops: &[ColorStop], space: ColorSpace, spline_type: SplineType) -> Self {
+        Gradient::try_new(stops, space, spline_type).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fails as [`Spline::try_new`] does on the stop positions.
+    pub fn try_new(stops: &[ColorStop], space: ColorSpace, spline_type: SplineType) -> Result<Self, SplineError> {
+        let converted: Vec<(f32, [f32; 3])> = stops.iter().map(|s| (s.position, space.encode(s.color))).collect();
+        let channel = |k: usize| {
+            let points: Vec<Point> = converted.iter().map(|&(x, c)| Point::new(x, c[k])).collect();
+            Spline::try_new(&points, spline_type)
+        };
+        Ok(Gradient {
+            space,
+            channels: [channel(0)?, channel(1)?, channel(2)?],
+        })
+    }
+
+    pub fn space(&self) -> ColorSpace {
+        self.space
+    }
+
+    /// The range of positions the stops cover.
+    pub fn domain(&self) -> (f32, f32) {
+        self.channels[0].domain()
+    }
+
+    /// The sRGB colour at `position`, in `[0, 1]`. Overshoot between stops
+    /// is clipped.
+    pub fn color_at(&self, position: f32) -> [f32; 3] {
+        self.space.decode([0, 1, 2].map(|k| self.channels[k].evaluate(position)))
+    }
+
+    /// `width` 8-bit sRGB pixels evenly across `domain()`, for a ramp image.
+    pub fn ramp(&self, width: usize) -> Vec<[u8; 3]> {
+        let (min, max) = self.domain();
+        (0..width)
+            .map(|i| {
+                let t = if width > 1 { i as f32 / (width - 1) as f32 } else { 0.0 };
+                self.color_at(min + (max - min) * t).map(|v| (v * 255.0).round() as u8)
+            })
+            .collect()
+    }
+
+    /// A CSS `linear-gradient` that approximates the curve with `stops`
+    /// evenly spaced colour stops, since CSS itself only interpolates
+    /// linearly in sRGB.
+    pub fn to_css(&self, stops: usize) -> String {
+        let stops = stops.max(2);
+        let colors: Vec<String> = self
+            .ramp(stops)
+            .iter()
+            .enumerate()
+            .map(|(i, [r, g, b])| {
+                let percent = 100.0 * i as f32 / (stops - 1) as f32;
+                format!("#{:02x}{:02x}{:02x} {}%", r, g, b, (percent * 100.0).round() / 100.0)
+            })
+            .collect();
+        format!("linear-gradient(90deg, {})", colors.join(", "))
+    }
+}
+
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.max(0.0).powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// Björn Ottosson's Oklab, from linear sRGB.
+fn linear_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn oklab_to_linear([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    [
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oklab_round_trips_srgb() {
+        let steps = [0.0, 0.02, 0.2, 0.5, 0.8, 1.0];
+        for &r in &steps {
+            for &g in &steps {
+                for &b in &steps {
+                    let rgb = [r, g, b];
+                    let back = ColorSpace::Oklab.decode(ColorSpace::Oklab.encode(rgb));
+                    for (v, w) in back.iter().zip(rgb) {
+                        assert!((v - w).abs() < 1e-4, "{:?} came back as {:?}", rgb, back);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn white_is_achromatic_at_full_lightness() {
+        let [l, a, b] = ColorSpace::Oklab.encode([1.0, 1.0, 1.0]);
+        assert!((l - 1.0).abs() < 1e-4, "L = {}", l);
+        assert!(a.abs() < 1e-4 && b.abs() < 1e-4, "a = {}, b = {}", a, b);
+    }
+}
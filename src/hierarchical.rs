@@ -0,0 +1,217 @@
+use crate::Spline;
+
+/// A uniform cubic B-spline offset over a fixed x-range, the detail added
+/// by one level of a [`HierarchicalCurve`]. Level `l` splits the range
+/// into `2^l` spans, so each finer level edits a region half as wide.
+///
+/// The offset is zero outside its range and starts out zero everywhere.
+#[derive(Clone, Debug)]
+pub struct DetailLayer {
+    pub enabled: bool,
+    level: u32,
+    domain: (f32, f32),
+    coefficients: Vec<f32>,
+}
+
+impl DetailLayer {
+    /// Panics if the domain is empty or `level > 16`.
+    pub fn new(domain: (f32, f32), level: u32) -> Self {
+        assert!(domain.0 < domain.1, "detail layer domain must be non-empty");
+        assert!(level <= 16, "detail level {} is finer than 2^16 spans", level);
+        DetailLayer {
+            enabled: true,
+            level,
+            domain,
+            coefficients: vec![0.0; (1 << level) + 3],
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn domain(&self) -> (f32, f32) {
+        self.domain
+    }
+
+    pub fn coefficients(&self) -> &[f32] {
+        &self.coefficients
+    }
+
+    /// Stretches the offset onto `domain`, keeping its shape.
+    ///
+    /// Panics if the domain is empty.
+    pub fn set_domain(&mut self, domain: (f32, f32)) {
+        assert!(domain.0 < domain.1, "detail layer domain must be non-empty");
+        self.domain = domain;
+    }
+
+    /// The offset at `x`, whether or not the layer is enabled.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        match self.basis(x) {
+            Some((first, weights)) => weights
+                .iter()
+                .zip(&self.coefficients[first..first + 4])
+                .map(|(w, c)| w * c)
+                .sum(),
+            None => 0.0,
+        }
+    }
+
+    /// Raises the offset at `x` by `dy`, changing only the four coefficients
+    /// whose basis functions reach `x`, each in proportion to its weight
+    /// there (the smallest such change).
+    pub fn nudge(&mut self, x: f32, dy: f32) {
+        if let Some((first, weights)) = self.basis(x) {
+            let norm: f32 = weights.iter().map(|w| w * w).sum();
+            for (c, w) in self.coefficients[first..first + 4].iter_mut().zip(weights) {
+                *c += dy * w / norm;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.coefficients.iter_mut().for_each(|c| *c = 0.0);
+    }
+
+    // The first coefficient acting at `x` and the four basis weights.
+    fn basis(&self, x: f32) -> Option<(usize, [f32; 4])> {
+        let (min, max) = self.domain;
+        if !(min..=max).contains(&x) {
+            return None;
+        }
+        let spans = (1usize << self.level) as f32;
+        let u = (x - min) / (max - min) * spans;
+        let span = (u.floor() as usize).min((1 << self.level) - 1);
+        let t = u - span as f32;
+        let s = 1.0 - t;
+        Some((
+            span,
+            [
+                s * s * s / 6.0,
+                (3.0 * t * t * t - 6.0 * t * t + 4.0) / 6.0,
+                (-3.0 * t * t * t + 3.0 * t * t + 3.0 * t + 1.0) / 6.0,
+                t * t * t / 6.0,
+            ],
+        ))
+    }
+}
+
+/// A base spline with B-spline detail layers on top, for local edits that
+/// can be switched off one level at a time.
+///
+/// The layers are offsets over a fixed x-range rather than changes to the
+/// base's knots, so they survive the base being moved, refitted or
+/// replaced with [`HierarchicalCurve::set_base`].
+pub struct HierarchicalCurve {
+    base: Spline,
+    layers: Vec<DetailLayer>,
+}
+
+impl HierarchicalCurve {
+    pub fn new(base: Spline) -> Self {
+        HierarchicalCurve { base, layers: Vec::new() }
+    }
+
+    pub fn base(&self) -> &Spline {
+        &self.base
+    }
+
+    pub fn set_base(&mut self, base: Spline) {
+        self.base = base;
+    }
+
+    /// Adds a layer at `level` over the base's current domain and returns
+    /// its index.
+    pub fn add_layer(&mut self, level: u32) -> usize {
+        self.layers.push(DetailLayer::new(self.base.domain(), level));
+        self.layers.len() - 1
+    }
+
+    pub fn layers(&self) -> &[DetailLayer] {
+        &self.layers
+    }
+
+    pub fn layer_mut(&mut self, i: usize) -> &mut DetailLayer {
+        &mut self.layers[i]
+    }
+
+    pub fn remove_layer(&mut self, i: usize) -> DetailLayer {
+        self.layers.remove(i)
+    }
+
+    /// The base plus every enabled layer.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        self.base.evaluate(x) + detail_offset(&self.layers, x)
+    }
+}
+
+/// The summed offset of the enabled layers at `x`.
+pub fn detail_offset(layers: &[DetailLayer], x: f32) -> f32 {
+    layers.iter().filter(|l| l.enabled).map(|l| l.evaluate(x)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, SplineType};
+
+    #[test]
+    fn nudge_raises_the_offset_by_exactly_dy() {
+        let mut layer = DetailLayer::new((-2.0, 3.0), 3);
+        for &(x, dy) in &[(0.4, 1.5), (-1.9, -0.25), (2.2, 3.0), (0.4, 0.5)] {
+            let before = layer.evaluate(x);
+            layer.nudge(x, dy);
+            assert!((layer.evaluate(x) - before - dy).abs() < 1e-5, "nudge at x = {}", x);
+        }
+    }
+
+    #[test]
+    fn nudge_stays_within_four_spans_of_its_coefficients() {
+        // 16 spans of width 1; x = 7.3 is in span 7, so coefficients 7..=10
+        // move and each of them only reaches the four spans up to its index.
+        let mut layer = DetailLayer::new((0.0, 16.0), 4);
+        layer.nudge(7.3, 2.0);
+
+        let moved: Vec<usize> = (0..layer.coefficients().len()).filter(|&k| layer.coefficients()[k] != 0.0).collect();
+        assert_eq!(moved, vec![7, 8, 9, 10]);
+        for i in 0..=160 {
+            let x = i as f32 * 0.1;
+            if !(4.0..11.0).contains(&x) {
+                assert_eq!(layer.evaluate(x), 0.0, "offset leaked to x = {}", x);
+            }
+        }
+        assert_ne!(layer.evaluate(4.5), 0.0);
+        assert_ne!(layer.evaluate(10.5), 0.0);
+    }
+
+    #[test]
+    fn nudge_reaches_the_end_of_the_domain() {
+        let mut layer = DetailLayer::new((0.0, 1.0), 2);
+        layer.nudge(1.0, 0.75);
+        assert!((layer.evaluate(1.0) - 0.75).abs() < 1e-6);
+        assert_eq!(layer.evaluate(1.0 + 1e-6), 0.0);
+
+        let mut outside = DetailLayer::new((0.0, 1.0), 2);
+        outside.nudge(1.0 + 1e-6, 0.75);
+        assert!(outside.coefficients().iter().all(|&c| c == 0.0));
+    }
+
+    #[test]
+    fn detail_survives_set_base() {
+        let flat = [Point::new(0.0, 0.0), Point::new(2.0, 0.0), Point::new(4.0, 0.0)];
+        let mut curve = HierarchicalCurve::new(Spline::new(&flat, SplineType::Cubic));
+        let coarse = curve.add_layer(1);
+        let fine = curve.add_layer(3);
+        curve.layer_mut(coarse).nudge(1.0, 0.5);
+        curve.layer_mut(fine).nudge(2.5, -0.2);
+        let detail: Vec<f32> = (0..=40).map(|i| detail_offset(curve.layers(), i as f32 * 0.1)).collect();
+
+        let bent = [Point::new(0.0, 1.0), Point::new(1.0, 3.0), Point::new(4.0, -2.0)];
+        curve.set_base(Spline::new(&bent, SplineType::Cubic));
+        for (i, d) in detail.iter().enumerate() {
+            let x = i as f32 * 0.1;
+            assert!((curve.evaluate(x) - curve.base().evaluate(x) - d).abs() < 1e-5, "detail moved at x = {}", x);
+        }
+    }
+}
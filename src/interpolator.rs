@@ -0,0 +1,108 @@
+use crate::spline::sorted_knots;
+use crate::{Point, PolynomialInterpolant, Spline, SplineError, SplineType};
+
+/// A curve fitted through a set of points, for interpolation methods beyond
+/// the built-in spline types.
+pub trait Interpolator {
+    fn evaluate(&self, x: f32) -> f32;
+    fn derivative(&self, x: f32) -> f32;
+    /// The x-range the curve was fitted over, as `(min_x, max_x)`.
+    fn domain(&self) -> (f32, f32);
+}
+
+impl Interpolator for Spline {
+    fn evaluate(&self, x: f32) -> f32 {
+        Spline::evaluate(self, x)
+    }
+
+    fn derivative(&self, x: f32) -> f32 {
+        Spline::derivative(self, x)
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        Spline::domain(self)
+    }
+}
+
+impl Interpolator for PolynomialInterpolant {
+    fn evaluate(&self, x: f32) -> f32 {
+        PolynomialInterpolant::evaluate(self, x)
+    }
+
+    fn derivative(&self, x: f32) -> f32 {
+        PolynomialInterpolant::derivative(self, x)
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        PolynomialInterpolant::domain(self)
+    }
+}
+
+/// Fits an interpolator through points given in any order.
+pub type Constructor = Box<dyn Fn(&[Point]) -> Result<Box<dyn Interpolator>, SplineError>>;
+
+/// Interpolation methods by name, in the order they are offered. Another
+/// crate adds its own with [`InterpolatorRegistry::register`]:
+///
+/// ```ignore
+/// let mut registry = InterpolatorRegistry::default();
+/// registry.register("nearest", Box::new(|points| Ok(Box::new(Nearest::new(points)?))));
+/// ```
+pub struct InterpolatorRegistry {
+    methods: Vec<(String, Constructor)>,
+}
+
+impl InterpolatorRegistry {
+    /// A registry without any methods.
+    pub fn empty() -> Self {
+        InterpolatorRegistry { methods: Vec::new() }
+    }
+
+    /// Adds a method, or replaces the one already registered under `name`.
+    pub fn register(&mut self, name: &str, constructor: Constructor) {
+        match self.methods.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = constructor,
+            None => self.methods.push((name.to_string(), constructor)),
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.methods.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.methods.iter().any(|(n, _)| n == name)
+    }
+
+    /// Fits the method called `name` through `points`, or `None` if there is
+    /// no such method.
+    pub fn build(&self, name: &str, points: &[Point]) -> Option<Result<Box<dyn Interpolator>, SplineError>> {
+        self.methods
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, constructor)| constructor(points))
+    }
+}
+
+/// The three spline types as `linear`, `quadratic` and `cubic`, then
+/// `polynomial`, the single polynomial through every point.
+impl Default for InterpolatorRegistry {
+    fn default() -> Self {
+        let mut registry = InterpolatorRegistry::empty();
+        for (name, spline_type) in [
+            ("linear", SplineType::Linear),
+            ("quadratic", SplineType::Quadratic),
+            ("cubic", SplineType::Cubic),
+        ] {
+            registry.register(
+                name,
+                Box::new(move |points| Ok(Box::new(Spline::try_new(points, spline_type)?))),
+            );
+        }
+        registry.register(
+            "polynomial",
+            Box::new(|points| Ok(Box::new(PolynomialInterpolant::new(&sorted_knots(points)?)))),
+        );
+        registry
+    }
+}
@@ -0,0 +1,109 @@
+use crate::segment::derivative;
+use crate::{Point, Segment, Spline, SplineError, SplineType};
+
+// Samples taken inside each piece, besides its knots, for the refit.
+const SAMPLES_PER_SEGMENT: usize = 8;
+
+impl Spline {
+    /// The x → y mapping turned around into y → x, for looking up the input
+    /// that produces a given output.
+    ///
+    /// A linear spline inverts exactly, by swapping its knots. Other types
+    /// are sampled at their knots and at points inside each piece, and a
+    /// cubic spline is fitted through the swapped samples; it reproduces the
+    /// knots exactly and the rest of the curve closely. Either way the
+    /// inverse covers the curve's y-range over `domain()`.
+    ///
+    /// Fails with [`SplineError::NotMonotone`] at the first x where the
+    /// curve turns back. A curve that is only flat at a point still inverts,
+    /// but the inverse is steep there.
+    pub fn invert(&self) -> Result<Spline, SplineError> {
+        self.monotone_direction()?;
+
+        if self.spline_type() == SplineType::Linear {
+            let swapped: Vec<Point> = self.points().iter().map(|p| Point::new(p.y, p.x)).collect();
+            return Spline::try_new(&swapped, SplineType::Linear);
+        }
+
+        let mut samples: Vec<Point> = self
+            .segments()
+            .flat_map(|segment| {
+                (0..=SAMPLES_PER_SEGMENT).map(move |i| {
+                    let x = segment.x0 + (segment.x1 - segment.x0) * i as f32 / (SAMPLES_PER_SEGMENT + 1) as f32;
+                    Point::new(segment.eval(x), x)
+                })
+            })
+            .collect();
+        let last = self.points()[self.points().len() - 1];
+        samples.push(Point::new(last.y, last.x));
+        Spline::try_new(&samples, SplineType::Cubic)
+    }
+
+    // 1 if the curve rises across its domain, -1 if it falls, or the first
+    // x where it turns back.
+    pub(crate) fn monotone_direction(&self) -> Result<f32, SplineError> {
+        let mut direction = 0.0;
+        for segment in self.segments() {
+            let coeffs = derivative(segment.coeffs);
+            let slope = Segment {
+                coeffs: &coeffs,
+                ..segment
+            };
+            let mut breaks = vec![segment.x0];
+            breaks.extend(slope.roots().into_iter().filter(|&x| x > segment.x0 && x < segment.x1));
+            breaks.push(segment.x1);
+            // Between roots of the slope the piece is monotone, so its rise
+            // over each interval tells which way it goes.
+            for w in breaks.windows(2).filter(|w| w[1] > w[0]) {
+                let rise = segment.eval(w[1]) - segment.eval(w[0]);
+                if rise == 0.0 || rise.signum() == -direction {
+                    return Err(SplineError::NotMonotone(w[0]));
+                }
+                direction = rise.signum();
+            }
+        }
+        Ok(direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_inverts_exactly() {
+        let points = [Point::new(0.0, 1.0), Point::new(2.0, 3.0), Point::new(3.0, 7.0)];
+        let inverse = Spline::new(&points, SplineType::Linear).invert().unwrap();
+        assert_eq!(inverse.spline_type(), SplineType::Linear);
+        assert_eq!(inverse.evaluate(2.0), 1.0);
+        assert_eq!(inverse.evaluate(5.0), 2.5);
+    }
+
+    #[test]
+    fn cubic_inverse_undoes_the_curve() {
+        let points = [
+            Point::new(0.0, 3.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.5, 0.5),
+            Point::new(4.0, 0.0),
+        ];
+        let spline = Spline::new(&points, SplineType::Cubic);
+        let inverse = spline.invert().unwrap();
+        for p in &points {
+            assert!((inverse.evaluate(p.y) - p.x).abs() < 1e-4, "knot {:?}", p);
+        }
+        for i in 0..=40 {
+            let x = i as f32 * 0.1;
+            let back = inverse.evaluate(spline.evaluate(x));
+            assert!((back - x).abs() < 0.02, "{} came back as {}", x, back);
+        }
+    }
+
+    #[test]
+    fn turning_back_is_an_error() {
+        let hill = [Point::new(0.0, 0.0), Point::new(1.0, 2.0), Point::new(2.0, 0.0)];
+        assert_eq!(Spline::new(&hill, SplineType::Linear).invert().err(), Some(SplineError::NotMonotone(1.0)));
+        let plateau = [Point::new(0.0, 0.0), Point::new(1.0, 1.0), Point::new(2.0, 1.0)];
+        assert_eq!(Spline::new(&plateau, SplineType::Linear).invert().err(), Some(SplineError::NotMonotone(1.0)));
+    }
+}
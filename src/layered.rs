@@ -0,0 +1,110 @@
+use crate::{Point, Spline, SplineType};
+
+/// A dense curve, such as loaded data, reshaped through a handful of master
+/// points.
+///
+/// Each master sits at a fixed x and holds an offset from the base curve. A
+/// cubic spline through the offsets is added to every base point, so moving
+/// one master bends its neighbourhood smoothly while the base keeps its
+/// fine detail.
+pub struct LayeredCurve {
+    base: Vec<Point>,
+    offset: Spline,
+}
+
+impl LayeredCurve {
+    /// Places `master_count` masters evenly across the base's x-range, all
+    /// with zero offset. Base points sharing an x value keep the first.
+    ///
+    /// Panics if `base` has fewer than 2 distinct x values or
+    /// `master_count < 2`.
+    pub fn new(base: &[Point], master_count: usize) -> Self {
+        assert!(master_count >= 2, "need at least 2 master points");
+        let mut base = base.to_vec();
+        base.sort_by(|a, b| a.x.total_cmp(&b.x));
+        base.dedup_by(|a, b| a.x == b.x);
+        assert!(base.len() >= 2, "need at least 2 base points with distinct x");
+
+        let (min_x, max_x) = (base[0].x, base[base.len() - 1].x);
+        let masters: Vec<Point> = (0..master_count)
+            .map(|i| Point::new(min_x + (max_x - min_x) * i as f32 / (master_count - 1) as f32, 0.0))
+            .collect();
+
+        LayeredCurve {
+            base,
+            offset: Spline::new(&masters, SplineType::Cubic),
+        }
+    }
+
+    pub fn base(&self) -> &[Point] {
+        &self.base
+    }
+
+    /// Each master's x and the offset it holds.
+    pub fn offsets(&self) -> &[Point] {
+        self.offset.points()
+    }
+
+    /// The masters where they sit on the edited curve.
+    pub fn masters(&self) -> Vec<Point> {
+        self.offsets()
+            .iter()
+            .map(|m| Point::new(m.x, self.base_at(m.x) + m.y))
+            .collect()
+    }
+
+    /// The curve with its base and masters moved through `f`, such as a
+    /// change of axes; `f` must keep x ascending.
+    pub fn map(&self, f: impl Fn(Point) -> Point) -> LayeredCurve {
+        let masters: Vec<Point> = self.masters().into_iter().map(&f).collect();
+        let flat: Vec<Point> = masters.iter().map(|m| Point::new(m.x, 0.0)).collect();
+        let mut curve = LayeredCurve {
+            base: self.base.iter().map(|&p| f(p)).collect(),
+            offset: Spline::new(&flat, SplineType::Cubic),
+        };
+        for (i, m) in masters.iter().enumerate() {
+            curve.set_master(i, m.y);
+        }
+        curve
+    }
+
+    /// Moves master `i` up or down so the edited curve passes through `y` at
+    /// the master's x. An index past the last master, or a non-finite `y`,
+    /// is ignored.
+    pub fn set_master(&mut self, i: usize, y: f32) {
+        let x = match self.offsets().get(i) {
+            Some(master) => master.x,
+            None => return,
+        };
+        // The master keeps its x, so only a non-finite y can be refused.
+        let _ = self.offset.set_point(i, Point::new(x, y - self.base_at(x)));
+    }
+
+    /// Puts every master back to zero offset.
+    pub fn reset(&mut self) {
+        for i in 0..self.offsets().len() {
+            let x = self.offsets()[i].x;
+            self.offset.set_point(i, Point::new(x, 0.0)).expect("a master keeps its x");
+        }
+    }
+
+    /// The base, linearly interpolated between its points, plus the offset.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        self.base_at(x) + self.offset.evaluate(x)
+    }
+
+    /// The base points with the offset added.
+    pub fn points(&self) -> Vec<Point> {
+        self.base
+            .iter()
+            .map(|p| Point::new(p.x, p.y + self.offset.evaluate(p.x)))
+            .collect()
+    }
+
+    fn base_at(&self, x: f32) -> f32 {
+        let i = self.base.partition_point(|p| p.x < x).clamp(1, self.base.len() - 1);
+        let (a, b) = (self.base[i - 1], self.base[i]);
+        let t = ((x - a.x) / (b.x - a.x)).clamp(0.0, 1.0);
+        a.y + (b.y - a.y) * t
+    }
+}
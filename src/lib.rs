@@ -0,0 +1,75 @@
+mod angle;
+mod annotation;
+mod axis;
+mod bake;
+mod batch;
+mod boolean;
+mod camera;
+mod contour;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod data;
+mod distribution;
+mod encoding;
+mod fitting;
+mod glyph;
+mod gradient;
+mod hash;
+mod hierarchical;
+mod interpolator;
+mod inverse;
+mod layered;
+mod limits;
+mod memo;
+mod metrics;
+mod noise;
+mod piecewise;
+mod polynomial;
+mod presets;
+mod quantize;
+mod random;
+mod scaled;
+mod segment;
+mod shape;
+mod share;
+mod smoothing;
+mod spectrum;
+mod spline;
+mod streaming;
+mod tag;
+mod track;
+
+pub use angle::{wrap_angle, AngleSpline};
+pub use annotation::{Anchor, Annotation};
+pub use axis::{Axis, Scale};
+pub use bake::BakedSpline;
+pub use boolean::{inside_polygon, polygon_area, BooleanOp};
+pub use camera::{CameraPath, Keyframe};
+pub use contour::Contour;
+pub use data::{load_csv, subsample, CsvData};
+pub use distribution::Distribution;
+pub use encoding::{DecodeError, Precision};
+pub use glyph::{EmSquare, Glyph};
+pub use gradient::{ColorSpace, ColorStop, Gradient};
+pub use hierarchical::{detail_offset, DetailLayer, HierarchicalCurve};
+pub use interpolator::{Constructor, Interpolator, InterpolatorRegistry};
+pub use layered::LayeredCurve;
+pub use limits::Limits;
+pub use memo::Memo;
+pub use metrics::{error_metrics, max_error, runge, ErrorMetrics};
+pub use noise::Noise;
+pub use piecewise::PiecewisePolynomial;
+pub use polynomial::PolynomialInterpolant;
+pub use presets::Preset;
+pub use quantize::Quantizer;
+pub use random::{random_points, Rng};
+pub use scaled::ScaledSpline;
+pub use segment::{Segment, Steepest};
+pub use shape::{Shape, ShapeConstraint};
+pub use share::Design;
+pub use smoothing::{RobustFit, SmoothingSpline};
+pub use spectrum::total_harmonic_distortion;
+pub use spline::{Point, Spline, SplineError, SplineType};
+pub use streaming::StreamingSpline;
+pub use tag::PointTag;
+pub use track::Track;
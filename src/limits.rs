@@ -0,0 +1,152 @@
+use crate::{Point, Spline, SplineType};
+
+/// Bounds on a spline's output, such as non-negativity for a brightness or
+/// the unit range for an opacity. Either side may be open.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Limits {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl Limits {
+    /// Panics if both bounds are set and `min > max`, or either is NaN.
+    pub fn new(min: Option<f32>, max: Option<f32>) -> Self {
+        assert!(
+            !min.is_some_and(f32::is_nan) && !max.is_some_and(f32::is_nan),
+            "limits must not be NaN"
+        );
+        if let (Some(min), Some(max)) = (min, max) {
+            assert!(min <= max, "lower limit {} is above upper limit {}", min, max);
+        }
+        Limits { min, max }
+    }
+
+    /// `[0, ∞)`.
+    pub fn non_negative() -> Self {
+        Limits::new(Some(0.0), None)
+    }
+
+    /// `[0, 1]`.
+    pub fn unit() -> Self {
+        Limits::new(Some(0.0), Some(1.0))
+    }
+
+    pub fn contains(&self, y: f32) -> bool {
+        self.min.is_none_or(|min| y >= min) && self.max.is_none_or(|max| y <= max)
+    }
+
+    /// Clamps an evaluated value into the bounds. This keeps the output in
+    /// range but flattens overshoot into corners where the curve meets a limit.
+    pub fn clamp(&self, y: f32) -> f32 {
+        let y = self.min.map_or(y, |min| y.max(min));
+        self.max.map_or(y, |max| y.min(max))
+    }
+
+    /// A spline that stays within the bounds everywhere, not just after
+    /// clamping. Knots outside the bounds are clamped first.
+    ///
+    /// Linear splines only need their knots clamped. Otherwise each piece is
+    /// rebuilt as the cubic Hermite with the original curve's slopes at its
+    /// knots, limited so that the piece's Bézier control points
+    /// `y₀ + h·m₀/3` and `y₁ - h·m₁/3` are in bounds; a cubic never leaves the
+    /// hull of its control points. Pieces that need no limiting come out
+    /// unchanged, and the result is always a cubic spline.
+    pub fn project(&self, spline: &Spline) -> Spline {
+        let points: Vec<Point> = spline.points().iter().map(|p| Point::new(p.x, self.clamp(p.y))).collect();
+        if spline.spline_type() == SplineType::Linear {
+            return Spline::new(&points, SplineType::Linear);
+        }
+
+        let (lo, hi) = (self.min.unwrap_or(f32::NEG_INFINITY), self.max.unwrap_or(f32::INFINITY));
+        let n = points.len();
+        let h: Vec<f32> = points.windows(2).map(|w| w[1].x - w[0].x).collect();
+        let last = spline.segment(n - 2);
+
+        let slopes: Vec<f32> = (0..n)
+            .map(|i| {
+                let slope = if i < n - 1 { spline.segment(i).coefficient(1) } else { last.derivative(last.x1) };
+                let y = points[i].y;
+                // Both ranges contain 0 because y is already in bounds.
+                let (mut min, mut max) = (f32::NEG_INFINITY, f32::INFINITY);
+                if i < n - 1 {
+                    min = min.max(3.0 * (lo - y) / h[i]);
+                    max = max.min(3.0 * (hi - y) / h[i]);
+                }
+                if i > 0 {
+                    min = min.max(3.0 * (y - hi) / h[i - 1]);
+                    max = max.min(3.0 * (y - lo) / h[i - 1]);
+                }
+                slope.clamp(min, max)
+            })
+            .collect();
+
+        let pieces = (0..n - 1)
+            .map(|i| {
+                let delta = (points[i + 1].y - points[i].y) / h[i];
+                let (m0, m1) = (slopes[i], slopes[i + 1]);
+                vec![
+                    points[i].y,
+                    m0,
+                    (3.0 * delta - 2.0 * m0 - m1) / h[i],
+                    (m0 + m1 - 2.0 * delta) / (h[i] * h[i]),
+                ]
+            })
+            .collect();
+        Spline::from_pieces(points, SplineType::Cubic, pieces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(spline: &Spline) -> impl Iterator<Item = f32> + '_ {
+        let (x0, x1) = spline.domain();
+        (0..=400).map(move |i| x0 + (x1 - x0) * i as f32 / 400.0)
+    }
+
+    #[test]
+    fn projected_cubic_stays_within_limits() {
+        // A step makes the natural cubic ring above 1 and below 0.
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.5, 1.0),
+            Point::new(3.0, 1.0),
+            Point::new(3.5, 0.0),
+            Point::new(5.0, 0.0),
+        ];
+        let limits = Limits::unit();
+        let spline = Spline::new(&points, SplineType::Cubic);
+        assert!(samples(&spline).any(|x| !limits.contains(spline.evaluate(x))));
+
+        let projected = limits.project(&spline);
+        for x in samples(&projected) {
+            let y = projected.evaluate(x);
+            assert!(limits.contains(y), "{} at x = {} is outside the limits", y, x);
+        }
+        assert_eq!(projected.points(), &points[..]);
+    }
+
+    #[test]
+    fn spline_within_limits_projects_to_itself() {
+        let points = [
+            Point::new(0.0, 0.2),
+            Point::new(1.0, 0.4),
+            Point::new(2.0, 0.7),
+            Point::new(3.5, 0.5),
+        ];
+        for spline_type in [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic] {
+            let spline = Spline::new(&points, spline_type);
+            let projected = Limits::new(Some(-1.0), Some(2.0)).project(&spline);
+            for x in samples(&spline) {
+                assert!(
+                    (projected.evaluate(x) - spline.evaluate(x)).abs() < 1e-5,
+                    "{:?} moved at x = {}",
+                    spline_type,
+                    x
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+// Built in, so there is always a complete table to fall back on.
+const ENGLISH: &str = include_str!("../locales/en.toml");
+
+// The strings of one language. Nested tables flatten into dotted keys, so
+// `[hud] seed = "Seed: {seed}"` is looked up as `hud.seed`.
+struct Locale {
+    code: String,
+    name: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    fn parse(code: &str, text: &str) -> Result<Locale, String> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+        let mut strings = HashMap::new();
+        flatten("", &table, &mut strings)?;
+        Ok(Locale {
+            code: code.to_string(),
+            name: strings.remove("name").unwrap_or_else(|| code.to_string()),
+            strings,
+        })
+    }
+}
+
+fn flatten(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) -> Result<(), String> {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::String(text) => {
+                strings.insert(key, text.clone());
+            }
+            toml::Value::Table(table) => flatten(&key, table, strings)?,
+            _ => return Err(format!("`{}` should be a string or a table of strings", key)),
+        }
+    }
+    Ok(())
+}
+
+/// The languages the on-screen text can be shown in, English first, and
+/// the one in use. A string a locale leaves out is shown in English.
+pub struct Locales {
+    locales: Vec<Locale>,
+    current: usize,
+}
+
+impl Locales {
+    /// English plus a locale for each `<code>.toml` in `dir`, which need
+    /// not exist; a file for a language already loaded overrides its
+    /// strings. Files that can't be read are skipped and returned as errors.
+    pub fn load(dir: impl AsRef<Path>) -> (Locales, Vec<String>) {
+        let mut locales = vec![Locale::parse("en", ENGLISH).expect("the built-in English strings are valid")];
+        let mut errors = Vec::new();
+
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let code = path.file_stem().unwrap_or_default().to_string_lossy();
+            let locale = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| Locale::parse(&code, &text));
+            match locale {
+                Ok(locale) => match locales.iter_mut().find(|l| l.code == locale.code) {
+                    Some(loaded) => {
+                        loaded.name = locale.name;
+                        loaded.strings.extend(locale.strings);
+                    }
+                    None => locales.push(locale),
+                },
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        (Locales { locales, current: 0 }, errors)
+    }
+
+    /// Switches to the language with this code, if it was loaded.
+    pub fn select(&mut self, code: &str) -> bool {
+        match self.locales.iter().position(|l| l.code == code) {
+            Some(i) => {
+                self.current = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % self.locales.len();
+    }
+
+    /// The current language's name for itself.
+    pub fn name(&self) -> &str {
+        &self.locales[self.current].name
+    }
+
+    /// The string for `key`, or the key itself where even English lacks it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        [&self.locales[self.current], &self.locales[0]]
+            .iter()
+            .find_map(|l| l.strings.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// The string for `key` with each `{name}` in it replaced by its value
+    /// in `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter().fold(self.get(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+    }
+}
@@ -1,278 +1,4098 @@
+mod action;
+mod cli;
+mod config;
+mod locale;
+mod output;
+mod palette;
+mod remote;
+
+#[cfg(test)]
+mod golden;
+
 use nannou::prelude::*;
 use nannou::color::rgb_u32;
+use nannou::glam::{Mat4, Vec4};
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use action::{Action, Scope};
+use cli::{AxisOptions, Options};
+use config::{Binding, Config};
+use locale::Locales;
+use output::LedOutput;
+use palette::Palette;
+use remote::{RemoteServer, Request};
+use serde_json::{json, Map, Value};
+use splines::{
+    detail_offset, error_metrics, inside_polygon, load_csv, max_error, polygon_area, random_points,
+    runge, subsample, total_harmonic_distortion, wrap_angle, Anchor, AngleSpline, Annotation, Axis,
+    BooleanOp, CameraPath, ColorSpace, ColorStop, Contour, CsvData, Design, DetailLayer, EmSquare,
+    ErrorMetrics, Glyph, Gradient, Interpolator, InterpolatorRegistry, Keyframe, LayeredCurve,
+    Limits, Memo, Noise, Point, PointTag, PolynomialInterpolant, Preset, Quantizer, Rng, RobustFit,
+    Scale, Segment, Shape, ShapeConstraint, SmoothingSpline, Spline, SplineError, SplineType,
+    StreamingSpline, Track,
+};
+
+struct Model {
+    control_points: Vec<Point>,
+    spline: Option<Spline>,
+    dragging_point: Option<usize>,
+    show_control_points: bool,
+    resolution: usize,
+    current_spline_type: SplineType,
+    // The interpolation methods the type cycle offers, the spline types first.
+    interpolators: InterpolatorRegistry,
+    // Some while a registered method other than a spline type draws the
+    // curve, with its fit; everything else keeps using the spline.
+    custom_method: Option<String>,
+    custom_curve: Option<Box<dyn Interpolator>>,
+    led_output: Option<LedOutput>,
+    led_enabled: bool,
+    seed: Option<u64>,
+    random_point_count: usize,
+    preset: Option<Preset>,
+    sample_point_count: usize,
+    runge_point_count: Option<usize>,
+    ground_truth: Option<GroundTruth>,
+    fit_tolerance: f32,
+    smoothing: Option<SmoothingSpline>,
+    // Parallel to the ground truth points when the smoothing fit was robust.
+    outliers: Vec<bool>,
+    show_confidence_band: bool,
+    live: Option<LiveSignal>,
+    // The data file loaded at startup, re-imported whenever it changes.
+    data_watch: Option<DataWatch>,
+    // Some with --serve: WebSocket clients' requests, answered between frames.
+    remote: Option<RemoteServer>,
+    session_path: String,
+    // Data ranges the canvas box from `canvas_extent` spans.
+    x_axis: Axis,
+    y_axis: Axis,
+    // Some while y values are read as angles; holds the y axis to restore.
+    angle_mode: Option<Axis>,
+    // Some while zoomed in on part of the x axis.
+    overview: Option<Overview>,
+    // In y data units.
+    limits: Limits,
+    limit_mode: LimitMode,
+    // In x data units. Violations are shown until the constraint is applied.
+    shape_constraint: Option<ShapeConstraint>,
+    constraint_edge: Option<f32>,
+    // While set, the control points are its masters and drag only vertically.
+    layered: Option<LayeredCurve>,
+    // Offsets over fixed screen x-ranges, kept when the points change. The
+    // last one is the one right-dragging edits.
+    detail_layers: Vec<DetailLayer>,
+    editing_detail: bool,
+    // The active group's points are the control points; the others keep
+    // their own.
+    groups: Vec<Group>,
+    // Whether `groups` are the RGB channels, and the other set of groups with
+    // its active index, set aside while V shows these.
+    channel_mode: bool,
+    parked_groups: Option<(Vec<Group>, usize)>,
+    active_group: usize,
+    // Belong to the active group, like the control points they anchor to.
+    annotations: Vec<Annotation>,
+    // The annotation being typed, placed with Enter.
+    annotation_draft: Option<Annotation>,
+    // Key/value metadata on the active group's points, kept through saves
+    // and exports.
+    tags: Vec<PointTag>,
+    // The point being tagged and the `key:value` typed so far.
+    tag_draft: Option<(usize, String)>,
+    // Some while the command palette is open, taking all keys.
+    palette: Option<Palette>,
+    // The key that starts an annotation or tag also arrives as a character.
+    skip_next_character: bool,
+    // Some while measuring: up to two picked positions, in screen space.
+    ruler: Option<Vec<Point>>,
+    // Some in terrain mode: the steepest grade allowed, in percent.
+    max_grade: Option<f32>,
+    default_max_grade: f32,
+    // Some in quantize mode, stepping what the outputs send.
+    quantizer: Option<Quantizer>,
+    default_quantizer: Quantizer,
+    // Some while previewing noise over the output; in data units.
+    noise: Option<Noise>,
+    noise_in_outputs: bool,
+    // Some in keyboard editing mode: the focused point's index.
+    keyboard_focus: Option<usize>,
+    config: Config,
+    // The on-screen text, in the language picked with Ctrl+L.
+    locale: Locales,
+    gradient: Option<GradientDesigner>,
+    camera: Option<CameraEditor>,
+    glyph: Option<GlyphSketch>,
+    tiling: Option<Tiling>,
+    show_spectrum: bool,
+    easing: Option<Easing>,
+    comparison: Option<Comparison>,
+    status: Option<String>,
+    // Shown in a banner until the points are replaced, for input that had to
+    // be cleaned up or rejected.
+    warning: Option<String>,
+    // Bumped by every key press and click, and by whatever else changes the
+    // points, axes or settings, so what the view derives from the model is
+    // redone on edits rather than every frame. Hovering leaves it alone.
+    revision: u64,
+    frame_cache: FrameCache,
+}
+
+// Two saved sessions overlaid, with B - A plotted along the bottom of the
+// window over the x-range both cover.
+struct Comparison {
+    names: (String, String),
+    a: Spline,
+    b: Spline,
+    overlap: Option<(f32, f32)>,
+    metrics: Option<ErrorMetrics>,
+}
+
+impl Comparison {
+    fn load(path_a: &str, path_b: &str) -> Result<Self, String> {
+        let load_spline = |path: &str| {
+            let design = Design::load(path).map_err(|e| format!("Could not load {}: {}", path, e))?;
+            Spline::try_new(&design.points, design.spline_type).map_err(|e| format!("Could not fit {}: {}", path, e))
+        };
+        let a = load_spline(path_a)?;
+        let b = load_spline(path_b)?;
+
+        let ((a_min, a_max), (b_min, b_max)) = (a.domain(), b.domain());
+        let overlap = Some((a_min.max(b_min), a_max.min(b_max))).filter(|(lo, hi)| lo < hi);
+        let metrics = overlap.map(|(lo, hi)| {
+            let last = (DENSE_SAMPLES - 1) as f32;
+            let samples: Vec<Point> = (0..DENSE_SAMPLES)
+                .map(|i| {
+                    let x = lo + (hi - lo) * i as f32 / last;
+                    Point::new(x, a.evaluate(x))
+                })
+                .collect();
+            error_metrics(&samples, |x| b.evaluate(x))
+        });
+
+        Ok(Comparison {
+            names: (path_a.to_string(), path_b.to_string()),
+            a,
+            b,
+            overlap,
+            metrics,
+        })
+    }
+}
+
+// A named sub-path with its own points and curve.
+struct Group {
+    name: String,
+    points: Vec<Point>,
+    annotations: Vec<Annotation>,
+    tags: Vec<PointTag>,
+    hidden: bool,
+    locked: bool,
+    // Set on channels, which are drawn in their own colour.
+    color: Option<u32>,
+}
+
+impl Group {
+    fn new(number: usize) -> Self {
+        Group {
+            name: format!("path-{}", number),
+            points: Vec::new(),
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            hidden: false,
+            locked: false,
+            color: None,
+        }
+    }
+
+    fn channel(name: &str, color: u32, points: Vec<Point>) -> Self {
+        Group {
+            name: name.to_string(),
+            points,
+            annotations: Vec::new(),
+            tags: Vec::new(),
+            hidden: false,
+            locked: false,
+            color: Some(color),
+        }
+    }
+}
+
+const CHANNELS: [(&str, u32); 3] = [("red", 0xFF5555), ("green", 0x55FF55), ("blue", 0x5599FF)];
+
+// Mouse y sampled over time into a streaming spline, scrolled across the
+// window with the newest sample at the right. The spline's window doubles as
+// the ring buffer the smoothing fit is refitted from on every sample.
+struct LiveSignal {
+    spline: StreamingSpline,
+    last_sample: f32,
+    lambda: f32,
+    smoothing: Option<SmoothingSpline>,
+}
+
+impl LiveSignal {
+    const SAMPLE_INTERVAL: f32 = 0.05;
+    const PIXELS_PER_SECOND: f32 = 200.0;
+    const CAPACITY: usize = 120;
+
+    fn new(now: f32) -> Self {
+        LiveSignal {
+            spline: StreamingSpline::new(LiveSignal::CAPACITY),
+            last_sample: now,
+            // Roughly a quarter-second smoothing window at 20 samples a second.
+            lambda: 0.03,
+            smoothing: None,
+        }
+    }
+
+    fn push(&mut self, p: Point) {
+        self.spline.push(p);
+        self.last_sample = p.x;
+        self.refit();
+    }
+
+    fn refit(&mut self) {
+        self.smoothing = if self.spline.len() >= 3 {
+            let points: Vec<Point> = self.spline.points().collect();
+            SmoothingSpline::fit(&points, self.lambda).ok()
+        } else {
+            None
+        };
+    }
+}
+
+// A dense dataset the control points were sampled from, kept in screen space.
+struct GroundTruth {
+    points: Vec<Point>,
+    units: DataUnits,
+}
+
+// How screen-space errors convert back into the dataset's own y units.
+#[derive(Clone, Copy)]
+enum DataUnits {
+    // A fixed scale, for the built-in curves.
+    PerPixel(f32),
+    // Through the y axis, for loaded data, so a log axis reads right too.
+    YAxis,
+}
+
+fn model(app: &App) -> Model {
+    let options = Options::from_args();
+
+    app.new_window()
+        .size(1600, 1200)
+        .title("Spline Visualization")
+        .view(view)
+        .mouse_pressed(mouse_pressed)
+        .mouse_released(mouse_released)
+        .mouse_moved(mouse_moved)
+        .key_pressed(key_pressed)
+        .received_character(received_character)
+        .build()
+        .unwrap();
+
+    let mut model = initial_model(&options);
+
+    if let Some((a, b)) = &options.compare {
+        match Comparison::load(a, b) {
+            Ok(comparison) => model.comparison = Some(comparison),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    if let Some(address) = &options.serve {
+        match RemoteServer::bind(address) {
+            Ok(server) => {
+                println!("Listening for WebSocket clients on ws://{}", server.address());
+                model.remote = Some(server);
+            }
+            Err(e) => eprintln!("Could not listen on {}: {}", address, e),
+        }
+    }
+
+    if let Some(name) = &options.interpolator {
+        if model.interpolators.contains(name) {
+            select_interpolator(&mut model, name);
+        } else {
+            let names: Vec<&str> = model.interpolators.names().collect();
+            eprintln!("Unknown interpolation method {}; expected one of {}", name, names.join(", "));
+        }
+    }
+
+    if let Some(text) = &options.share {
+        match Design::from_share_string(text) {
+            Ok(design) => load_design(&mut model, design),
+            Err(e) => eprintln!("Could not load share string: {}", e),
+        }
+    } else if let Some(path) = &options.data {
+        match load_data_file(app.window_rect(), &mut model, path, false) {
+            Ok(()) => match DataWatch::new(path) {
+                Ok(watch) => model.data_watch = Some(watch),
+                Err(e) => eprintln!("Could not watch {} for changes: {}", path, e),
+            },
+            Err(e) => eprintln!("Could not load {}: {}", path, e),
+        }
+    } else if let Some(seed) = options.seed {
+        load_random_points(app.window_rect(), &mut model, seed);
+    } else if Path::new(&options.session).exists() {
+        match Design::load(&options.session) {
+            Ok(design) => load_design(&mut model, design),
+            Err(e) => eprintln!("Could not load session {}: {}", options.session, e),
+        }
+    }
+
+    model
+}
+
+// The model before any window exists or anything is loaded.
+fn initial_model(options: &Options) -> Model {
+    model_with_config(options, load_config(&options.config))
+}
+
+fn model_with_config(options: &Options, config: Config) -> Model {
+    let control_points = vec![
+        Point::new(-300.0, 0.0),
+        Point::new(-150.0, 100.0),
+        Point::new(0.0, -100.0),
+        Point::new(150.0, 100.0),
+        Point::new(300.0, 0.0),
+    ];
+
+    let current_spline_type = SplineType::Cubic;
+
+    let spline = if control_points.len() >= 2 {
+        Some(Spline::new(&control_points, current_spline_type))
+    } else {
+        None
+    };
+
+    let led_output = open_led_output(options);
+    let led_enabled = led_output.is_some();
+    let locale = load_locales(&config);
+
+    Model {
+        control_points,
+        spline,
+        dragging_point: None,
+        show_control_points: true,
+        resolution: 400,
+        current_spline_type,
+        interpolators: INTERPOLATORS.with(|cell| cell.borrow_mut().take()).unwrap_or_default(),
+        custom_method: None,
+        custom_curve: None,
+        led_output,
+        led_enabled,
+        seed: None,
+        random_point_count: options.random_points.max(2),
+        preset: None,
+        sample_point_count: options.sample_points.max(2),
+        runge_point_count: None,
+        ground_truth: None,
+        fit_tolerance: options.fit_tolerance,
+        smoothing: None,
+        outliers: Vec::new(),
+        show_confidence_band: true,
+        live: None,
+        data_watch: None,
+        remote: None,
+        session_path: options.session.clone(),
+        x_axis: axis_from_options(&options.x_axis, "x"),
+        y_axis: axis_from_options(&options.y_axis, "y"),
+        angle_mode: None,
+        overview: None,
+        limits: Limits::new(options.limits.0, options.limits.1),
+        limit_mode: LimitMode::Off,
+        shape_constraint: None,
+        constraint_edge: None,
+        layered: None,
+        detail_layers: Vec::new(),
+        editing_detail: false,
+        groups: vec![Group::new(1)],
+        channel_mode: false,
+        parked_groups: None,
+        active_group: 0,
+        annotations: Vec::new(),
+        annotation_draft: None,
+        tags: Vec::new(),
+        tag_draft: None,
+        palette: None,
+        skip_next_character: false,
+        ruler: None,
+        max_grade: None,
+        default_max_grade: options.max_grade.abs(),
+        quantizer: None,
+        default_quantizer: Quantizer::new(8),
+        noise: None,
+        noise_in_outputs: false,
+        keyboard_focus: None,
+        config,
+        locale,
+        gradient: None,
+        camera: None,
+        glyph: None,
+        tiling: None,
+        show_spectrum: false,
+        easing: None,
+        comparison: None,
+        status: None,
+        warning: None,
+        revision: 0,
+        frame_cache: FrameCache::default(),
+    }
+}
+
+fn load_config(path: &str) -> Config {
+    Config::load(path).unwrap_or_else(|e| {
+        eprintln!("Could not load config {}, using the defaults: {}", path, e);
+        Config::default()
+    })
+}
+
+fn load_locales(config: &Config) -> Locales {
+    let (mut locales, errors) = Locales::load(&config.locales);
+    for e in errors {
+        eprintln!("Could not load locale {}", e);
+    }
+    if let Some(language) = &config.language {
+        if !locales.select(language) {
+            eprintln!("No {}.toml in {}; showing English", language, config.locales);
+        }
+    }
+    locales
+}
+
+fn axis_from_options(options: &AxisOptions, name: &str) -> Axis {
+    let mut axis = Axis::new(options.range.0, options.range.1);
+    axis.title = options.title.clone();
+    axis.unit = options.unit.clone();
+    if options.log {
+        if axis.supports_log() {
+            axis.scale = Scale::Log;
+        } else {
+            eprintln!("A log {} axis needs a positive range; using a linear one.", name);
+        }
+    }
+    axis
+}
+
+fn open_led_output(options: &Options) -> Option<LedOutput> {
+    let output = if let Some(path) = &options.led_serial {
+        LedOutput::serial(path, options.led_count)
+    } else if let Some(host) = &options.led_sacn {
+        let host = if host == "multicast" { None } else { Some(host.as_str()) };
+        LedOutput::sacn(host, options.sacn_universe, options.led_count)
+    } else {
+        return None;
+    };
+
+    match output {
+        Ok(output) => Some(output),
+        Err(e) => {
+            eprintln!("Could not open LED output: {}", e);
+            None
+        }
+    }
+}
+
+// What the view derives from the model, kept across frames until the next
+// edit or resize, so playback and other animation only redraw it.
+#[derive(Default)]
+struct FrameCache {
+    curve: Memo<FrameKey, Vec<Point>>,
+    noisy_curve: Memo<FrameKey, Vec<Vec2>>,
+    quantized_runs: Memo<FrameKey, Vec<(f32, f32, f32)>>,
+    spectrum: Memo<FrameKey, Option<Vec<f32>>>,
+    easing: Memo<FrameKey, EasePlan>,
+    camera: Memo<FrameKey, Option<CameraPlan>>,
+    shape_fit: Memo<FrameKey, Option<Spline>>,
+    runge_errors: Memo<FrameKey, Vec<(String, f32)>>,
+    ground_truth_error: Memo<FrameKey, ErrorMetrics>,
+}
+
+// The model's revision and the window size.
+type FrameKey = (u64, Vec2);
 
-#[derive(Clone, Copy, Debug)]
-struct Point {
-    x: f32,
-    y: f32,
+fn frame_key(rect: Rect, model: &Model) -> FrameKey {
+    (model.revision, rect.wh())
 }
 
-impl Point {
-    fn new(x: f32, y: f32) -> Self {
-        Point { x, y }
+fn update(app: &App, model: &mut Model, _update: Update) {
+    if let Some(live) = &mut model.live {
+        if app.time - live.last_sample >= LiveSignal::SAMPLE_INTERVAL {
+            live.push(Point::new(app.time, app.mouse.y));
+        }
+    }
+
+    if model.data_watch.as_ref().is_some_and(DataWatch::changed) {
+        reload_data_file(app.window_rect(), model);
+    }
+
+    if model.remote.is_some() {
+        serve_remote(app.window_rect(), model);
+    }
+
+    if model.led_enabled {
+        send_led_frame(app.window_rect(), model);
+    }
+}
+
+fn send_led_frame(rect: Rect, model: &mut Model) {
+    let (led_count, spline) = match (model.led_output.as_ref(), model.spline.as_ref()) {
+        (Some(output), Some(spline)) => (output.led_count(), spline),
+        _ => return,
+    };
+
+    let levels: Vec<u8> = output_samples(rect, model, spline, led_count)
+        .iter()
+        .map(|p| map_range(p.y, rect.bottom(), rect.top(), 0.0, 255.0).clamp(0.0, 255.0) as u8)
+        .collect();
+
+    if let Some(output) = model.led_output.as_mut() {
+        if let Err(e) = output.write_levels(&levels) {
+            eprintln!("LED output failed, disabling: {}", e);
+            model.led_enabled = false;
+        }
+    }
+}
+
+// The control points are kept in the spline's ascending-x order so that an
+// index into one is an index into the other.
+fn rebuild_spline(model: &mut Model) {
+    model.revision += 1;
+    model.control_points.sort_by(|a, b| a.x.total_cmp(&b.x));
+    model.spline = match Spline::try_new(&model.control_points, model.current_spline_type) {
+        Ok(spline) => Some(spline),
+        Err(SplineError::TooFewPoints) => None,
+        Err(e) => {
+            model.warning = Some(model.locale.format("warnings.cannot_fit", &[("error", &e)]));
+            None
+        }
+    };
+    refit_custom_curve(model);
+}
+
+fn refit_custom_curve(model: &mut Model) {
+    model.custom_curve = None;
+    if let Some(name) = &model.custom_method {
+        match model.interpolators.build(name, &model.control_points) {
+            Some(Ok(curve)) => model.custom_curve = Some(curve),
+            Some(Err(SplineError::TooFewPoints)) | None => {}
+            Some(Err(e)) => model.warning = Some(model.locale.format("warnings.cannot_fit", &[("error", &e)])),
+        }
+    }
+}
+
+// The spline types set `current_spline_type`; any other method only takes
+// over drawing the curve.
+fn select_interpolator(model: &mut Model, name: &str) {
+    match [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic]
+        .into_iter()
+        .find(|&t| remote::type_name(t) == name)
+    {
+        Some(spline_type) => {
+            model.current_spline_type = spline_type;
+            model.custom_method = None;
+        }
+        None => model.custom_method = Some(name.to_string()),
+    }
+    rebuild_spline(model);
+}
+
+fn next_interpolator(model: &mut Model) {
+    let names: Vec<String> = model.interpolators.names().map(String::from).collect();
+    let current = match &model.custom_method {
+        Some(name) => name.as_str(),
+        None => remote::type_name(model.current_spline_type),
+    };
+    let next = names.iter().position(|n| n == current).map_or(0, |i| (i + 1) % names.len());
+    if let Some(name) = names.get(next) {
+        select_interpolator(model, name);
+    }
+}
+
+// A registered method's curve, sampled evenly across its domain. Limits,
+// detail layers and noise only apply to the spline types.
+fn custom_samples(curve: &dyn Interpolator, n: usize) -> Vec<Point> {
+    let (min, max) = curve.domain();
+    let last = n.saturating_sub(1).max(1);
+    (0..n)
+        .map(|i| {
+            let x = min + (max - min) * (i as f32 / last as f32);
+            Point::new(x, curve.evaluate(x))
+        })
+        .collect()
+}
+
+const DENSE_SAMPLES: usize = 401;
+
+// The x-range and vertical amplitude generated point sets are laid out in.
+fn canvas_extent(rect: Rect) -> ((f32, f32), f32) {
+    ((rect.left() * 0.85, rect.right() * 0.85), rect.h() * 0.35)
+}
+
+fn screen_to_data(rect: Rect, x_axis: &Axis, y_axis: &Axis, p: Point) -> Point {
+    let ((left, right), amplitude) = canvas_extent(rect);
+    Point::new(
+        x_axis.value_at((p.x - left) / (right - left)),
+        y_axis.value_at((p.y + amplitude) / (2.0 * amplitude)),
+    )
+}
+
+fn data_to_screen(rect: Rect, x_axis: &Axis, y_axis: &Axis, p: Point) -> Point {
+    let ((left, right), amplitude) = canvas_extent(rect);
+    Point::new(
+        left + (right - left) * x_axis.fraction(p.x),
+        -amplitude + 2.0 * amplitude * y_axis.fraction(p.y),
+    )
+}
+
+// Switches an axis between linear and log while keeping every point's data
+// value, so the points and curve move on screen.
+fn toggle_log_scale(rect: Rect, model: &mut Model, horizontal: bool) {
+    if horizontal {
+        zoom_out_fully(rect, model);
+    }
+    let (old_x_axis, old_y_axis) = (model.x_axis.clone(), model.y_axis.clone());
+    // Points off the canvas can sit outside the axis range, so each is
+    // checked as well.
+    let positive = |p: &Point| {
+        let data = screen_to_data(rect, &old_x_axis, &old_y_axis, *p);
+        (if horizontal { data.x } else { data.y }) > 0.0
+    };
+    let annotations = model.annotations.iter().chain(model.groups.iter().flat_map(|g| &g.annotations));
+    let fixed_anchors = annotations.filter_map(|a| match a.anchor {
+        Anchor::Position(p) => Some(p),
+        Anchor::Point(_) => None,
+    });
+    let all_positive = model.control_points.iter().all(positive)
+        && model.groups.iter().all(|g| g.points.iter().all(positive))
+        && model.ground_truth.as_ref().is_none_or(|g| g.points.iter().all(positive))
+        && fixed_anchors.chain(model.ruler.iter().flatten().copied()).all(|p| positive(&p))
+        && (!horizontal || model.detail_layers.iter().all(|l| positive(&Point::new(l.domain().0, 0.0))));
+    let (axis, name) = if horizontal { (&mut model.x_axis, "x") } else { (&mut model.y_axis, "y") };
+    axis.scale = match axis.scale {
+        Scale::Log => Scale::Linear,
+        Scale::Linear if axis.supports_log() && all_positive => Scale::Log,
+        Scale::Linear => {
+            let key = if axis.supports_log() { "status.log_axis_points" } else { "status.log_axis_range" };
+            model.status = Some(model.locale.format(key, &[("axis", &name)]));
+            return;
+        }
+    };
+    reproject(rect, model, &old_x_axis, &old_y_axis);
+}
+
+// Moves everything placed on screen so its data value, read against the old
+// axes, is unchanged against the current ones.
+fn reproject(rect: Rect, model: &mut Model, old_x_axis: &Axis, old_y_axis: &Axis) {
+    let (x_axis, y_axis) = (model.x_axis.clone(), model.y_axis.clone());
+    let reproject = |p: Point| data_to_screen(rect, &x_axis, &y_axis, screen_to_data(rect, old_x_axis, old_y_axis, p));
+    let move_points = |points: &mut [Point]| points.iter_mut().for_each(|p| *p = reproject(*p));
+    let move_annotation = |annotation: &mut Annotation| {
+        if let Anchor::Position(p) = &mut annotation.anchor {
+            *p = reproject(*p);
+        }
+    };
+
+    move_points(&mut model.control_points);
+    if let Some(ground_truth) = &mut model.ground_truth {
+        move_points(&mut ground_truth.points);
+    }
+    let parked = model.parked_groups.iter_mut().flat_map(|(groups, _)| groups);
+    for group in model.groups.iter_mut().chain(parked) {
+        move_points(&mut group.points);
+        group.annotations.iter_mut().for_each(move_annotation);
+    }
+    model.annotations.iter_mut().chain(&mut model.annotation_draft).for_each(move_annotation);
+    if let Some(picks) = &mut model.ruler {
+        move_points(picks);
+    }
+    model.layered = model.layered.as_ref().map(|layered| layered.map(reproject));
+    // The layers keep their offsets in pixels; only their x-ranges move.
+    for layer in &mut model.detail_layers {
+        let (lo, hi) = layer.domain();
+        layer.set_domain((reproject(Point::new(lo, 0.0)).x, reproject(Point::new(hi, 0.0)).x));
+    }
+    model.dragging_point = None;
+    rebuild_spline(model);
+    if model.smoothing.is_some() {
+        let robust = !model.outliers.is_empty();
+        model.smoothing = match fit_smoothing(model, robust) {
+            Ok(smoothing) => Some(smoothing),
+            Err(_) => {
+                model.outliers.clear();
+                None
+            }
+        };
+    }
+}
+
+// The whole x axis while zoomed in, drawn as a strip along the bottom with
+// the zoomed region highlighted. Dragging the region pans.
+struct Overview {
+    full: Axis,
+    // Where the drag took hold, as a fraction of the full axis from the
+    // region's left edge.
+    grab: Option<f32>,
+}
+
+const ZOOM_STEP: f32 = 2.0;
+// The narrowest region, as a fraction of the full axis.
+const MIN_ZOOM_WIDTH: f32 = 1.0 / 1024.0;
+
+fn overview_strip(rect: Rect) -> Rect {
+    Rect::from_w_h(600.0, 50.0).mid_bottom_of(rect.pad(30.0))
+}
+
+// The zoomed region as fractions of the full axis.
+fn zoom_region(model: &Model, overview: &Overview) -> (f32, f32) {
+    (overview.full.fraction(model.x_axis.min), overview.full.fraction(model.x_axis.max))
+}
+
+// Shows `lo..hi` of the full axis, shifted back inside it if need be; the
+// whole of it ends the zoom.
+fn set_zoom_region(rect: Rect, model: &mut Model, full: Axis, lo: f32, hi: f32) {
+    let width = (hi - lo).clamp(MIN_ZOOM_WIDTH, 1.0);
+    let lo = lo.clamp(0.0, 1.0 - width);
+    let (old_x_axis, old_y_axis) = (model.x_axis.clone(), model.y_axis.clone());
+    if width < 1.0 {
+        model.x_axis = Axis {
+            min: full.value_at(lo),
+            max: full.value_at(lo + width),
+            ..full.clone()
+        };
+        model.overview = Some(Overview { full, grab: None });
+    } else {
+        model.x_axis = full;
+        model.overview = None;
+    }
+    reproject(rect, model, &old_x_axis, &old_y_axis);
+}
+
+// Scales the visible x-range by `1 / factor`, keeping the data under the
+// mouse where it is.
+fn zoom(rect: Rect, model: &mut Model, factor: f32, mouse: Vec2) {
+    let full = match &model.overview {
+        Some(overview) => overview.full.clone(),
+        None => model.x_axis.clone(),
+    };
+    let ((left, right), _) = canvas_extent(rect);
+    let at = ((mouse.x - left) / (right - left)).clamp(0.0, 1.0);
+    let (lo, hi) = (full.fraction(model.x_axis.min), full.fraction(model.x_axis.max));
+    let anchor = lo + (hi - lo) * at;
+    let width = (hi - lo) / factor;
+    set_zoom_region(rect, model, full, anchor - width * at, anchor - width * at + width);
+}
+
+fn zoom_out_fully(rect: Rect, model: &mut Model) {
+    if let Some(overview) = model.overview.take() {
+        set_zoom_region(rect, model, overview.full, 0.0, 1.0);
+    }
+}
+
+// A click outside the region centres it there before the drag.
+fn grab_overview(rect: Rect, model: &mut Model, mouse: Vec2) {
+    let overview = match &model.overview {
+        Some(overview) => overview,
+        None => return,
+    };
+    let strip = overview_strip(rect);
+    let at = (mouse.x - strip.left()) / strip.w();
+    let (lo, hi) = zoom_region(model, overview);
+    let grab = if (lo..=hi).contains(&at) {
+        at - lo
+    } else {
+        let full = overview.full.clone();
+        set_zoom_region(rect, model, full, at - (hi - lo) / 2.0, at + (hi - lo) / 2.0);
+        (hi - lo) / 2.0
+    };
+    if let Some(overview) = &mut model.overview {
+        overview.grab = Some(grab);
+    }
+}
+
+fn pan_overview(rect: Rect, model: &mut Model, mouse: Vec2) {
+    let (full, grab, width) = match &model.overview {
+        Some(overview) => match overview.grab {
+            Some(grab) => {
+                let (lo, hi) = zoom_region(model, overview);
+                (overview.full.clone(), grab, hi - lo)
+            }
+            None => return,
+        },
+        None => return,
+    };
+    let strip = overview_strip(rect);
+    let start = (mouse.x - strip.left()) / strip.w() - grab;
+    set_zoom_region(rect, model, full, start, start + width);
+    if let Some(overview) = &mut model.overview {
+        overview.grab = Some(grab);
+    }
+}
+
+// The curve across the full axis, against the same y axis as the canvas.
+fn draw_overview(draw: &Draw, rect: Rect, model: &Model, overview: &Overview) {
+    let strip = overview_strip(rect);
+    draw.rect().xy(strip.xy()).wh(strip.wh()).color(rgba(0.0, 0.0, 0.0, 0.6));
+
+    let to_strip = |p: Point| {
+        let data = screen_to_data(rect, &model.x_axis, &model.y_axis, p);
+        let y = model.y_axis.fraction(data.y).clamp(0.0, 1.0);
+        pt2(strip.left() + strip.w() * overview.full.fraction(data.x), strip.bottom() + strip.h() * y)
+    };
+    if let Some(spline) = &model.spline {
+        let curve: Vec<Vec2> = spline
+            .iter_samples(DENSE_SAMPLES)
+            .map(to_strip)
+            .filter(|p| p.x >= strip.left() && p.x <= strip.right())
+            .collect();
+        draw.polyline().weight(1.5).points(curve).color(rgb_u32(0x00FFAA));
+    }
+    for &p in &model.control_points {
+        let p = to_strip(p);
+        if p.x >= strip.left() && p.x <= strip.right() {
+            draw.ellipse().xy(p).radius(2.0).color(WHITE);
+        }
+    }
+
+    let (lo, hi) = zoom_region(model, overview);
+    let region = Rect::from_x_y_w_h(
+        strip.left() + strip.w() * (lo + hi) / 2.0,
+        strip.y(),
+        (strip.w() * (hi - lo)).max(2.0),
+        strip.h(),
+    );
+    draw.rect().xy(region.xy()).wh(region.wh()).color(rgba(1.0, 1.0, 1.0, 0.15));
+    draw.rect()
+        .xy(region.xy())
+        .wh(region.wh())
+        .no_fill()
+        .stroke(rgb_u32(0xFFCC00))
+        .stroke_weight(1.5);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LimitMode {
+    Off,
+    // Evaluate, then clamp into the limits.
+    Clamp,
+    // Reshape the curve so it stays within the limits.
+    Project,
+}
+
+impl LimitMode {
+    fn next(self) -> Self {
+        match self {
+            LimitMode::Off => LimitMode::Clamp,
+            LimitMode::Clamp => LimitMode::Project,
+            LimitMode::Project => LimitMode::Off,
+        }
+    }
+}
+
+// The limits mapped onto screen y. Bounds that have no place on the axis,
+// such as a negative limit on a log axis, are dropped.
+fn screen_limits(rect: Rect, model: &Model) -> Limits {
+    let to_screen = |y: f32| data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(model.x_axis.min, y)).y;
+    let min = model.limits.min.map(to_screen).filter(|y| !y.is_nan());
+    let max = model.limits.max.map(to_screen).filter(|y| !y.is_nan());
+    if model.y_axis.min > model.y_axis.max {
+        Limits::new(max, min)
+    } else {
+        Limits::new(min, max)
+    }
+}
+
+// The curve as it is shown and sent to the LEDs: the spline plus its detail
+// layers, with the limit mode applied. Projection reshapes the spline alone,
+// so details still go on top of it.
+fn limited_samples(rect: Rect, model: &Model, spline: &Spline, n: usize) -> Vec<Point> {
+    let output = limited_output(rect, model, spline);
+    spline.iter_samples(n).map(|p| Point::new(p.x, output(p.x))).collect()
+}
+
+// The curve as the view draws it, one sample per unit of resolution,
+// recomputed only after an edit.
+fn curve_samples(rect: Rect, model: &Model, spline: &Spline) -> Rc<Vec<Point>> {
+    model.frame_cache.curve.get(frame_key(rect, model), || match &model.custom_curve {
+        Some(curve) => custom_samples(curve.as_ref(), model.resolution + 1),
+        None => limited_samples(rect, model, spline, model.resolution + 1),
+    })
+}
+
+fn limited_output<'a>(rect: Rect, model: &'a Model, spline: &'a Spline) -> Box<dyn Fn(f32) -> f32 + 'a> {
+    let detail = move |x: f32| detail_offset(&model.detail_layers, x);
+    // The Runge demo has no data axis for the limits to refer to.
+    if model.runge_point_count.is_some() {
+        return Box::new(move |x| spline.evaluate(x) + detail(x));
+    }
+    let limits = screen_limits(rect, model);
+    match model.limit_mode {
+        LimitMode::Off => Box::new(move |x| spline.evaluate(x) + detail(x)),
+        LimitMode::Clamp => Box::new(move |x| limits.clamp(spline.evaluate(x) + detail(x))),
+        LimitMode::Project => {
+            let projected = limits.project(spline);
+            Box::new(move |x| projected.evaluate(x) + detail(x))
+        }
+    }
+}
+
+// What the LEDs and remote clients are sent: the curve as shown, with the
+// noise when it is part of the output, stepped while quantizing.
+fn output_samples(rect: Rect, model: &Model, spline: &Spline, n: usize) -> Vec<Point> {
+    let noisy = model.noise.is_some() && model.noise_in_outputs;
+    if model.runge_point_count.is_some() || (model.quantizer.is_none() && !noisy) {
+        return limited_samples(rect, model, spline, n);
+    }
+    let output = limited_output(rect, model, spline);
+    let in_data = data_output(rect, model, &output);
+    let (domain, range) = quantize_extent(rect, model);
+    let to_data = |x: f32| screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(x, 0.0)).x;
+    spline
+        .iter_samples(n)
+        .map(|p| {
+            let y = match model.quantizer {
+                Some(quantizer) => quantizer.apply(&in_data, to_data(p.x), domain, range),
+                None => in_data(to_data(p.x)),
+            };
+            Point::new(p.x, data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(model.x_axis.min, y)).y)
+        })
+        .collect()
+}
+
+// Where the quantizer works, in data units: the curve's x-range, and the
+// y axis the levels span.
+fn quantize_extent(rect: Rect, model: &Model) -> ((f32, f32), (f32, f32)) {
+    let points = data_points(rect, model);
+    let domain = (points[0].x, points[points.len() - 1].x);
+    (domain, (model.y_axis.min, model.y_axis.max))
+}
+
+// `output`, which works on screen positions, read in data units, plus the
+// noise when it is part of the output.
+fn data_output<'a>(rect: Rect, model: &'a Model, output: &'a dyn Fn(f32) -> f32) -> impl Fn(f32) -> f32 + 'a {
+    let noise = model.noise.as_ref().filter(|_| model.noise_in_outputs);
+    move |x| {
+        let screen_x = data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(x, model.y_axis.min)).x;
+        let y = screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(screen_x, output(screen_x))).y;
+        y + noise.map_or(0.0, |noise| noise.at(x))
+    }
+}
+
+// Starts at a twentieth of the y axis in amplitude, with eight bumps across
+// the x axis.
+fn toggle_noise(model: &mut Model) {
+    if model.noise.take().is_some() {
+        return;
+    }
+    let amplitude = (model.y_axis.max - model.y_axis.min).abs() / 20.0;
+    let frequency = 8.0 / (model.x_axis.max - model.x_axis.min).abs();
+    model.noise = Some(Noise::new(model.seed.unwrap_or(1), amplitude, frequency));
+}
+
+fn reseed_noise(noise: &mut Noise) {
+    *noise = Noise::new(noise.seed().wrapping_add(1), noise.amplitude, noise.frequency);
+}
+
+// The shown curve with the noise on top, whether or not the outputs get it.
+fn draw_noisy_curve(draw: &Draw, rect: Rect, model: &Model, spline: &Spline, noise: &Noise) {
+    let points = model.frame_cache.noisy_curve.get(frame_key(rect, model), || {
+        limited_samples(rect, model, spline, DENSE_SAMPLES)
+            .into_iter()
+            .map(|p| {
+                let data = screen_to_data(rect, &model.x_axis, &model.y_axis, p);
+                let noisy = Point::new(data.x, data.y + noise.at(data.x));
+                let p = data_to_screen(rect, &model.x_axis, &model.y_axis, noisy);
+                pt2(p.x, p.y)
+            })
+            .collect()
+    });
+    draw.polyline().weight(1.5).points(points.iter().copied()).color(rgb_u32(0xCC88FF));
+}
+
+fn quantized_runs(rect: Rect, model: &Model, spline: &Spline, quantizer: Quantizer) -> Vec<(f32, f32, f32)> {
+    let output = limited_output(rect, model, spline);
+    let (domain, range) = quantize_extent(rect, model);
+    quantizer.runs(data_output(rect, model, &output), domain, range, DENSE_SAMPLES)
+}
+
+fn toggle_quantize(model: &mut Model) {
+    if let Some(quantizer) = model.quantizer.take() {
+        model.default_quantizer = quantizer;
+    } else {
+        model.quantizer = Some(model.default_quantizer);
+    }
+}
+
+// Steps double or halve, as a sequencer's usually come in powers of two;
+// halving below 2 turns them off.
+fn adjust_quantizer(quantizer: &mut Quantizer, up: bool, steps: bool) {
+    if steps {
+        quantizer.steps = match (quantizer.steps, up) {
+            (None, true) => Some(2),
+            (Some(n), true) => Some((n * 2).min(1024)),
+            (Some(n), false) if n > 2 => Some(n / 2),
+            _ => None,
+        };
+    } else {
+        let levels = if up { quantizer.levels + 1 } else { quantizer.levels - 1 };
+        quantizer.levels = levels.clamp(2, 256);
+    }
+}
+
+fn draw_staircase(draw: &Draw, rect: Rect, model: &Model, runs: &[(f32, f32, f32)]) {
+    let to_screen = |x: f32, y: f32| {
+        let p = data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(x, y));
+        pt2(p.x, p.y)
+    };
+    let corners: Vec<Vec2> = runs
+        .iter()
+        .flat_map(|&(start, end, level)| [to_screen(start, level), to_screen(end, level)])
+        .collect();
+    draw.polyline().weight(2.0).points(corners).color(rgb_u32(0xFFCC00));
+}
+
+// One row per flat run of the staircase, in data units.
+fn export_staircase(rect: Rect, model: &mut Model) {
+    let (spline, quantizer) = match (&model.spline, model.quantizer) {
+        (Some(spline), Some(quantizer)) => (spline, quantizer),
+        _ => return,
+    };
+    let runs = quantized_runs(rect, model, spline, quantizer);
+    let mut csv = String::from("start,end,level\n");
+    for (start, end, level) in &runs {
+        csv.push_str(&format!("{},{},{}\n", start, end, level));
+    }
+
+    let path = "steps.csv";
+    let t = &model.locale;
+    model.status = Some(match std::fs::write(path, csv) {
+        Ok(()) => t.format("status.exported_steps", &[("count", &runs.len()), ("path", &path)]),
+        Err(e) => t.format("status.save_failed", &[("path", &path), ("error", &e)]),
+    });
+}
+
+// The points in data units with their tags, as points.csv (tags joined as
+// `key=value;...`) and points.json, for pipelines that read both.
+fn export_points(rect: Rect, model: &mut Model) {
+    let points = data_points(rect, model);
+    let tags_of = |i: usize| model.tags.iter().filter(move |t| t.point == i);
+
+    let mut csv = String::from("x,y,tags\n");
+    for (i, p) in points.iter().enumerate() {
+        let tags: Vec<String> = tags_of(i).map(|t| format!("{}={}", t.key, t.value)).collect();
+        csv.push_str(&format!("{},{},\"{}\"\n", p.x, p.y, tags.join(";").replace('"', "\"\"")));
+    }
+    let json: Vec<Value> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let tags: Map<String, Value> = tags_of(i).map(|t| (t.key.clone(), json!(t.value))).collect();
+            json!({ "x": p.x, "y": p.y, "tags": tags })
+        })
+        .collect();
+
+    let files = [("points.csv", csv), ("points.json", Value::Array(json).to_string())];
+    for (path, contents) in &files {
+        if let Err(e) = std::fs::write(path, contents) {
+            model.status = Some(model.locale.format("status.save_failed", &[("path", path), ("error", &e)]));
+            return;
+        }
+    }
+    model.status = Some(model.locale.format("status.exported_points", &[("count", &points.len())]));
+}
+
+// Each layer is one level finer than the one before, over the curve's
+// current x-range.
+fn add_detail_layer(model: &mut Model) {
+    let domain = match &model.spline {
+        Some(spline) => spline.domain(),
+        None => return,
+    };
+    let level = 2 + model.detail_layers.len() as u32;
+    if level > 8 {
+        model.status = Some(model.locale.format("status.detail_layer_limit", &[("level", &8)]));
+        return;
+    }
+    model.detail_layers.push(DetailLayer::new(domain, level));
+    model.status = Some(model.locale.format(
+        "status.detail_layer_added",
+        &[("number", &model.detail_layers.len()), ("level", &level)],
+    ));
+}
+
+// Pulls the curve towards the mouse through the newest detail layer only.
+fn drag_detail(model: &mut Model, pos: Vec2) {
+    let current = match &model.spline {
+        Some(spline) => spline.evaluate(pos.x) + detail_offset(&model.detail_layers, pos.x),
+        None => return,
+    };
+    if let Some(layer) = model.detail_layers.last_mut() {
+        if layer.enabled {
+            layer.nudge(pos.x, pos.y - current);
+            model.revision += 1;
+        }
+    }
+}
+
+fn toggle_detail_layer(model: &mut Model, i: usize) {
+    if let Some(layer) = model.detail_layers.get_mut(i) {
+        layer.enabled = !layer.enabled;
+    }
+}
+
+fn describe_limits(limits: &Limits) -> String {
+    let bound = |value: Option<f32>, open: &str| value.map_or(open.to_string(), |v| v.to_string());
+    format!(
+        "{}{}, {}{}",
+        if limits.min.is_some() { "[" } else { "(" },
+        bound(limits.min, "-∞"),
+        bound(limits.max, "∞"),
+        if limits.max.is_some() { "]" } else { ")" },
+    )
+}
+
+fn cycle_shape_constraint(model: &mut Model) {
+    let shape = match model.shape_constraint.map(|c| c.shape) {
+        None => Some(Shape::Increasing),
+        Some(Shape::Increasing) => Some(Shape::Decreasing),
+        Some(Shape::Decreasing) => Some(Shape::Convex),
+        Some(Shape::Convex) => Some(Shape::Concave),
+        Some(Shape::Concave) => None,
+    };
+    let (min_x, max_x) = model
+        .shape_constraint
+        .map_or((model.x_axis.min, model.x_axis.max), |c| (c.min_x, c.max_x));
+    model.shape_constraint = shape.map(|shape| ShapeConstraint::new(shape, min_x, max_x));
+}
+
+// The first press marks one end of the constraint's range and the second
+// the other; with no constraint the marks are kept for the next one.
+fn mark_constraint_edge(rect: Rect, model: &mut Model, mouse: Vec2) {
+    let x = screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(mouse.x, mouse.y)).x;
+    match model.constraint_edge.take() {
+        None => model.constraint_edge = Some(x),
+        Some(edge) => {
+            let shape = model.shape_constraint.map_or(Shape::Increasing, |c| c.shape);
+            model.shape_constraint = Some(ShapeConstraint::new(shape, edge, x));
+        }
+    }
+}
+
+// Replaces the points with the constrained fit's knots and the curve with
+// the fit, a cubic, until the points are next edited. The fit is redone on
+// screen, where it is the same curve as in data units while both axes are
+// linear.
+fn apply_shape_constraint(rect: Rect, model: &mut Model) {
+    let constraint = match model.shape_constraint {
+        Some(constraint) => constraint,
+        None => return,
+    };
+    if model.x_axis.scale == Scale::Log || model.y_axis.scale == Scale::Log {
+        model.status = Some(model.locale.get("status.shape_needs_linear").to_string());
+        return;
+    }
+    let screen_x = |x: f32| data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(x, model.y_axis.min)).x;
+    let on_screen = ShapeConstraint::new(constraint.shape, screen_x(constraint.min_x), screen_x(constraint.max_x));
+    match Spline::fit_shape_constrained(&model.control_points, &[on_screen]) {
+        Ok(fitted) => {
+            model.control_points = fitted.points().to_vec();
+            model.current_spline_type = SplineType::Cubic;
+            model.custom_method = None;
+            model.custom_curve = None;
+            model.dragging_point = None;
+            model.spline = Some(fitted);
+        }
+        Err(e) => model.warning = Some(model.locale.format("warnings.cannot_fit", &[("error", &e)])),
+    }
+}
+
+// Shape constraints are stated in data units, where convexity survives a
+// log axis differently from how it looks on screen.
+fn data_points(rect: Rect, model: &Model) -> Vec<Point> {
+    model
+        .control_points
+        .iter()
+        .map(|&p| screen_to_data(rect, &model.x_axis, &model.y_axis, p))
+        .collect()
+}
+
+// Points stay where they are on screen; only the y axis they're read
+// against changes.
+fn toggle_angle_mode(model: &mut Model) {
+    match model.angle_mode.take() {
+        Some(y_axis) => model.y_axis = y_axis,
+        None => {
+            let mut angle_axis = Axis::new(-PI, PI);
+            angle_axis.title = Some("angle".to_string());
+            angle_axis.unit = Some("rad".to_string());
+            model.angle_mode = Some(std::mem::replace(&mut model.y_axis, angle_axis));
+        }
+    }
+}
+
+// Replaces the point set and forgets where the previous one came from; the
+// loaders below then record their own source.
+fn set_control_points(model: &mut Model, points: Vec<Point>) {
+    model.control_points = points;
+    model.dragging_point = None;
+    model.seed = None;
+    model.preset = None;
+    model.runge_point_count = None;
+    model.ground_truth = None;
+    model.layered = None;
+    model.annotations.retain(|a| matches!(a.anchor, Anchor::Position(_)));
+    model.tags.clear();
+    model.smoothing = None;
+    model.outliers.clear();
+    model.status = None;
+    model.warning = None;
+    rebuild_spline(model);
+}
+
+fn load_design(model: &mut Model, design: Design) {
+    model.current_spline_type = design.spline_type;
+    model.custom_method = None;
+    set_control_points(model, design.points);
+    model.annotations = design.annotations;
+    model.tags = design.tags;
+}
+
+// Parks the control points in the active group and takes over `i`'s.
+fn switch_group(model: &mut Model, i: usize) {
+    park_active_group(model);
+    open_group(model, i);
+    model.status = Some(model.locale.format("status.editing_group", &[("name", &model.groups[i].name)]));
+}
+
+fn park_active_group(model: &mut Model) {
+    let group = &mut model.groups[model.active_group];
+    group.points = std::mem::take(&mut model.control_points);
+    group.annotations = std::mem::take(&mut model.annotations);
+    group.tags = std::mem::take(&mut model.tags);
+}
+
+fn open_group(model: &mut Model, i: usize) {
+    model.active_group = i;
+    model.control_points = std::mem::take(&mut model.groups[i].points);
+    model.annotations = std::mem::take(&mut model.groups[i].annotations);
+    model.tags = std::mem::take(&mut model.groups[i].tags);
+    model.dragging_point = None;
+    model.layered = None;
+    rebuild_spline(model);
+}
+
+// The channels are a fixed three.
+fn new_group(model: &mut Model) {
+    if is_channel_mode(model) {
+        model.status = Some(model.locale.get("status.channels_full").to_string());
+        return;
+    }
+    model.groups.push(Group::new(model.groups.len() + 1));
+    switch_group(model, model.groups.len() - 1);
+}
+
+// Swaps the groups for red, green and blue channels over the same x-range
+// and back. Neither set is lost: the first time, the channels start as the
+// identity diagonal, and after that each set comes back as it was left.
+fn toggle_channels(rect: Rect, model: &mut Model) {
+    park_active_group(model);
+    let (groups, active) = model.parked_groups.take().unwrap_or_else(|| {
+        let ((left, right), amplitude) = canvas_extent(rect);
+        let identity = vec![Point::new(left, -amplitude), Point::new(right, amplitude)];
+        let channels = CHANNELS
+            .iter()
+            .map(|&(name, color)| Group::channel(name, color, identity.clone()))
+            .collect();
+        (channels, 0)
+    });
+    let left = std::mem::replace(&mut model.groups, groups);
+    model.parked_groups = Some((left, model.active_group));
+    model.channel_mode = !model.channel_mode;
+    open_group(model, active);
+    let key = if model.channel_mode { "status.channels_on" } else { "status.channels_off" };
+    model.status = Some(model.locale.get(key).to_string());
+}
+
+fn is_channel_mode(model: &Model) -> bool {
+    model.channel_mode
+}
+
+fn channel_tab(rect: Rect, i: usize) -> Rect {
+    let ((_, right), amplitude) = canvas_extent(rect);
+    Rect::from_x_y_w_h(right - 40.0 - (CHANNELS.len() - 1 - i) as f32 * 90.0, amplitude + 40.0, 80.0, 26.0)
+}
+
+fn channel_tab_at(rect: Rect, model: &Model, p: Vec2) -> Option<usize> {
+    if !is_channel_mode(model) {
+        return None;
+    }
+    (0..model.groups.len()).find(|&i| channel_tab(rect, i).contains(p))
+}
+
+fn draw_channel_tabs(draw: &Draw, rect: Rect, model: &Model) {
+    for (i, group) in model.groups.iter().enumerate() {
+        let tab = channel_tab(rect, i);
+        let color = rgb_u32(group.color.unwrap_or(0xFFFFFF));
+        if i == model.active_group {
+            draw.rect().xy(tab.xy()).wh(tab.wh()).color(color);
+            draw.text(&group.name).xy(tab.xy()).color(BLACK).font_size(14);
+        } else {
+            draw.rect().xy(tab.xy()).wh(tab.wh()).no_fill().stroke(color).stroke_weight(1.5);
+            draw.text(&group.name).xy(tab.xy()).color(color).font_size(14);
+        }
+    }
+}
+
+// One row per sample across the shared x-range, in data units, with a
+// column per channel.
+fn export_channels(rect: Rect, model: &mut Model) {
+    const ROWS: usize = 256;
+    let ((left, right), _) = canvas_extent(rect);
+    let active = model.active_group;
+    let splines: Vec<Option<Spline>> = model
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(i, g)| {
+            let points = if i == active { &model.control_points } else { &g.points };
+            Spline::try_new(points, model.current_spline_type).ok()
+        })
+        .collect();
+
+    let mut csv = format!(
+        "x,{}\n",
+        model.groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>().join(",")
+    );
+    for i in 0..ROWS {
+        let x = left + (right - left) * i as f32 / (ROWS - 1) as f32;
+        let data_x = screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(x, 0.0)).x;
+        let values: Vec<String> = splines
+            .iter()
+            .map(|spline| match spline {
+                Some(spline) => {
+                    let y = spline.evaluate(x);
+                    format!("{}", screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(x, y)).y)
+                }
+                None => String::new(),
+            })
+            .collect();
+        csv.push_str(&format!("{},{}\n", data_x, values.join(",")));
+    }
+
+    let path = "channels.csv";
+    let t = &model.locale;
+    model.status = Some(match std::fs::write(path, csv) {
+        Ok(()) => t.format("status.exported_channels", &[("count", &model.groups.len()), ("path", &path)]),
+        Err(e) => t.format("status.save_failed", &[("path", &path), ("error", &e)]),
+    });
+}
+
+// Colour stops along a strip across the canvas, at positions from 0 to 1,
+// interpolated with the current spline type.
+struct GradientDesigner {
+    stops: Vec<ColorStop>,
+    space: ColorSpace,
+    selected: Option<usize>,
+    dragging: bool,
+    // Hex digits typed for the selected stop, applied with Enter.
+    hex: String,
+}
+
+impl GradientDesigner {
+    fn new() -> Self {
+        GradientDesigner {
+            stops: vec![
+                ColorStop { position: 0.0, color: [0.1, 0.2, 0.9] },
+                ColorStop { position: 1.0, color: [1.0, 0.8, 0.1] },
+            ],
+            space: ColorSpace::Oklab,
+            selected: None,
+            dragging: false,
+            hex: String::new(),
+        }
+    }
+
+    fn gradient(&self, spline_type: SplineType) -> Option<Gradient> {
+        let mut stops = self.stops.clone();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        Gradient::try_new(&stops, self.space, spline_type).ok()
+    }
+}
+
+fn gradient_strip(rect: Rect) -> Rect {
+    let ((left, right), _) = canvas_extent(rect);
+    Rect::from_x_y_w_h((left + right) / 2.0, 0.0, right - left, 80.0)
+}
+
+fn gradient_position(rect: Rect, x: f32) -> f32 {
+    let strip = gradient_strip(rect);
+    ((x - strip.left()) / strip.w()).clamp(0.0, 1.0)
+}
+
+// Selects the stop under the mouse, or adds one there in the colour the
+// gradient already has, unless the click is off the strip.
+fn pick_gradient_stop(rect: Rect, model: &mut Model, mouse: Vec2) {
+    let spline_type = model.current_spline_type;
+    let designer = match &mut model.gradient {
+        Some(designer) => designer,
+        None => return,
+    };
+    let strip = gradient_strip(rect);
+    if (mouse.y - strip.y()).abs() > strip.h() / 2.0 + 30.0 {
+        designer.selected = None;
+        return;
+    }
+    let position = gradient_position(rect, mouse.x);
+    let near = designer
+        .stops
+        .iter()
+        .position(|s| ((s.position - position) * strip.w()).abs() < 10.0);
+    let i = match near {
+        Some(i) => i,
+        None => {
+            let color = match designer.gradient(spline_type) {
+                Some(gradient) => gradient.color_at(position),
+                None => [1.0; 3],
+            };
+            designer.stops.push(ColorStop { position, color });
+            designer.stops.len() - 1
+        }
+    };
+    designer.selected = Some(i);
+    designer.dragging = true;
+    designer.hex.clear();
+}
+
+fn drag_gradient_stop(rect: Rect, designer: &mut GradientDesigner, x: f32) {
+    if let (true, Some(i)) = (designer.dragging, designer.selected) {
+        let position = gradient_position(rect, x);
+        // Two stops at the same position would leave no gradient to draw.
+        if designer.stops.iter().enumerate().all(|(k, s)| k == i || s.position != position) {
+            designer.stops[i].position = position;
+        }
+    }
+}
+
+fn parse_hex_color(hex: &str) -> Option<[f32; 3]> {
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |k: usize| u8::from_str_radix(&hex[2 * k..2 * k + 2], 16).ok();
+    Some([channel(0)?, channel(1)?, channel(2)?].map(|c| c as f32 / 255.0))
+}
+
+fn edit_gradient(model: &mut Model, action: Action) {
+    let spline_type = model.current_spline_type;
+    let designer = match &mut model.gradient {
+        Some(designer) => designer,
+        None => return,
+    };
+    match action {
+        Action::GradientDesigner | Action::LeaveMode => model.gradient = None,
+        Action::CommandPalette => model.palette = Some(Palette::new()),
+        Action::NextColorSpace => {
+            designer.space = match designer.space {
+                ColorSpace::Srgb => ColorSpace::LinearRgb,
+                ColorSpace::LinearRgb => ColorSpace::Oklab,
+                ColorSpace::Oklab => ColorSpace::Srgb,
+            }
+        }
+        Action::SetStopColor => {
+            if let Some(i) = designer.selected {
+                match parse_hex_color(&designer.hex) {
+                    Some(color) => designer.stops[i].color = color,
+                    None => {
+                        model.status = Some(model.locale.format("status.not_hex_color", &[("text", &designer.hex)]))
+                    }
+                }
+                designer.hex.clear();
+            }
+        }
+        Action::DeleteSelected => {
+            if let Some(i) = designer.selected.filter(|_| designer.stops.len() > 2) {
+                designer.stops.remove(i);
+                designer.selected = None;
+            }
+        }
+        Action::Export => {
+            if let Some(gradient) = designer.gradient(spline_type) {
+                model.status = Some(export_gradient(&model.locale, &gradient));
+            }
+        }
+        // With a stop selected, digits are typed into its colour instead.
+        Action::Linear | Action::Quadratic | Action::Cubic if designer.selected.is_none() => {
+            set_spline_type(model, action)
+        }
+        _ => {}
+    }
+}
+
+fn set_spline_type(model: &mut Model, action: Action) {
+    model.current_spline_type = match action {
+        Action::Linear => SplineType::Linear,
+        Action::Quadratic => SplineType::Quadratic,
+        _ => SplineType::Cubic,
+    };
+    rebuild_spline(model);
+}
+
+// A 256-pixel PNG ramp, plus the CSS string on stdout and the clipboard.
+fn export_gradient(t: &Locales, gradient: &Gradient) -> String {
+    const WIDTH: usize = 256;
+    const HEIGHT: u32 = 16;
+    let path = "gradient.png";
+    let ramp = gradient.ramp(WIDTH);
+    let image = nannou::image::RgbImage::from_fn(WIDTH as u32, HEIGHT, |x, _| nannou::image::Rgb(ramp[x as usize]));
+    if let Err(e) = image.save(path) {
+        return t.format("status.save_failed", &[("path", &path), ("error", &e)]);
+    }
+
+    let css = gradient.to_css(16);
+    println!("{}", css);
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(css)) {
+        Ok(()) => t.format("status.gradient_copied", &[("path", &path)]),
+        Err(_) => t.format("status.gradient_printed", &[("path", &path)]),
+    }
+}
+
+fn draw_gradient_designer(draw: &Draw, rect: Rect, model: &Model, designer: &GradientDesigner) {
+    let strip = gradient_strip(rect);
+    match designer.gradient(model.current_spline_type) {
+        Some(gradient) => {
+            // One-pixel columns, held flat beyond the outermost stops.
+            let (min, max) = gradient.domain();
+            for x in 0..strip.w() as usize {
+                let [r, g, b] = gradient.color_at((x as f32 / strip.w()).clamp(min, max));
+                draw.rect()
+                    .x_y(strip.left() + x as f32 + 0.5, strip.y())
+                    .w_h(1.0, strip.h())
+                    .color(rgb(r, g, b));
+            }
+        }
+        None => {
+            draw.rect().xy(strip.xy()).wh(strip.wh()).no_fill().stroke(GRAY).stroke_weight(1.0);
+        }
+    }
+
+    for (i, stop) in designer.stops.iter().enumerate() {
+        let x = strip.left() + stop.position * strip.w();
+        let [r, g, b] = stop.color;
+        let selected = designer.selected == Some(i);
+        let outline = if selected { rgb_u32(0xFF3366) } else { rgb_u32(0xFFFFFF) };
+        draw.line()
+            .start(pt2(x, strip.bottom()))
+            .end(pt2(x, strip.bottom() - 14.0))
+            .weight(2.0)
+            .color(outline);
+        draw.rect()
+            .x_y(x, strip.bottom() - 26.0)
+            .w_h(18.0, 18.0)
+            .color(rgb(r, g, b))
+            .stroke(outline)
+            .stroke_weight(if selected { 3.0 } else { 1.5 });
+    }
+}
+
+// Camera keyframes edited in a top-down plan, a fixed time apart, with the
+// height of the selected handle on Up/Down.
+struct CameraEditor {
+    keyframes: Vec<Keyframe>,
+    // The keyframe and whether its target, rather than its position, is
+    // selected.
+    selected: Option<(usize, bool)>,
+    dragging: bool,
+    // When the running fly-through started, in app time.
+    preview_start: Option<f32>,
+}
+
+// Pixels per world unit in the plan.
+const PLAN_SCALE: f32 = 60.0;
+const KEYFRAME_SECONDS: f32 = 2.0;
+const CAMERA_FPS: f32 = 30.0;
+
+impl CameraEditor {
+    fn new() -> Self {
+        let keyframe = |i: usize, position, target| Keyframe {
+            time: i as f32 * KEYFRAME_SECONDS,
+            position,
+            target,
+        };
+        CameraEditor {
+            keyframes: vec![
+                keyframe(0, [-6.0, 2.0, 6.0], [0.0, 0.0, 0.0]),
+                keyframe(1, [0.0, 3.0, 8.0], [0.0, 0.0, 0.0]),
+                keyframe(2, [6.0, 2.0, 6.0], [1.0, 0.5, 0.0]),
+            ],
+            selected: None,
+            dragging: false,
+            preview_start: None,
+        }
+    }
+
+    fn path(&self, spline_type: SplineType) -> Option<CameraPath> {
+        CameraPath::try_new(&self.keyframes, spline_type).ok()
+    }
+}
+
+fn plan_to_screen(p: [f32; 3]) -> Vec2 {
+    vec2(p[0] * PLAN_SCALE, -p[2] * PLAN_SCALE)
+}
+
+// Picks the position or target handle under the mouse, or else appends a
+// keyframe there that keeps looking where the last one did.
+fn pick_camera_handle(model: &mut Model, mouse: Vec2) {
+    let editor = match &mut model.camera {
+        Some(editor) => editor,
+        None => return,
+    };
+    let near = |p: [f32; 3]| plan_to_screen(p).distance(mouse) < 12.0;
+    let hit = editor.keyframes.iter().enumerate().find_map(|(i, k)| {
+        if near(k.target) {
+            Some((i, true))
+        } else if near(k.position) {
+            Some((i, false))
+        } else {
+            None
+        }
+    });
+    editor.selected = Some(hit.unwrap_or_else(|| {
+        let last = editor.keyframes.last().copied();
+        editor.keyframes.push(Keyframe {
+            time: editor.keyframes.len() as f32 * KEYFRAME_SECONDS,
+            position: [mouse.x / PLAN_SCALE, last.map_or(2.0, |k| k.position[1]), -mouse.y / PLAN_SCALE],
+            target: last.map_or([0.0; 3], |k| k.target),
+        });
+        (editor.keyframes.len() - 1, false)
+    }));
+    editor.dragging = true;
+}
+
+fn selected_handle(editor: &mut CameraEditor) -> Option<&mut [f32; 3]> {
+    let (i, is_target) = editor.selected?;
+    let keyframe = &mut editor.keyframes[i];
+    Some(if is_target { &mut keyframe.target } else { &mut keyframe.position })
+}
+
+fn edit_camera(app: &App, model: &mut Model, action: Action) {
+    let spline_type = model.current_spline_type;
+    let editor = match &mut model.camera {
+        Some(editor) => editor,
+        None => return,
+    };
+    match action {
+        Action::CameraEditor | Action::LeaveMode => model.camera = None,
+        Action::CommandPalette => model.palette = Some(Palette::new()),
+        Action::PreviewCamera => {
+            editor.preview_start = match editor.preview_start {
+                Some(_) => None,
+                None => Some(app.time),
+            }
+        }
+        Action::Increase | Action::Decrease => {
+            if let Some(handle) = selected_handle(editor) {
+                handle[1] += if action == Action::Increase { 0.25 } else { -0.25 };
+            }
+        }
+        // Later keyframes move up to close the gap in time.
+        Action::DeleteSelected => {
+            if let Some((i, _)) = editor.selected.filter(|_| editor.keyframes.len() > 2) {
+                editor.keyframes.remove(i);
+                for (k, keyframe) in editor.keyframes.iter_mut().enumerate() {
+                    keyframe.time = k as f32 * KEYFRAME_SECONDS;
+                }
+                editor.selected = None;
+            }
+        }
+        Action::Export => {
+            if let Some(path) = editor.path(spline_type) {
+                let (file, t) = ("camera.json", &model.locale);
+                model.status = Some(match std::fs::write(file, path.to_json(CAMERA_FPS)) {
+                    Ok(()) => t.format("status.camera_exported", &[("path", &file), ("fps", &CAMERA_FPS)]),
+                    Err(e) => t.format("status.save_failed", &[("path", &file), ("error", &e)]),
+                });
+            }
+        }
+        Action::Linear | Action::Quadratic | Action::Cubic => set_spline_type(model, action),
+        _ => {}
+    }
+}
+
+// The flight and its top-down polylines, which only change with the
+// keyframes.
+struct CameraPlan {
+    path: CameraPath,
+    positions: Vec<Vec2>,
+    targets: Vec<Vec2>,
+}
+
+fn camera_plan(editor: &CameraEditor, spline_type: SplineType) -> Option<CameraPlan> {
+    let path = editor.path(spline_type)?;
+    let (start, end) = path.duration();
+    let times: Vec<f32> = (0..=200).map(|i| start + (end - start) * i as f32 / 200.0).collect();
+    Some(CameraPlan {
+        positions: times.iter().map(|&t| plan_to_screen(path.position(t))).collect(),
+        targets: times.iter().map(|&t| plan_to_screen(path.target(t))).collect(),
+        path,
+    })
+}
+
+fn draw_camera_plan(draw: &Draw, editor: &CameraEditor, plan: Option<&CameraPlan>, now: f32) {
+    for i in -10..=10 {
+        let (near, far) = (i as f32 * PLAN_SCALE, 10.0 * PLAN_SCALE);
+        let color = rgba(1.0, 1.0, 1.0, if i == 0 { 0.3 } else { 0.1 });
+        draw.line().start(pt2(near, -far)).end(pt2(near, far)).weight(1.0).color(color);
+        draw.line().start(pt2(-far, near)).end(pt2(far, near)).weight(1.0).color(color);
+    }
+
+    if let Some(plan) = plan {
+        draw.polyline()
+            .weight(3.0)
+            .points(plan.positions.iter().copied())
+            .color(rgb_u32(0x00FFAA));
+        draw.polyline()
+            .weight(2.0)
+            .points(plan.targets.iter().copied())
+            .color(rgb_u32(0xFFAA33));
+        if let Some(started) = editor.preview_start {
+            let path = &plan.path;
+            let t = preview_time(path, started, now);
+            draw.line()
+                .start(plan_to_screen(path.position(t)))
+                .end(plan_to_screen(path.target(t)))
+                .weight(1.5)
+                .color(WHITE);
+        }
+    }
+
+    for (i, keyframe) in editor.keyframes.iter().enumerate() {
+        let (position, target) = (plan_to_screen(keyframe.position), plan_to_screen(keyframe.target));
+        draw.line().start(position).end(target).weight(1.0).color(rgba(1.0, 1.0, 1.0, 0.25));
+        for (point, is_target, radius) in [(position, false, 8.0), (target, true, 6.0)] {
+            let color = if editor.selected == Some((i, is_target)) { rgb_u32(0xFF3366) } else { rgb_u32(0xFFFFFF) };
+            draw.ellipse().xy(point).radius(radius).color(color);
+        }
+        draw.text(&format!("{}s", keyframe.time))
+            .xy(position + vec2(0.0, 18.0))
+            .color(WHITE)
+            .font_size(12);
+    }
+}
+
+// The fly-through loops from the first keyframe to the last.
+fn preview_time(path: &CameraPath, started: f32, now: f32) -> f32 {
+    let (start, end) = path.duration();
+    start + (now - started).rem_euclid(end - start)
+}
+
+// Perspective view from the camera at `time`: the ground grid, the target
+// path and the keyframe positions, projected into `viewport`.
+// The part of a line inside the view volume, found in clip space where
+// the sides are -w <= x, y <= w and the near plane is z >= 0: lines are cut
+// at the viewport's edges, and where they pass behind the camera.
+fn clip_segment(a: Vec4, b: Vec4) -> Option<(Vec4, Vec4)> {
+    let inside: [fn(Vec4) -> f32; 5] = [|p| p.w + p.x, |p| p.w - p.x, |p| p.w + p.y, |p| p.w - p.y, |p| p.z];
+    let (mut start, mut end) = (0.0f32, 1.0f32);
+    for distance in inside {
+        let (da, db) = (distance(a), distance(b));
+        if da < 0.0 && db < 0.0 {
+            return None;
+        }
+        if da < 0.0 {
+            start = start.max(da / (da - db));
+        } else if db < 0.0 {
+            end = end.min(da / (da - db));
+        }
+    }
+    (start < end).then(|| (a.lerp(b, start), a.lerp(b, end)))
+}
+
+// nannou's `Draw` only has a 2D view, so the preview projects the scene
+// itself with a look-at and perspective matrix.
+fn draw_camera_preview(draw: &Draw, viewport: Rect, path: &CameraPath, time: f32) {
+    let [ex, ey, ez] = path.position(time);
+    let [tx, ty, tz] = path.target(time);
+    let view = Mat4::look_at_rh(vec3(ex, ey, ez), vec3(tx, ty, tz), Vec3::Y);
+    let projection = Mat4::perspective_rh(60f32.to_radians(), viewport.w() / viewport.h(), 0.1, 100.0);
+    let camera = projection * view;
+    let to_viewport = |clip: Vec4| viewport.xy() + vec2(clip.x / clip.w, clip.y / clip.w) * viewport.wh() / 2.0;
+    let segment = |a: Vec3, b: Vec3, (r, g, bl, alpha): (f32, f32, f32, f32)| {
+        if let Some((a, b)) = clip_segment(camera * a.extend(1.0), camera * b.extend(1.0)) {
+            draw.line()
+                .start(to_viewport(a))
+                .end(to_viewport(b))
+                .weight(1.5)
+                .color(rgba(r, g, bl, alpha));
+        }
+    };
+
+    draw.rect().xy(viewport.xy()).wh(viewport.wh()).color(rgb_u32(0x0A1A2A));
+    for i in -10..=10 {
+        let (near, far) = (i as f32, 10.0);
+        let color = (1.0, 1.0, 1.0, if i == 0 { 0.4 } else { 0.15 });
+        segment(vec3(near, 0.0, -far), vec3(near, 0.0, far), color);
+        segment(vec3(-far, 0.0, near), vec3(far, 0.0, near), color);
+    }
+    let (start, end) = path.duration();
+    let targets: Vec<Vec3> = (0..=100)
+        .map(|i| Vec3::from(path.target(start + (end - start) * i as f32 / 100.0)))
+        .collect();
+    for w in targets.windows(2) {
+        segment(w[0], w[1], (1.0, 0.67, 0.2, 1.0));
+    }
+    for keyframe in path.keyframes() {
+        let p = Vec3::from(keyframe.position);
+        segment(p - Vec3::Y * 0.2, p + Vec3::Y * 0.2, (0.0, 1.0, 0.67, 1.0));
+    }
+    draw.rect()
+        .xy(viewport.xy())
+        .wh(viewport.wh())
+        .no_fill()
+        .stroke(WHITE)
+        .stroke_weight(1.0);
+}
+
+// A glyph drawn as closed contours inside its em-square. Points are in font
+// units; the contour being drawn stays open until Enter closes it.
+struct GlyphSketch {
+    glyph: Glyph,
+    em: EmSquare,
+    open: Vec<Point>,
+    // The contour (None for the open one) and point being dragged.
+    dragging: Option<(Option<usize>, usize)>,
+    // The last two contours combined, shown over the sketch until applied.
+    boolean: Option<(BooleanOp, Vec<Vec<Point>>)>,
+}
+
+// Screen pixels the em-square is tall.
+const EM_PIXELS: f32 = 700.0;
+
+impl GlyphSketch {
+    fn new() -> Self {
+        GlyphSketch {
+            glyph: Glyph {
+                name: "a".to_string(),
+                unicode: Some('a'),
+                advance: 600.0,
+                contours: Vec::new(),
+            },
+            em: EmSquare::default(),
+            open: Vec::new(),
+            dragging: None,
+            boolean: None,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        EM_PIXELS / self.em.units_per_em
+    }
+
+    fn to_screen(&self, p: Point) -> Vec2 {
+        let s = self.scale();
+        vec2((p.x - self.glyph.advance / 2.0) * s, (p.y - self.em.descender) * s - EM_PIXELS / 2.0)
+    }
+
+    // Kept inside the em-square and the advance width.
+    fn to_glyph(&self, p: Vec2) -> Point {
+        let s = self.scale();
+        Point::new(
+            (p.x / s + self.glyph.advance / 2.0).clamp(0.0, self.glyph.advance),
+            ((p.y + EM_PIXELS / 2.0) / s + self.em.descender).clamp(self.em.descender, self.em.ascender()),
+        )
+    }
+}
+
+fn pick_glyph_point(model: &mut Model, mouse: Vec2) {
+    let sketch = match &mut model.glyph {
+        Some(sketch) => sketch,
+        None => return,
+    };
+    let near = |p: &Point| sketch.to_screen(*p).distance(mouse) < 10.0;
+    let hit = sketch
+        .open
+        .iter()
+        .position(near)
+        .map(|i| (None, i))
+        .or_else(|| {
+            sketch
+                .glyph
+                .contours
+                .iter()
+                .enumerate()
+                .find_map(|(c, contour)| contour.points().iter().position(near).map(|i| (Some(c), i)))
+        });
+    sketch.dragging = match hit {
+        Some(hit) => Some(hit),
+        None => {
+            let point = sketch.to_glyph(mouse);
+            sketch.open.push(point);
+            Some((None, sketch.open.len() - 1))
+        }
+    };
+}
+
+fn drag_glyph_point(sketch: &mut GlyphSketch, pos: Vec2) {
+    let point = sketch.to_glyph(pos);
+    match sketch.dragging {
+        Some((None, i)) => sketch.open[i] = point,
+        Some((Some(c), i)) => {
+            let mut points = sketch.glyph.contours[c].points().to_vec();
+            points[i] = point;
+            sketch.glyph.contours[c] = Contour::new(&points);
+        }
+        None => {}
+    }
+}
+
+// Outer contours run counter-clockwise, as UFO expects, and a contour
+// started inside another runs the other way so it cuts a counter.
+fn close_glyph_contour(t: &Locales, sketch: &mut GlyphSketch) -> Result<(), String> {
+    if sketch.open.len() < 3 {
+        return Err(t.format("status.contour_too_small", &[("count", &3)]));
+    }
+    let contour = Contour::new(&std::mem::take(&mut sketch.open));
+    let depth = sketch
+        .glyph
+        .contours
+        .iter()
+        .filter(|c| inside_polygon(&c.flatten(1.0), contour.points()[0]))
+        .count();
+    let counter_clockwise = depth % 2 == 0;
+    sketch.glyph.contours.push(if (contour.signed_area() > 0.0) == counter_clockwise {
+        contour
+    } else {
+        contour.reversed()
+    });
+    Ok(())
+}
+
+fn edit_glyph(model: &mut Model, action: Action) {
+    let sketch = match &mut model.glyph {
+        Some(sketch) => sketch,
+        None => return,
+    };
+    match action {
+        Action::GlyphSketcher | Action::LeaveMode => model.glyph = None,
+        Action::CommandPalette => model.palette = Some(Palette::new()),
+        Action::CloseContour => {
+            if let Err(e) = close_glyph_contour(&model.locale, sketch) {
+                model.status = Some(e);
+            }
+        }
+        Action::UndoGlyphPoint => {
+            sketch.open.pop();
+        }
+        Action::RemoveContour => {
+            sketch.glyph.contours.pop();
+            sketch.boolean = None;
+        }
+        Action::UnionContours | Action::IntersectContours | Action::SubtractContours => {
+            let op = match action {
+                Action::UnionContours => BooleanOp::Union,
+                Action::IntersectContours => BooleanOp::Intersection,
+                _ => BooleanOp::Difference,
+            };
+            if let Err(e) = preview_boolean(&model.locale, sketch, op) {
+                model.status = Some(e);
+            }
+        }
+        Action::ApplyBoolean => apply_boolean(sketch),
+        Action::NarrowerGlyph => sketch.glyph.advance = (sketch.glyph.advance - 10.0).max(10.0),
+        Action::WiderGlyph => sketch.glyph.advance += 10.0,
+        Action::Export => model.status = Some(export_glyph(&model.locale, sketch)),
+        _ => {}
+    }
+}
+
+fn export_glyph(t: &Locales, sketch: &GlyphSketch) -> String {
+    let glyph = &sketch.glyph;
+    let files = [
+        (format!("{}.glif", glyph.name), glyph.to_glif()),
+        (format!("{}.svg", glyph.name), glyph.to_svg(&sketch.em)),
+    ];
+    for (path, contents) in &files {
+        if let Err(e) = std::fs::write(path, contents) {
+            return t.format("status.save_failed", &[("path", path), ("error", &e)]);
+        }
+    }
+    t.format(
+        "status.glyph_exported",
+        &[("count", &glyph.contours.len()), ("glif", &files[0].0), ("svg", &files[1].0)],
+    )
+}
+
+// Flattened to a font unit, which is finer than any outline needs.
+fn preview_boolean(t: &Locales, sketch: &mut GlyphSketch, op: BooleanOp) -> Result<(), String> {
+    let contours = &sketch.glyph.contours;
+    if contours.len() < 2 {
+        return Err(t.format("status.combine_too_few", &[("count", &2)]));
+    }
+    let (a, b) = (&contours[contours.len() - 2], &contours[contours.len() - 1]);
+    sketch.boolean = Some((op, a.boolean(b, op, 1.0)));
+    Ok(())
+}
+
+// Swaps the two contours for the result, refitted with a point about every
+// 60 font units so it stays editable.
+fn apply_boolean(sketch: &mut GlyphSketch) {
+    if let Some((_, loops)) = sketch.boolean.take() {
+        let contours = &mut sketch.glyph.contours;
+        contours.truncate(contours.len().saturating_sub(2));
+        for polygon in loops {
+            let perimeter: f32 = (0..polygon.len())
+                .map(|i| {
+                    let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                    (b.x - a.x).hypot(b.y - a.y)
+                })
+                .sum();
+            contours.push(Contour::from_polygon(&polygon, ((perimeter / 60.0).round() as usize).max(4)));
+        }
+    }
+}
+
+// Typing a letter or digit makes the sketch that character's glyph.
+fn name_glyph(sketch: &mut GlyphSketch, c: char) {
+    sketch.glyph.unicode = Some(c);
+    sketch.glyph.name = if c.is_ascii_alphanumeric() {
+        c.to_string()
+    } else {
+        format!("uni{:04X}", c as u32)
+    };
+}
+
+fn draw_glyph_sketch(draw: &Draw, sketch: &GlyphSketch) {
+    let em = &sketch.em;
+    let advance = sketch.glyph.advance;
+    let (bottom_left, top_right) = (
+        sketch.to_screen(Point::new(0.0, em.descender)),
+        sketch.to_screen(Point::new(advance, em.ascender())),
+    );
+    let square = Rect::from_corners(bottom_left, top_right);
+    draw.rect()
+        .xy(square.xy())
+        .wh(square.wh())
+        .no_fill()
+        .stroke(rgb_u32(0x6688AA))
+        .stroke_weight(1.0);
+    for (name, y) in [("baseline", 0.0), ("x-height", em.x_height), ("cap height", em.cap_height)] {
+        let left = sketch.to_screen(Point::new(0.0, y));
+        draw.line()
+            .start(left)
+            .end(pt2(square.right(), left.y))
+            .weight(1.0)
+            .color(rgba(0.4, 0.8, 1.0, 0.5));
+        draw.text(name)
+            .xy(pt2(square.left() - 50.0, left.y))
+            .color(rgb_u32(0x88BBDD))
+            .font_size(12);
+    }
+
+    for contour in &sketch.glyph.contours {
+        let mut outline: Vec<Vec2> = contour.flatten(1.0).iter().map(|&p| sketch.to_screen(p)).collect();
+        outline.push(outline[0]);
+        draw.polyline().weight(3.0).points(outline).color(rgb_u32(0x00FFAA));
+    }
+    if sketch.open.len() >= 2 {
+        draw.polyline()
+            .weight(1.5)
+            .points(sketch.open.iter().map(|&p| sketch.to_screen(p)))
+            .color(rgba(1.0, 1.0, 1.0, 0.5));
+    }
+
+    if let Some((_, loops)) = &sketch.boolean {
+        for polygon in loops {
+            let mut outline: Vec<Vec2> = polygon.iter().map(|&p| sketch.to_screen(p)).collect();
+            // Holes are left unfilled, so they show through.
+            if polygon_area(polygon) > 0.0 {
+                draw.polygon().points(outline.clone()).color(rgba(1.0, 0.6, 0.2, 0.35));
+            }
+            outline.push(outline[0]);
+            draw.polyline().weight(2.0).points(outline).color(rgb_u32(0xFFAA33));
+        }
+    }
+
+    let points = sketch
+        .glyph
+        .contours
+        .iter()
+        .flat_map(|c| c.points().iter())
+        .chain(&sketch.open);
+    for &p in points {
+        draw.ellipse().xy(sketch.to_screen(p)).radius(5.0).color(WHITE);
+    }
+}
+
+fn can_edit_group(model: &Model) -> bool {
+    let group = &model.groups[model.active_group];
+    !group.hidden && !group.locked
+}
+
+// Translates the whole active group along with the dragged point.
+fn move_group(model: &mut Model, i: usize, point: Point) {
+    let (dx, dy) = (point.x - model.control_points[i].x, point.y - model.control_points[i].y);
+    for p in &mut model.control_points {
+        *p = Point::new(p.x + dx, p.y + dy);
+    }
+    rebuild_spline(model);
+}
+
+// Writes the active group on its own, as a session file named after it.
+fn export_group(model: &mut Model) {
+    let design = Design {
+        points: model.control_points.clone(),
+        spline_type: model.current_spline_type,
+        annotations: model.annotations.clone(),
+        tags: model.tags.clone(),
+    };
+    let path = format!("{}.spline", model.groups[model.active_group].name);
+    let t = &model.locale;
+    model.status = Some(match design.save(&path) {
+        Ok(()) => t.format("status.group_exported", &[("count", &design.points.len()), ("path", &path)]),
+        Err(e) => t.format("status.save_failed", &[("path", &path), ("error", &e)]),
+    });
+}
+
+fn draw_inactive_groups(draw: &Draw, model: &Model) {
+    for (i, group) in model.groups.iter().enumerate() {
+        if i == model.active_group || group.hidden {
+            continue;
+        }
+        if let Ok(spline) = Spline::try_new(&group.points, model.current_spline_type) {
+            draw.polyline()
+                .weight(2.0)
+                .points(spline.samples(DENSE_SAMPLES).iter().map(|p| pt2(p.x, p.y)))
+                .color(rgb_u32(group.color.unwrap_or(0x55AA88)));
+        }
+        for p in &group.points {
+            draw.ellipse().x_y(p.x, p.y).radius(4.0).color(rgb_u32(0x889999));
+        }
+    }
+}
+
+// The clipboard can be missing (e.g. no display server), so the string is
+// always printed as well.
+fn copy_share_string(model: &mut Model) {
+    let design = Design {
+        points: model.control_points.clone(),
+        spline_type: model.current_spline_type,
+        annotations: model.annotations.clone(),
+        tags: model.tags.clone(),
+    };
+    let text = design.to_share_string();
+    println!("{}", text);
+
+    let t = &model.locale;
+    model.status = Some(match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        Ok(()) => t.get("status.share_copied").to_string(),
+        Err(e) => t.format("status.share_printed", &[("error", &e)]),
+    });
+}
+
+// The curve in data units as a standalone function, for firmware.
+fn export_baked(rect: Rect, model: &mut Model) {
+    let spline = match Spline::try_new(&data_points(rect, model), model.current_spline_type) {
+        Ok(spline) => spline.bake(),
+        Err(e) => {
+            model.status = Some(model.locale.format("status.nothing_to_bake", &[("error", &e)]));
+            return;
+        }
+    };
+    let files = [("curve.rs", spline.to_rust("curve")), ("curve.c", spline.to_c("curve"))];
+    for (path, source) in &files {
+        if let Err(e) = std::fs::write(path, source) {
+            model.status = Some(model.locale.format("status.save_failed", &[("path", path), ("error", &e)]));
+            return;
+        }
+    }
+    model.status = Some(model.locale.format("status.baked", &[("rust", &files[0].0), ("c", &files[1].0)]));
+}
+
+fn save_session(model: &mut Model) {
+    let design = Design {
+        points: model.control_points.clone(),
+        spline_type: model.current_spline_type,
+        annotations: model.annotations.clone(),
+        tags: model.tags.clone(),
+    };
+    let (t, path) = (&model.locale, &model.session_path);
+    model.status = Some(match design.save(path) {
+        Ok(()) => t.format("status.session_saved", &[("path", path)]),
+        Err(e) => t.format("status.save_failed", &[("path", path), ("error", &e)]),
+    });
+}
+
+fn paste_share_string(model: &mut Model) {
+    let text = match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        Ok(text) => text,
+        Err(e) => {
+            model.status = Some(model.locale.format("status.clipboard_unreadable", &[("error", &e)]));
+            return;
+        }
+    };
+    match Design::from_share_string(&text) {
+        Ok(design) => {
+            load_design(model, design);
+            model.status = Some(model.locale.get("status.share_loaded").to_string());
+        }
+        Err(e) => model.warning = Some(model.locale.format("warnings.not_share_string", &[("error", &e)])),
+    }
+}
+
+fn load_random_points(rect: Rect, model: &mut Model, seed: u64) {
+    let (domain, amplitude) = canvas_extent(rect);
+    set_control_points(model, random_points(seed, model.random_point_count, domain, amplitude));
+    model.seed = Some(seed);
+}
+
+fn load_preset(rect: Rect, model: &mut Model, preset: Preset) {
+    let (domain, amplitude) = canvas_extent(rect);
+    set_control_points(model, preset.points(model.sample_point_count, domain, amplitude));
+    model.preset = Some(preset);
+    model.ground_truth = Some(GroundTruth {
+        points: preset.points(DENSE_SAMPLES, domain, amplitude),
+        units: DataUnits::PerPixel(1.0 / amplitude),
+    });
+}
+
+// Stretches the file's bounding box over the canvas; errors are reported
+// back in the file's units through the y axis.
+// With `keep_view`, the axes stay as they are rather than taking on the
+// data's range.
+fn load_data_file(rect: Rect, model: &mut Model, path: &str, keep_view: bool) -> std::io::Result<()> {
+    let CsvData { points: data, non_finite_rows } = load_csv(path)?;
+    let mut warnings = Vec::new();
+
+    if !keep_view {
+        let min_y = data.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = data.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let mid_y = (min_y + max_y) / 2.0;
+        let half_range = ((max_y - min_y) / 2.0).max(f32::EPSILON);
+
+        // The axes take on the data's range, keeping their titles and units,
+        // and any zoom is dropped.
+        model.overview = None;
+        model.x_axis.min = data[0].x;
+        model.x_axis.max = data[data.len() - 1].x;
+        model.y_axis.min = mid_y - half_range;
+        model.y_axis.max = mid_y + half_range;
+    }
+    for (axis, name) in [(&mut model.x_axis, "x"), (&mut model.y_axis, "y")] {
+        if axis.scale == Scale::Log && !axis.supports_log() {
+            axis.scale = Scale::Linear;
+            warnings.push(model.locale.format("warnings.linear_axis", &[("axis", &name)]));
+        }
+    }
+
+    let points: Vec<Point> = data
+        .iter()
+        .map(|&p| data_to_screen(rect, &model.x_axis, &model.y_axis, p))
+        .collect();
+
+    set_control_points(model, subsample(&points, model.sample_point_count));
+    model.ground_truth = Some(GroundTruth {
+        points,
+        units: DataUnits::YAxis,
+    });
+    if non_finite_rows > 0 {
+        let key = if non_finite_rows == 1 { "warnings.skipped_row" } else { "warnings.skipped_rows" };
+        warnings.push(model.locale.format(key, &[("count", &non_finite_rows), ("path", &path)]));
+    }
+    if !warnings.is_empty() {
+        model.warning = Some(warnings.join("; "));
+    }
+    Ok(())
+}
+
+// Watches the file's directory rather than the file, since editors often
+// save by writing a new file and renaming it over the old one.
+struct DataWatch {
+    path: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+}
+
+impl DataWatch {
+    fn new(path: &str) -> notify::Result<Self> {
+        use notify::Watcher;
+
+        let path = std::fs::canonicalize(path)?;
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        let directory = path.parent().unwrap_or(Path::new("."));
+        watcher.watch(directory, notify::RecursiveMode::NonRecursive)?;
+        Ok(DataWatch {
+            path,
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    // Drains the pending events, so a burst of writes reloads once.
+    fn changed(&self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            if let Ok(event) = event {
+                let writes = event.kind.is_modify() || event.kind.is_create();
+                changed |= writes && event.paths.iter().any(|p| p == &self.path);
+            }
+        }
+        changed
+    }
+}
+
+// Keeps the axes, spline type and sample count. A file caught half-written
+// fails to parse and is picked up again on its next change.
+fn reload_data_file(rect: Rect, model: &mut Model) {
+    let path = match &model.data_watch {
+        Some(watch) => watch.path.to_string_lossy().into_owned(),
+        None => return,
+    };
+    model.revision += 1;
+    let result = load_data_file(rect, model, &path, true);
+    model.status = Some(match result {
+        Ok(()) => model.locale.format("status.reloaded", &[("path", &path)]),
+        Err(e) => model.locale.format("status.reload_failed", &[("path", &path), ("error", &e)]),
+    });
+}
+
+// Answers what clients have asked since the last frame, then sends any
+// subscribers the curve if it has changed, remotely or in the window.
+fn serve_remote(rect: Rect, model: &mut Model) {
+    while let Some(call) = model.remote.as_ref().and_then(RemoteServer::next_call) {
+        model.revision += 1;
+        let result = answer_remote(rect, model, &call.request);
+        if let (Request::Subscribe, Some(server)) = (&call.request, &mut model.remote) {
+            server.subscribe(&call);
+        }
+        call.answer(result);
+    }
+
+    if model.remote.as_ref().is_some_and(RemoteServer::has_subscribers) {
+        let state = remote_state(rect, model);
+        if let Some(server) = &mut model.remote {
+            server.publish(state);
+        }
+    }
+}
+
+fn answer_remote(rect: Rect, model: &mut Model, request: &Request) -> Result<Value, String> {
+    match request {
+        Request::SetPoints(points) => {
+            let screen: Vec<Point> = points
+                .iter()
+                .map(|&p| data_to_screen(rect, &model.x_axis, &model.y_axis, p))
+                .collect();
+            Spline::try_new(&screen, model.current_spline_type).map_err(|e| e.to_string())?;
+            set_control_points(model, screen);
+            Ok(json!({}))
+        }
+        Request::SetType(spline_type) => {
+            model.current_spline_type = *spline_type;
+            model.custom_method = None;
+            rebuild_spline(model);
+            Ok(json!({}))
+        }
+        // A new subscriber starts from the current curve.
+        Request::GetPoints | Request::Subscribe => Ok(remote_state(rect, model)),
+        Request::Sample(count) => {
+            let spline = model.spline.as_ref().ok_or("there is no curve to sample")?;
+            let samples: Vec<[f32; 2]> = output_samples(rect, model, spline, *count)
+                .into_iter()
+                .map(|p| screen_to_data(rect, &model.x_axis, &model.y_axis, p))
+                .map(|p| [p.x, p.y])
+                .collect();
+            Ok(json!({ "samples": samples }))
+        }
+    }
+}
+
+fn remote_state(rect: Rect, model: &Model) -> Value {
+    let points: Vec<[f32; 2]> = data_points(rect, model).iter().map(|p| [p.x, p.y]).collect();
+    let tags: Vec<Value> = model
+        .tags
+        .iter()
+        .map(|t| json!({ "point": t.point, "key": t.key, "value": t.value }))
+        .collect();
+    json!({
+        "points": points,
+        "spline_type": remote::type_name(model.current_spline_type),
+        "tags": tags,
+    })
+}
+
+// The Runge demo works on x in [-1, 1] and y in [0, 1], mapped onto the
+// middle of the window.
+fn runge_to_screen(rect: Rect, x: f32, y: f32) -> Point {
+    Point::new(x * rect.w() * 0.4, (y - 0.5) * rect.h() * 0.5)
+}
+
+fn screen_to_runge(rect: Rect, p: Point) -> Point {
+    Point::new(p.x / (rect.w() * 0.4), p.y / (rect.h() * 0.5) + 0.5)
+}
+
+// A screen y in the y axis's data units.
+fn data_y(rect: Rect, model: &Model, y: f32) -> f32 {
+    screen_to_data(rect, &model.x_axis, &model.y_axis, Point::new(0.0, y)).y
+}
+
+// The curve's error against the dataset, in the dataset's own units.
+fn ground_truth_error(rect: Rect, model: &Model, ground_truth: &GroundTruth, spline: &Spline) -> ErrorMetrics {
+    match ground_truth.units {
+        DataUnits::PerPixel(scale) => {
+            let metrics = error_metrics(&ground_truth.points, |x| spline.evaluate(x));
+            ErrorMetrics {
+                rms: metrics.rms * scale,
+                max: metrics.max * scale,
+            }
+        }
+        DataUnits::YAxis => {
+            let data: Vec<Point> =
+                ground_truth.points.iter().map(|p| Point::new(p.x, data_y(rect, model, p.y))).collect();
+            error_metrics(&data, |x| data_y(rect, model, spline.evaluate(x)))
+        }
+    }
+}
+
+// On a log axis a pixel spans more data higher up, so the tolerance is
+// converted where a pixel spans the most, and holds everywhere.
+fn pixel_tolerance(rect: Rect, model: &Model, ground_truth: &GroundTruth) -> f32 {
+    let units_per_pixel = match ground_truth.units {
+        DataUnits::PerPixel(scale) => scale,
+        DataUnits::YAxis => ground_truth
+            .points
+            .iter()
+            .map(|p| (data_y(rect, model, p.y + 1.0) - data_y(rect, model, p.y)).abs())
+            .fold(0.0, f32::max),
+    };
+    model.fit_tolerance / units_per_pixel
+}
+
+fn fit_adaptive_to_ground_truth(rect: Rect, model: &mut Model) {
+    let ground_truth = match &model.ground_truth {
+        Some(ground_truth) => ground_truth,
+        None => return,
+    };
+
+    let tolerance = pixel_tolerance(rect, model, ground_truth);
+    let spline = match Spline::fit_adaptive(&ground_truth.points, tolerance, model.current_spline_type) {
+        Ok(spline) => spline,
+        Err(e) => {
+            model.warning = Some(model.locale.format("warnings.cannot_fit", &[("error", &e)]));
+            return;
+        }
+    };
+    model.control_points = spline.points().to_vec();
+    model.dragging_point = None;
+    model.layered = None;
+    model.status = Some(model.locale.format(
+        "status.adaptive_fit",
+        &[("count", &model.control_points.len()), ("tolerance", &model.fit_tolerance)],
+    ));
+    model.spline = Some(spline);
+    refit_custom_curve(model);
+}
+
+// Also marks the outliers when `robust`, and clears them otherwise.
+fn fit_smoothing(model: &mut Model, robust: bool) -> Result<SmoothingSpline, SplineError> {
+    let points = model.ground_truth.as_ref().map_or(&[][..], |g| &g.points);
+    if robust {
+        RobustFit::fit(points).map(|fit| {
+            model.outliers = (0..points.len()).map(|i| fit.is_outlier(i)).collect();
+            fit.into_smoothing()
+        })
+    } else {
+        model.outliers.clear();
+        SmoothingSpline::fit_gcv(points)
+    }
+}
+
+fn smooth_ground_truth(model: &mut Model, robust: bool) {
+    if model.ground_truth.is_none() {
+        return;
+    }
+    let smoothing = match fit_smoothing(model, robust) {
+        Ok(smoothing) => smoothing,
+        Err(e) => {
+            model.warning = Some(model.locale.format("warnings.cannot_fit", &[("error", &e)]));
+            return;
+        }
+    };
+
+    let (lambda, edf) = (format!("{:.3e}", smoothing.lambda()), format!("{:.2}", smoothing.edf()));
+    let outliers = model.outliers.iter().filter(|&&o| o).count();
+    model.status = Some(if robust {
+        model.locale.format(
+            "status.robust_smoothing",
+            &[("lambda", &lambda), ("edf", &edf), ("outliers", &outliers)],
+        )
+    } else {
+        model.locale.format("status.smoothing", &[("lambda", &lambda), ("edf", &edf)])
+    });
+    model.smoothing = Some(smoothing);
+}
+
+const MASTER_POINTS: usize = 6;
+
+// Entering hands the loaded data to a layered curve with a few masters to
+// drag; leaving bakes the edit into the data and resamples it.
+fn toggle_master_editing(model: &mut Model) {
+    if let Some(layered) = model.layered.take() {
+        let points = layered.points();
+        let units = model.ground_truth.as_ref().map_or(DataUnits::YAxis, |g| g.units);
+        set_control_points(model, subsample(&points, model.sample_point_count));
+        model.ground_truth = Some(GroundTruth { points, units });
+        model.status = Some(model.locale.get("status.master_applied").to_string());
+        return;
+    }
+
+    let ground_truth = match &model.ground_truth {
+        Some(ground_truth) if ground_truth.points.len() >= 2 => ground_truth,
+        _ => {
+            model.status = Some(model.locale.get("status.master_needs_data").to_string());
+            return;
+        }
+    };
+    let layered = LayeredCurve::new(&ground_truth.points, MASTER_POINTS);
+    model.control_points = layered.masters();
+    model.dragging_point = None;
+    model.layered = Some(layered);
+    model.status = Some(model.locale.format("status.master_editing", &[("count", &MASTER_POINTS)]));
+}
+
+// Gaussian noise at 5% of the data's vertical range, so there is something
+// for the smoothing fit to remove.
+fn add_noise_to_ground_truth(model: &mut Model, seed: u64) {
+    if let Some(ground_truth) = &mut model.ground_truth {
+        let min_y = ground_truth.points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = ground_truth.points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let sigma = 0.05 * (max_y - min_y);
+
+        let mut rng = Rng::new(seed);
+        for p in &mut ground_truth.points {
+            p.y += sigma * rng.gaussian();
+        }
+        model.smoothing = None;
+        model.outliers.clear();
+        model.status = Some(model.locale.format("status.data_noise", &[("seed", &seed)]));
+    }
+}
+
+fn runge_samples(rect: Rect, count: usize) -> Vec<Point> {
+    (0..count)
+        .map(|i| {
+            let x = -1.0 + 2.0 * i as f32 / (count - 1) as f32;
+            runge_to_screen(rect, x, runge(x))
+        })
+        .collect()
+}
+
+fn load_runge_demo(rect: Rect, model: &mut Model, point_count: usize) {
+    set_control_points(model, runge_samples(rect, point_count));
+    model.runge_point_count = Some(point_count);
+    model.ground_truth = Some(GroundTruth {
+        points: runge_samples(rect, DENSE_SAMPLES),
+        units: DataUnits::PerPixel(1.0 / (rect.h() * 0.5)),
+    });
+}
+
+// Max error against the true function for every spline type, plus the single
+// interpolating polynomial the phenomenon is usually demonstrated with.
+fn runge_errors(rect: Rect, points: &[Point]) -> Vec<(String, f32)> {
+    let unit_points: Vec<Point> = points.iter().map(|&p| screen_to_runge(rect, p)).collect();
+    let domain = (-1.0, 1.0);
+    let mut errors = Vec::new();
+
+    for spline_type in [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic] {
+        let spline = Spline::new(&unit_points, spline_type);
+        errors.push((format!("{:?}", spline_type), max_error(|x| spline.evaluate(x), runge, domain, 1000)));
+    }
+
+    let polynomial = PolynomialInterpolant::new(&unit_points);
+    errors.push(("Polynomial".to_string(), max_error(|x| polynomial.evaluate(x), runge, domain, 1000)));
+    errors
+}
+
+// 95% pointwise band as a closed ribbon: the upper edge left to right, then
+// the lower edge back.
+fn draw_confidence_band(draw: &Draw, smoothing: &SmoothingSpline, resolution: usize) {
+    let xs: Vec<f32> = smoothing.spline().iter_samples(resolution + 1).map(|p| p.x).collect();
+    let bands: Vec<(f32, f32)> = xs.iter().map(|&x| smoothing.confidence_band(x, 1.96)).collect();
+
+    let upper = xs.iter().zip(&bands).map(|(&x, &(_, hi))| pt2(x, hi));
+    let lower = xs.iter().zip(&bands).rev().map(|(&x, &(lo, _))| pt2(x, lo));
+    draw.polygon()
+        .points(upper.chain(lower))
+        .color(rgba(1.0, 0.8, 0.0, 0.25));
+}
+
+fn draw_axes(draw: &Draw, rect: Rect, x_axis: &Axis, y_axis: &Axis) {
+    let ((left, right), amplitude) = canvas_extent(rect);
+    let (bottom, top) = (-amplitude, amplitude);
+    let color = rgb_u32(0x6688AA);
+
+    draw.line().start(pt2(left, bottom)).end(pt2(right, bottom)).weight(1.0).color(color);
+    draw.line().start(pt2(left, bottom)).end(pt2(left, top)).weight(1.0).color(color);
+
+    for (value, label) in x_axis.ticks(10) {
+        let x = left + (right - left) * x_axis.fraction(value);
+        draw.line().start(pt2(x, bottom)).end(pt2(x, bottom - 6.0)).weight(1.0).color(color);
+        draw.text(&label).x_y(x, bottom - 18.0).color(color).font_size(12);
+    }
+    for (value, label) in y_axis.ticks(8) {
+        let y = bottom + (top - bottom) * y_axis.fraction(value);
+        draw.line().start(pt2(left, y)).end(pt2(left - 6.0, y)).weight(1.0).color(color);
+        draw.text(&label).x_y(left - 40.0, y).w(60.0).right_justify().color(color).font_size(12);
+    }
+
+    if let Some(label) = x_axis.label() {
+        draw.text(&label).x_y((left + right) / 2.0, bottom - 40.0).w(right - left).color(color).font_size(14);
+    }
+    if let Some(label) = y_axis.label() {
+        draw.text(&label)
+            .x_y(left - 90.0, 0.0)
+            .w(top - bottom)
+            .rotate(PI / 2.0)
+            .color(color)
+            .font_size(14);
+    }
+}
+
+// Copies of the curve repeated across the window, one period (the control
+// points' x-range) apart, to check it joins up with itself.
+#[derive(Clone, Copy)]
+struct Tiling {
+    vertical: bool,
+    // Every other copy flipped left to right.
+    mirror: bool,
+}
+
+// Off, then horizontal, then horizontal and vertical.
+fn cycle_tiling(model: &mut Model) {
+    model.tiling = match model.tiling {
+        None => Some(Tiling { vertical: false, mirror: false }),
+        Some(Tiling { vertical: false, mirror }) => Some(Tiling { vertical: true, mirror }),
+        Some(Tiling { vertical: true, .. }) => None,
+    };
+}
+
+// Copies either side of the curve, and above and below, at most; a curve
+// narrower than MIN_TILE_WIDTH pixels is not tiled at all.
+const MAX_TILES: i32 = 64;
+const MIN_TILE_WIDTH: f32 = 4.0;
+
+fn draw_tiling(draw: &Draw, rect: Rect, tiling: Tiling, spline: &Spline) {
+    let (left, right) = spline.domain();
+    let width = right - left;
+    if width < MIN_TILE_WIDTH {
+        return;
+    }
+    let samples = spline.samples(DENSE_SAMPLES);
+    let (low, high) = samples
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+    let height = (high - low).max(1.0);
+
+    let columns = ((rect.w() / width).ceil() as i32 + 1).min(MAX_TILES);
+    let rows = if tiling.vertical { ((rect.h() / height).ceil() as i32 + 1).min(MAX_TILES) } else { 0 };
+    for row in -rows..=rows {
+        for column in -columns..=columns {
+            if row == 0 && column == 0 {
+                continue;
+            }
+            let flipped = tiling.mirror && column % 2 != 0;
+            let shift = column as f32 * width;
+            let copy = samples.iter().map(|p| {
+                let x = if flipped { left + shift + (right - p.x) } else { p.x + shift };
+                pt2(x, p.y + row as f32 * height)
+            });
+            draw.polyline().weight(2.0).points(copy).color(rgba(0.0, 1.0, 0.67, 0.35));
+        }
+    }
+
+    for x in [left, right] {
+        draw.line()
+            .start(pt2(x, rect.bottom()))
+            .end(pt2(x, rect.top()))
+            .weight(1.0)
+            .color(rgba(1.0, 1.0, 1.0, 0.2));
+    }
+}
+
+// How far the curve jumps where one copy meets the next, in value and in
+// slope, in data units. A mirrored copy always meets in value but turns
+// back on itself unless the end is flat.
+fn seam_mismatch(rect: Rect, model: &Model, tiling: Tiling) -> Option<(f32, f32)> {
+    let spline = Spline::try_new(&data_points(rect, model), model.current_spline_type).ok()?;
+    let segments: Vec<Segment> = spline.segments().collect();
+    let (first, last) = (segments[0], segments[segments.len() - 1]);
+    let (start_slope, end_slope) = (first.derivative(first.x0), last.derivative(last.x1));
+    Some(if tiling.mirror {
+        (0.0, 2.0 * start_slope.abs().max(end_slope.abs()))
+    } else {
+        ((last.eval(last.x1) - first.eval(first.x0)).abs(), (end_slope - start_slope).abs())
+    })
+}
+
+// A retiming of the curve, edited in a unit square in the bottom-left
+// corner: x is playback time and y the time the curve is read at, both as
+// fractions of the curve's x-range. A marker plays the eased curve.
+struct Easing {
+    points: Vec<Point>,
+    dragging: Option<usize>,
+    started: f32,
+}
+
+const EASE_PERIOD: f32 = 3.0;
+
+impl Easing {
+    fn new(now: f32) -> Self {
+        Easing {
+            points: vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.3, 0.1),
+                Point::new(0.7, 0.9),
+                Point::new(1.0, 1.0),
+            ],
+            dragging: None,
+            started: now,
+        }
+    }
+
+    // The curve through `points` as a track, with the ease scaled onto its
+    // x-range.
+    fn track(&self, points: &[Point], spline_type: SplineType) -> Option<Track> {
+        let spline = Spline::try_new(points, spline_type).ok()?;
+        let (min, max) = spline.domain();
+        let scaled: Vec<Point> = self
+            .points
+            .iter()
+            .map(|p| Point::new(min + p.x * (max - min), min + p.y * (max - min)))
+            .collect();
+        let mut track = Track::new(spline);
+        track.set_ease(Some(Spline::try_new(&scaled, spline_type).ok()?));
+        Some(track)
+    }
+}
+
+fn ease_panel(rect: Rect) -> Rect {
+    Rect::from_w_h(220.0, 220.0).bottom_left_of(rect.pad(30.0))
+}
+
+fn ease_to_screen(panel: Rect, p: Point) -> Vec2 {
+    vec2(panel.left() + p.x * panel.w(), panel.bottom() + p.y * panel.h())
+}
+
+// Drags the ease point under the mouse, or adds one. The end points stay
+// at the start and end of playback.
+fn pick_ease_point(rect: Rect, easing: &mut Easing, mouse: Vec2) {
+    let panel = ease_panel(rect);
+    if let Some(i) = easing.points.iter().position(|&p| ease_to_screen(panel, p).distance(mouse) < 10.0) {
+        easing.dragging = Some(i);
+        return;
+    }
+    let p = Point::new(
+        ((mouse.x - panel.left()) / panel.w()).clamp(0.0, 1.0),
+        ((mouse.y - panel.bottom()) / panel.h()).clamp(0.0, 1.0),
+    );
+    let i = easing.points.partition_point(|q| q.x < p.x);
+    if i > 0 && i < easing.points.len() && easing.points[i].x != p.x {
+        easing.points.insert(i, p);
+        easing.dragging = Some(i);
+    }
+}
+
+fn drag_ease_point(rect: Rect, easing: &mut Easing, pos: Vec2) {
+    let panel = ease_panel(rect);
+    if let Some(i) = easing.dragging {
+        let last = easing.points.len() - 1;
+        let y = ((pos.y - panel.bottom()) / panel.h()).clamp(0.0, 1.0);
+        // Interior points stay strictly between their neighbours.
+        let x = if i == 0 || i == last {
+            easing.points[i].x
+        } else {
+            let (lo, hi) = (easing.points[i - 1].x + 1e-3, easing.points[i + 1].x - 1e-3);
+            ((pos.x - panel.left()) / panel.w()).clamp(lo, hi)
+        };
+        easing.points[i] = Point::new(x, y);
+    }
+}
+
+fn remove_ease_point(rect: Rect, easing: &mut Easing, mouse: Vec2) {
+    let panel = ease_panel(rect);
+    let last = easing.points.len() - 1;
+    if let Some(i) = (1..last).find(|&i| ease_to_screen(panel, easing.points[i]).distance(mouse) < 10.0) {
+        easing.points.remove(i);
+    }
+}
+
+// What the ease panel plays back: the ease, its polyline in the unit
+// square, and the curve retimed by it.
+type EasePlan = (Option<Spline>, Vec<Point>, Option<Track>);
+
+fn draw_easing(draw: &Draw, rect: Rect, model: &Model, easing: &Easing, now: f32) {
+    let panel = ease_panel(rect);
+    draw.rect().xy(panel.xy()).wh(panel.wh()).color(rgba(0.0, 0.0, 0.0, 0.6));
+    draw.line()
+        .start(panel.bottom_left())
+        .end(panel.top_right())
+        .weight(1.0)
+        .color(rgba(1.0, 1.0, 1.0, 0.2));
+
+    let phase = ((now - easing.started) / EASE_PERIOD).fract();
+    let plan = model.frame_cache.easing.get(frame_key(rect, model), || {
+        let ease = Spline::try_new(&easing.points, model.current_spline_type).ok();
+        let samples = ease.as_ref().map_or_else(Vec::new, |ease| ease.samples(101));
+        let track = easing.track(&model.control_points, model.current_spline_type);
+        (ease, samples, track)
+    });
+    let (ease, samples, track) = &*plan;
+    if let Some(ease) = ease {
+        draw.polyline()
+            .weight(2.0)
+            .points(samples.iter().map(|&p| ease_to_screen(panel, p)))
+            .color(rgb_u32(0xFF66CC));
+        let playhead = ease_to_screen(panel, Point::new(phase, ease.evaluate(phase)));
+        draw.ellipse().xy(playhead).radius(4.0).color(WHITE);
+    }
+    for &p in &easing.points {
+        draw.ellipse().xy(ease_to_screen(panel, p)).radius(5.0).color(rgb_u32(0xFF66CC));
+    }
+    draw.text(model.locale.get("hud.easing"))
+        .x_y(panel.x(), panel.top() + 12.0)
+        .w(panel.w())
+        .color(WHITE)
+        .font_size(12);
+
+    let track = match track {
+        Some(track) => track,
+        None => return,
+    };
+    let (min, max) = track.curve().domain();
+    let t = min + phase * (max - min);
+    // Unretimed, for comparison, then the eased marker.
+    draw.ellipse()
+        .x_y(t, track.curve().evaluate(t))
+        .radius(6.0)
+        .color(rgba(1.0, 1.0, 1.0, 0.3));
+    draw.ellipse()
+        .x_y(track.remap(t), track.evaluate(t))
+        .radius(9.0)
+        .color(rgb_u32(0xFF66CC));
+}
+
+const SPECTRUM_SAMPLES: usize = 1024;
+const SHOWN_HARMONICS: usize = 32;
+
+// The curve as one waveform cycle, in data units.
+fn spectrum(rect: Rect, model: &Model) -> Option<Vec<f32>> {
+    let spline = Spline::try_new(&data_points(rect, model), model.current_spline_type).ok()?;
+    Some(spline.harmonic_spectrum(SPECTRUM_SAMPLES))
+}
+
+// Bars for harmonics 1 to SHOWN_HARMONICS in the bottom-right corner,
+// scaled to the strongest.
+fn draw_spectrum(draw: &Draw, rect: Rect, t: &Locales, spectrum: &[f32]) {
+    let panel = Rect::from_w_h(420.0, 160.0).bottom_right_of(rect.pad(20.0));
+    draw.rect().xy(panel.xy()).wh(panel.wh()).color(rgba(0.0, 0.0, 0.0, 0.6));
+
+    let harmonics = &spectrum[1..=SHOWN_HARMONICS.min(spectrum.len() - 1)];
+    let strongest = harmonics.iter().fold(f32::EPSILON, |m, &a| m.max(a));
+    let slot = (panel.w() - 20.0) / SHOWN_HARMONICS as f32;
+    let floor = panel.bottom() + 20.0;
+    for (i, &amplitude) in harmonics.iter().enumerate() {
+        let height = (panel.h() - 40.0) * amplitude / strongest;
+        let x = panel.left() + 10.0 + slot * (i as f32 + 0.5);
+        draw.rect()
+            .x_y(x, floor + height / 2.0)
+            .w_h(slot * 0.7, height.max(1.0))
+            .color(rgb_u32(0xFFCC00));
+        if i == 0 || (i + 1) % 8 == 0 {
+            draw.text(&format!("{}", i + 1))
+                .x_y(x, floor - 10.0)
+                .color(WHITE)
+                .font_size(10);
+        }
+    }
+    draw.text(t.get("hud.harmonics"))
+        .x_y(panel.x(), panel.top() - 12.0)
+        .w(panel.w())
+        .color(WHITE)
+        .font_size(12);
+}
+
+// The control points' y values are angles on the [-π, π) axis; the wrapped
+// curve is broken wherever it crosses the seam.
+fn draw_angle_curve(draw: &Draw, rect: Rect, model: &Model) {
+    let angles: Vec<Point> = model
+        .control_points
+        .iter()
+        .map(|&p| screen_to_data(rect, &model.x_axis, &model.y_axis, p))
+        .collect();
+    let spline = match AngleSpline::try_new(&angles, model.current_spline_type) {
+        Ok(spline) => spline,
+        Err(_) => return,
+    };
+
+    let mut runs: Vec<Vec<Vec2>> = vec![Vec::new()];
+    let mut previous: Option<f32> = None;
+    for p in spline.spline().samples(DENSE_SAMPLES) {
+        let angle = wrap_angle(p.y);
+        if previous.is_some_and(|previous| (angle - previous).abs() > PI) {
+            runs.push(Vec::new());
+        }
+        previous = Some(angle);
+        let screen = data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(p.x, angle));
+        runs.last_mut().unwrap().push(pt2(screen.x, screen.y));
+    }
+
+    for run in runs.into_iter().filter(|run| run.len() >= 2) {
+        draw.polyline().weight(3.0).points(run).color(rgb_u32(0x00FFAA));
+    }
+}
+
+fn draw_limit_lines(draw: &Draw, rect: Rect, model: &Model) {
+    let ((left, right), _) = canvas_extent(rect);
+    let color = rgb_u32(0xFF6666);
+    for value in [model.limits.min, model.limits.max].into_iter().flatten() {
+        let y = data_to_screen(rect, &model.x_axis, &model.y_axis, Point::new(model.x_axis.min, value)).y;
+        if !y.is_finite() {
+            continue;
+        }
+        draw.line().start(pt2(left, y)).end(pt2(right, y)).weight(1.0).color(color);
+        draw.text(&value.to_string()).x_y(right + 20.0, y).color(color).font_size(12);
+    }
+}
+
+fn draw_shape_constraint(draw: &Draw, rect: Rect, model: &Model, constraint: &ShapeConstraint) {
+    let (_, amplitude) = canvas_extent(rect);
+    let to_screen = |p: Point| data_to_screen(rect, &model.x_axis, &model.y_axis, p);
+    let points = data_points(rect, model);
+    if points.len() < 2 {
+        return;
+    }
+
+    for x in [constraint.min_x, constraint.max_x] {
+        let sx = to_screen(Point::new(x, model.y_axis.min)).x;
+        draw.line()
+            .start(pt2(sx, -amplitude))
+            .end(pt2(sx, amplitude))
+            .weight(1.0)
+            .color(rgb_u32(0xFF9900));
+    }
+
+    let spline = match Spline::try_new(&points, model.current_spline_type) {
+        Ok(spline) => spline,
+        Err(_) => return,
+    };
+    let violations = spline.shape_violations(&[*constraint]);
+    if violations.is_empty() {
+        return;
+    }
+
+    // What applying the constraint gives, fitted once per edit.
+    let fitted = model.frame_cache.shape_fit.get(frame_key(rect, model), || {
+        Spline::fit_shape_constrained(&points, &[*constraint]).ok()
+    });
+    if let Some(fitted) = fitted.as_ref() {
+        draw.polyline()
+            .weight(2.0)
+            .points(fitted.samples(DENSE_SAMPLES).iter().map(|&p| {
+                let s = to_screen(p);
+                pt2(s.x, s.y)
+            }))
+            .color(rgb_u32(0xFF9900));
+    }
+    for (lo, hi) in violations {
+        let (left, right) = (to_screen(Point::new(lo, 0.0)).x, to_screen(Point::new(hi, 0.0)).x);
+        draw.rect()
+            .x_y((left + right) / 2.0, 0.0)
+            .w_h((right - left).abs().max(2.0), 2.0 * amplitude)
+            .color(rgba(1.0, 0.2, 0.2, 0.2));
+        let span: Vec<Vec2> = (0..=32)
+            .map(|i| {
+                let x = lo + (hi - lo) * i as f32 / 32.0;
+                let s = to_screen(Point::new(x, spline.evaluate(x)));
+                pt2(s.x, s.y)
+            })
+            .collect();
+        draw.polyline().weight(5.0).points(span).color(rgb_u32(0xFF3333));
+    }
+}
+
+// Reads x as distance and y as elevation, both in metres unless the axes
+// say otherwise.
+fn toggle_terrain_mode(model: &mut Model) {
+    if let Some(max_grade) = model.max_grade.take() {
+        model.default_max_grade = max_grade;
+        return;
+    }
+    model.max_grade = Some(model.default_max_grade);
+    for (axis, title) in [(&mut model.x_axis, "Distance"), (&mut model.y_axis, "Elevation")] {
+        if axis.title.is_none() && axis.unit.is_none() {
+            axis.title = Some(title.to_string());
+            axis.unit = Some("m".to_string());
+        }
+    }
+}
+
+// The curve in data units, which terrain mode grades.
+fn terrain_spline(rect: Rect, model: &Model) -> Option<Spline> {
+    Spline::try_new(&data_points(rect, model), model.current_spline_type).ok()
+}
+
+// Each piece's average grade, and its steepest grade with where that is,
+// in percent.
+fn segment_grades(spline: &Spline) -> Vec<(Segment<'_>, f32, f32)> {
+    spline
+        .segments()
+        .map(|s| {
+            let average = (s.eval(s.x1) - s.eval(s.x0)) / (s.x1 - s.x0);
+            (s, 100.0 * average, 100.0 * s.steepest().slope)
+        })
+        .collect()
+}
+
+fn draw_grades(draw: &Draw, rect: Rect, model: &Model, max_grade: f32) {
+    let to_screen = |p: Point| data_to_screen(rect, &model.x_axis, &model.y_axis, p);
+    let spline = match terrain_spline(rect, model) {
+        Some(spline) => spline,
+        None => return,
+    };
+    for (segment, average, steepest) in segment_grades(&spline) {
+        let too_steep = steepest.abs() > max_grade;
+        if too_steep {
+            let span: Vec<Vec2> = (0..=32)
+                .map(|i| {
+                    let x = segment.x0 + (segment.x1 - segment.x0) * i as f32 / 32.0;
+                    let s = to_screen(Point::new(x, segment.eval(x)));
+                    pt2(s.x, s.y)
+                })
+                .collect();
+            draw.polyline().weight(6.0).points(span).color(rgb_u32(0xFF3333));
+        }
+
+        let mid = (segment.x0 + segment.x1) / 2.0;
+        let label = to_screen(Point::new(mid, segment.eval(mid)));
+        let (average, steepest) = (format!("{:+.1}", average), format!("{:+.1}", steepest));
+        draw.text(&model.locale.format("hud.grade", &[("average", &average), ("max", &steepest)]))
+            .x_y(label.x, label.y - 24.0)
+            .w(160.0)
+            .color(if too_steep { rgb_u32(0xFF6666) } else { rgb_u32(0xCCDDEE) })
+            .font_size(12);
+    }
+}
+
+fn draw_runge_overlay(draw: &Draw, rect: Rect, points: &[Point]) {
+    let unit_points: Vec<Point> = points.iter().map(|&p| screen_to_runge(rect, p)).collect();
+    let polynomial = PolynomialInterpolant::new(&unit_points);
+    let xs = (0..=400).map(|i| -1.0 + 2.0 * i as f32 / 400.0);
+
+    let truth = xs.clone().map(|x| {
+        let p = runge_to_screen(rect, x, runge(x));
+        pt2(p.x, p.y)
+    });
+    draw.polyline().weight(1.5).points(truth).color(rgb_u32(0x888888));
+
+    let interpolant = xs.map(|x| {
+        let p = runge_to_screen(rect, x, polynomial.evaluate(x));
+        pt2(p.x, p.y.clamp(rect.bottom() * 2.0, rect.top() * 2.0))
+    });
+    draw.polyline().weight(1.5).points(interpolant).color(rgb_u32(0xFFAA33));
+}
+
+// Short enough to read off the screen and pass back in with `--seed`.
+fn fresh_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    nanos as u64 % 1_000_000
+}
+
+// Two knots can't share an x, so clicks and drags onto an occupied column
+// are ignored.
+fn add_point(model: &mut Model, point: Point) {
+    match model.spline.as_mut() {
+        Some(spline) => {
+            let i = match spline.insert_point(point) {
+                Ok(i) => i,
+                Err(_) => return,
+            };
+            model.control_points.insert(i, point);
+            reindex_point_anchors(model, |k| if k >= i { k + 1 } else { k });
+            refit_custom_curve(model);
+        }
+        None => {
+            if model.control_points.iter().any(|p| p.x == point.x) {
+                return;
+            }
+            model.control_points.push(point);
+            rebuild_spline(model);
+        }
+    }
+}
+
+fn move_point(model: &mut Model, i: usize, point: Point) -> usize {
+    match model.spline.as_mut() {
+        Some(spline) => {
+            let j = match spline.set_point(i, point) {
+                Ok(j) => j,
+                Err(_) => return i,
+            };
+            model.revision += 1;
+            model.control_points.remove(i);
+            model.control_points.insert(j, point);
+            reindex_point_anchors(model, |k| match k {
+                k if k == i => j,
+                k if i < k && k <= j => k - 1,
+                k if j <= k && k < i => k + 1,
+                k => k,
+            });
+            refit_custom_curve(model);
+            j
+        }
+        None => {
+            model.revision += 1;
+            model.control_points[i] = point;
+            i
+        }
+    }
+}
+
+// Keeps point anchors and tags on the same point when insertion or a move
+// past a neighbour changes its index.
+fn reindex_point_anchors(model: &mut Model, new_index: impl Fn(usize) -> usize) {
+    for annotation in &mut model.annotations {
+        if let Anchor::Point(k) = &mut annotation.anchor {
+            *k = new_index(*k);
+        }
+    }
+    for tag in &mut model.tags {
+        tag.point = new_index(tag.point);
+    }
+}
+
+const ANNOTATION_OFFSET: (f32, f32) = (40.0, 50.0);
+
+// Anchors to the control point under the mouse if there is one, or else to
+// the mouse position itself.
+fn start_annotation(model: &mut Model, mouse: Vec2) {
+    let anchor = point_under(model, mouse).map_or(Anchor::Position(Point::new(mouse.x, mouse.y)), Anchor::Point);
+    model.annotation_draft = Some(Annotation {
+        text: String::new(),
+        anchor,
+        offset: Point::new(ANNOTATION_OFFSET.0, ANNOTATION_OFFSET.1),
+    });
+    model.skip_next_character = true;
+    model.status = Some(model.locale.get("status.annotate").to_string());
+}
+
+fn remove_nearest_annotation(model: &mut Model, mouse: Vec2) {
+    let points = &model.control_points;
+    let distance = |a: &Annotation| {
+        [a.anchor_position(points), a.label_position(points)]
+            .into_iter()
+            .flatten()
+            .map(|p| (p.x - mouse.x).hypot(p.y - mouse.y))
+            .fold(f32::INFINITY, f32::min)
+    };
+    let nearest = (0..model.annotations.len())
+        .filter(|&i| distance(&model.annotations[i]) < 30.0)
+        .min_by(|&a, &b| distance(&model.annotations[a]).total_cmp(&distance(&model.annotations[b])));
+    if let Some(i) = nearest {
+        model.annotations.remove(i);
+    }
+}
+
+// Typing takes over the keyboard until the annotation is placed or dropped.
+fn edit_annotation_draft(model: &mut Model, key: Key) {
+    match key {
+        Key::Return => {
+            if let Some(draft) = model.annotation_draft.take() {
+                if !draft.text.is_empty() {
+                    model.annotations.push(draft);
+                }
+            }
+            model.status = None;
+        }
+        Key::Escape => {
+            model.annotation_draft = None;
+            model.status = None;
+        }
+        Key::Back => {
+            if let Some(draft) = &mut model.annotation_draft {
+                draft.text.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn toggle_keyboard_editing(model: &mut Model) {
+    if model.keyboard_focus.take().is_some() {
+        model.status = None;
+        return;
+    }
+    model.keyboard_focus = Some(0);
+    let keys = &model.config.keyboard;
+    model.status = Some(model.locale.format(
+        "status.keyboard_editing",
+        &[
+            ("next", &keys.next.name()),
+            ("previous", &keys.previous.name()),
+            ("add", &keys.add.name()),
+            ("delete", &keys.delete.name()),
+            ("left", &keys.left.name()),
+            ("right", &keys.right.name()),
+            ("up", &keys.up.name()),
+            ("down", &keys.down.name()),
+            ("exit", &shortcut_name(model, Action::KeyboardEditing)),
+        ],
+    ));
+}
+
+// Returns whether the key was one of the mode's, leaving the rest to their
+// usual actions.
+fn edit_with_keyboard(model: &mut Model, key: Key, shift: bool) -> bool {
+    let n = model.control_points.len();
+    let focus = model.keyboard_focus.unwrap_or(0).min(n.saturating_sub(1));
+    let keys = &model.config.keyboard;
+    let step = |binding: &Binding| {
+        if binding.matches(key, shift, false) {
+            Some(keys.step)
+        } else if binding.key == key && !binding.shift && shift {
+            Some(keys.step * 5.0)
+        } else {
+            None
+        }
+    };
+    let offset = if let Some(step) = step(&keys.left) {
+        Some((-step, 0.0))
+    } else if let Some(step) = step(&keys.right) {
+        Some((step, 0.0))
+    } else if let Some(step) = step(&keys.up) {
+        Some((0.0, step))
+    } else {
+        step(&keys.down).map(|step| (0.0, -step))
+    };
+    let editable = can_edit_group(model) && model.layered.is_none();
+
+    let focus = if keys.next.matches(key, shift, false) {
+        (focus + 1) % n.max(1)
+    } else if keys.previous.matches(key, shift, false) {
+        (focus + n.max(1) - 1) % n.max(1)
+    } else if keys.add.matches(key, shift, false) && editable {
+        add_point_after(model, focus).unwrap_or(focus)
+    } else if keys.delete.matches(key, shift, false) && editable && n > 0 {
+        remove_point(model, focus);
+        focus.min(n.saturating_sub(2))
+    } else if let (Some((dx, dy)), true, true) = (offset, editable, n > 0) {
+        let p = model.control_points[focus];
+        move_point(model, focus, Point::new(p.x + dx, p.y + dy))
+    } else {
+        return false;
+    };
+    model.keyboard_focus = Some(focus);
+    true
+}
+
+// Halfway to the next point, on the curve, or a little past the last one.
+// Returns where the new point landed.
+fn add_point_after(model: &mut Model, i: usize) -> Option<usize> {
+    let points = &model.control_points;
+    let point = match (points.get(i), points.get(i + 1)) {
+        (Some(a), Some(b)) => {
+            let x = (a.x + b.x) / 2.0;
+            let y = model.spline.as_ref().map_or((a.y + b.y) / 2.0, |s| s.evaluate(x));
+            Point::new(x, y)
+        }
+        (Some(a), None) => Point::new(a.x + 40.0, a.y),
+        (None, _) => Point::new(0.0, 0.0),
+    };
+    add_point(model, point);
+    model.control_points.iter().position(|p| *p == point)
+}
+
+// Annotations and tags on the point go with it.
+fn remove_point(model: &mut Model, i: usize) {
+    model.control_points.remove(i);
+    model.annotations.retain(|a| !matches!(a.anchor, Anchor::Point(k) if k == i));
+    model.tags.retain(|t| t.point != i);
+    reindex_point_anchors(model, |k| if k > i { k - 1 } else { k });
+    model.dragging_point = None;
+    rebuild_spline(model);
+}
+
+// A ring on the focused point, labelled with where it is in data units.
+fn draw_keyboard_focus(draw: &Draw, rect: Rect, model: &Model, focus: usize) {
+    let color = rgb_u32(0xFFCC00);
+    let n = model.control_points.len();
+    let (label, text) = match model.control_points.get(focus.min(n.saturating_sub(1))) {
+        Some(&p) => {
+            draw.ellipse()
+                .x_y(p.x, p.y)
+                .radius(14.0)
+                .no_fill()
+                .stroke(color)
+                .stroke_weight(3.0);
+            let data = screen_to_data(rect, &model.x_axis, &model.y_axis, p);
+            let (x, y) = (format!("{:.4}", data.x), format!("{:.4}", data.y));
+            let number = focus.min(n - 1) + 1;
+            let text = model
+                .locale
+                .format("hud.keyboard_focus", &[("number", &number), ("count", &n), ("x", &x), ("y", &y)]);
+            (pt2(p.x, p.y + 30.0), text)
+        }
+        None => {
+            let add = model.config.keyboard.add.name();
+            (pt2(0.0, 0.0), model.locale.format("hud.keyboard_no_points", &[("add", &add)]))
+        }
+    };
+    draw.text(&text).xy(label).w(300.0).color(color).font_size(14);
+}
+
+fn point_under(model: &Model, mouse: Vec2) -> Option<usize> {
+    model
+        .control_points
+        .iter()
+        .position(|p| (p.x - mouse.x).hypot(p.y - mouse.y) < 15.0)
+}
+
+fn start_tag(model: &mut Model, mouse: Vec2) {
+    match point_under(model, mouse) {
+        Some(i) => {
+            model.tag_draft = Some((i, String::new()));
+            model.skip_next_character = true;
+            model.status = Some(model.locale.get("status.tag").to_string());
+        }
+        None => model.status = Some(model.locale.get("status.tag_needs_point").to_string()),
+    }
+}
+
+fn clear_tags(model: &mut Model, mouse: Vec2) {
+    if let Some(i) = point_under(model, mouse) {
+        model.tags.retain(|t| t.point != i);
+    }
+}
+
+// A tag replaces any with the same key on that point.
+fn edit_tag_draft(model: &mut Model, key: Key) {
+    match key {
+        Key::Return => {
+            if let Some((i, text)) = model.tag_draft.take() {
+                match PointTag::parse(i, &text) {
+                    Some(tag) => {
+                        model.tags.retain(|t| t.point != i || t.key != tag.key);
+                        model.tags.push(tag);
+                        model.status = None;
+                    }
+                    None => model.warning = Some(model.locale.format("warnings.tag_format", &[("text", &text)])),
+                }
+            }
+        }
+        Key::Escape => {
+            model.tag_draft = None;
+            model.status = None;
+        }
+        Key::Back => {
+            if let Some((_, text)) = &mut model.tag_draft {
+                text.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn draw_tags(draw: &Draw, model: &Model) {
+    let color = rgb_u32(0xAACCFF);
+    let mut lines: Vec<(usize, String)> = model
+        .tags
+        .iter()
+        .map(|t| (t.point, format!("{}:{}", t.key, t.value)))
+        .collect();
+    if let Some((i, text)) = &model.tag_draft {
+        lines.push((*i, format!("{}|", text)));
+    }
+    for (i, p) in model.control_points.iter().enumerate() {
+        for (row, (_, line)) in lines.iter().filter(|(point, _)| *point == i).enumerate() {
+            draw.text(line)
+                .x_y(p.x, p.y - 20.0 - 14.0 * row as f32)
+                .w(200.0)
+                .color(color)
+                .font_size(11);
+        }
+    }
+}
+
+// Matches scrolled past stay reachable with Up and Down.
+const PALETTE_ROWS: usize = 12;
+
+// Every action the palette offers in the current mode, and its label in the current language.
+fn palette_actions(model: &Model) -> (Vec<Action>, Vec<String>) {
+    let scope = current_scope(model);
+    let actions: Vec<Action> = Action::ALL
+        .iter()
+        .copied()
+        .filter(|&a| a != Action::CommandPalette && a.works_in(scope))
+        .collect();
+    let labels = actions.iter().map(|&a| action_label(&model.locale, a)).collect();
+    (actions, labels)
+}
+
+fn action_label(t: &Locales, action: Action) -> String {
+    match action {
+        Action::LoadPreset(preset) => t.format("actions.load_preset", &[("name", &preset.name())]),
+        Action::ToggleDetailLayer(number) => t.format("actions.toggle_detail_layer", &[("number", &number)]),
+        _ => t.get(&format!("actions.{}", action.id())).to_string(),
+    }
+}
+
+fn shortcut_name(model: &Model, action: Action) -> String {
+    match model.config.shortcut(action) {
+        Some(binding) => binding.name(),
+        None => model.locale.get("palette.unbound").to_string(),
+    }
+}
+
+// Typed characters arrive through `received_character`.
+fn edit_palette(app: &App, model: &mut Model, key: Key) {
+    let (actions, labels) = palette_actions(model);
+    let palette = match &mut model.palette {
+        Some(palette) => palette,
+        None => return,
+    };
+    let matches = palette.ranked(&labels);
+    match key {
+        Key::Escape => model.palette = None,
+        Key::Up => palette.selected = palette.selected.saturating_sub(1),
+        Key::Down => palette.selected = (palette.selected + 1).min(matches.len().saturating_sub(1)),
+        Key::Back => {
+            palette.query.pop();
+            palette.selected = 0;
+        }
+        Key::Return => {
+            let action = matches.get(palette.selected).map(|&i| actions[i]);
+            model.palette = None;
+            if let Some(action) = action {
+                perform(app, model, action);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn draw_palette(draw: &Draw, rect: Rect, model: &Model, palette: &Palette) {
+    let t = &model.locale;
+    let (actions, labels) = palette_actions(model);
+    let matches = palette.ranked(&labels);
+    let (width, row) = (640.0, 24.0);
+    let rows = matches.len().clamp(1, PALETTE_ROWS);
+    let top = rect.top() - 80.0;
+    let height = row * (rows + 1) as f32 + 16.0;
+    draw.rect()
+        .x_y(0.0, top - height / 2.0)
+        .w_h(width, height)
+        .color(rgba(0.05, 0.07, 0.1, 0.95));
+
+    let (query, color) = if palette.query.is_empty() {
+        (t.get("palette.prompt").to_string(), rgb_u32(0x778899))
+    } else {
+        (format!("{}|", palette.query), rgb_u32(0xFFFFFF))
+    };
+    let text_width = width - 30.0;
+    draw.text(&query)
+        .x_y(0.0, top - row)
+        .w(text_width)
+        .left_justify()
+        .color(color)
+        .font_size(16);
+
+    if matches.is_empty() {
+        draw.text(t.get("palette.no_matches"))
+            .x_y(0.0, top - 2.0 * row - 8.0)
+            .w(text_width)
+            .left_justify()
+            .color(rgb_u32(0x778899))
+            .font_size(14);
+    }
+    // Scroll so the selection stays on the last row at most.
+    let first = palette.selected.saturating_sub(PALETTE_ROWS - 1);
+    for (k, &i) in matches.iter().enumerate().skip(first).take(PALETTE_ROWS) {
+        let y = top - row * (k - first + 2) as f32 - 8.0;
+        if k == palette.selected {
+            draw.rect().x_y(0.0, y).w_h(width - 10.0, row).color(rgb_u32(0x335577));
+        }
+        draw.text(&labels[i])
+            .x_y(0.0, y)
+            .w(text_width)
+            .left_justify()
+            .color(WHITE)
+            .font_size(14);
+        draw.text(&shortcut_name(model, actions[i]))
+            .x_y(0.0, y)
+            .w(text_width)
+            .right_justify()
+            .color(rgb_u32(0x99AABB))
+            .font_size(14);
+    }
+}
+
+fn received_character(_app: &App, model: &mut Model, c: char) {
+    if std::mem::take(&mut model.skip_next_character) {
+        return;
+    }
+    if let Some((_, text)) = &mut model.tag_draft {
+        if !c.is_control() {
+            text.push(c);
+        }
+    }
+    if let Some(palette) = &mut model.palette {
+        if !c.is_control() {
+            palette.query.push(c);
+            palette.selected = 0;
+        }
+        return;
+    }
+    if let Some(draft) = &mut model.annotation_draft {
+        if !c.is_control() {
+            draft.text.push(c);
+        }
+    }
+    if let Some(sketch) = &mut model.glyph {
+        if c.is_alphanumeric() {
+            name_glyph(sketch, c);
+        }
+    }
+    if let Some(designer) = &mut model.gradient {
+        if c.is_ascii_hexdigit() && designer.selected.is_some() && designer.hex.len() < 6 {
+            designer.hex.push(c.to_ascii_lowercase());
+        }
+    }
+}
+
+fn draw_annotation(draw: &Draw, points: &[Point], annotation: &Annotation, text: &str) {
+    let (anchor, label) = match (annotation.anchor_position(points), annotation.label_position(points)) {
+        (Some(anchor), Some(label)) => (anchor, label),
+        _ => return,
+    };
+    let color = rgb_u32(0xFFEEAA);
+    draw.line()
+        .start(pt2(anchor.x, anchor.y))
+        .end(pt2(label.x, label.y))
+        .weight(1.0)
+        .color(color);
+    draw.ellipse().x_y(anchor.x, anchor.y).radius(3.0).color(color);
+    draw.text(text)
+        .x_y(label.x, label.y + 10.0)
+        .w(240.0)
+        .left_justify()
+        .color(color)
+        .font_size(14);
+}
+
+// A third pick starts a new measurement.
+fn pick_ruler_point(model: &mut Model, p: Point) {
+    if let Some(picks) = &mut model.ruler {
+        if picks.len() == 2 {
+            picks.clear();
+        }
+        picks.push(p);
+    }
+}
+
+// Everything is measured in data units. The arc runs along the curve between
+// the picks' projections straight up or down onto it.
+fn ruler_readout(rect: Rect, model: &Model, a: Point, b: Point) -> String {
+    let to_data = |p: Point| screen_to_data(rect, &model.x_axis, &model.y_axis, p);
+    let (a, b) = (to_data(a), to_data(b));
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let unit = |axis: &Axis| axis.unit.as_ref().map_or(String::new(), |u| format!(" {}", u));
+
+    let t = &model.locale;
+    let mut text = t.format(
+        "hud.ruler",
+        &[
+            ("distance", &format!("{:.4}", dx.hypot(dy))),
+            ("dx", &format!("{:.4}{}", dx, unit(&model.x_axis))),
+            ("dy", &format!("{:.4}{}", dy, unit(&model.y_axis))),
+            ("slope", &format!("{:.4}", dy / dx)),
+            ("angle", &format!("{:.1}", dy.atan2(dx).to_degrees())),
+        ],
+    );
+    if let Ok(spline) = Spline::try_new(&data_points(rect, model), model.current_spline_type) {
+        let length = format!("{:.4}", spline.arc_length(a.x, b.x));
+        text.push_str(&t.format("hud.ruler_arc_length", &[("length", &length)]));
+    }
+    text
+}
+
+fn draw_ruler(draw: &Draw, model: &Model, picks: &[Point]) {
+    let color = rgb_u32(0x66CCFF);
+    for p in picks {
+        draw.ellipse().x_y(p.x, p.y).radius(5.0).no_fill().stroke(color).stroke_weight(2.0);
+    }
+    let (a, b) = match picks {
+        [a, b] => (*a, *b),
+        _ => return,
+    };
+    draw.line().start(pt2(a.x, a.y)).end(pt2(b.x, b.y)).weight(1.5).color(color);
+
+    if let Some(spline) = &model.spline {
+        let (lo, hi) = (a.x.min(b.x), a.x.max(b.x));
+        let arc: Vec<Vec2> = (0..=64)
+            .map(|i| {
+                let x = lo + (hi - lo) * i as f32 / 64.0;
+                pt2(x, spline.evaluate(x))
+            })
+            .collect();
+        draw.polyline().weight(5.0).points(arc).color(rgba(0.4, 0.8, 1.0, 0.6));
+        for p in [a, b] {
+            draw.line()
+                .start(pt2(p.x, p.y))
+                .end(pt2(p.x, spline.evaluate(p.x)))
+                .weight(1.0)
+                .color(color);
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum SplineType {
-    Linear,
-    Quadratic,
-    Cubic,
-}
+fn draw_live_signal(draw: &Draw, rect: Rect, live: &LiveSignal, now: f32) {
+    let right = rect.right() * 0.85;
+    let to_screen_x = |t: f32| right - (now - t) * LiveSignal::PIXELS_PER_SECOND;
 
-struct Spline {
-    points: Vec<Point>,
-    spline_type: SplineType,
-    a_coeffs: Vec<f32>,
-    b_coeffs: Vec<f32>,
-    c_coeffs: Vec<f32>,
-    d_coeffs: Vec<f32>,
-}
+    if let Some((start, end)) = live.spline.domain() {
+        let samples = live.spline.len() * 4;
+        let curve = (0..=samples).map(|i| {
+            let t = start + (end - start) * i as f32 / samples as f32;
+            pt2(to_screen_x(t), live.spline.evaluate(t))
+        });
+        draw.polyline()
+            .weight(3.0)
+            .points(curve)
+            .color(rgb_u32(0x00FFAA));
 
-impl Spline {
-    fn new(points: &[Point], spline_type: SplineType) -> Self {
-        if points.len() < 2 {
-            panic!("Need at least 2 points to interpolate;");
+        if let Some(smoothing) = &live.smoothing {
+            let curve = (0..=samples).map(|i| {
+                let t = start + (end - start) * i as f32 / samples as f32;
+                pt2(to_screen_x(t), smoothing.evaluate(t))
+            });
+            draw.polyline()
+                .weight(3.0)
+                .points(curve)
+                .color(rgb_u32(0xFFCC00));
         }
-        let mut sorted_points = points.to_vec();
-        sorted_points.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+    }
 
-        let n = sorted_points.len();
-        let x_coords: Vec<f32> = sorted_points.iter().map(|p| p.x).collect();
-        let y_coords: Vec<f32> = sorted_points.iter().map(|p| p.y).collect();
+    for p in live.spline.points() {
+        draw.ellipse()
+            .x_y(to_screen_x(p.x), p.y)
+            .radius(3.0)
+            .color(rgb_u32(0xFFFFFF));
+    }
+}
 
-        let mut h = vec![0.0; n - 1];
-        for i in 0..n - 1 {
-            h[i] = x_coords[i + 1] - x_coords[i];
-            if h[i] == 0.0 {
-                panic!("x values must be distinct for spline calculation.");
-            }
+fn draw_comparison(draw: &Draw, rect: Rect, comparison: &Comparison) {
+    for (spline, color) in [(&comparison.a, 0x00FFAA), (&comparison.b, 0xFF66CC)] {
+        let curve: Vec<Vec2> = spline.samples(DENSE_SAMPLES).iter().map(|p| pt2(p.x, p.y)).collect();
+        draw.polyline().weight(3.0).points(curve).color(rgb_u32(color));
+        for p in spline.points() {
+            draw.ellipse().x_y(p.x, p.y).radius(5.0).color(rgb_u32(color));
         }
+    }
 
-        let a_coeffs = y_coords.clone();
-        let mut b_coeffs = vec![0.0; n - 1];
-        let mut c_coeffs = vec![0.0; n - 1];
-        let mut d_coeffs = vec![0.0; n - 1];
-
-        match spline_type {
-            SplineType::Linear => {
-                for i in 0..n - 1 {
-                    b_coeffs[i] = (a_coeffs[i + 1] - a_coeffs[i]) / h[i];
-                }
-            }
-            SplineType::Quadratic => {
-                if n >= 2 {
-                    if h[0] != 0.0 {
-                        b_coeffs[0] = (a_coeffs[1] - a_coeffs[0]) / h[0];
-                        c_coeffs[0] = 0.0;
-                    }
+    let (lo, hi) = match comparison.overlap {
+        Some(overlap) => overlap,
+        None => return,
+    };
+    let last = (DENSE_SAMPLES - 1) as f32;
+    let differences: Vec<(f32, f32)> = (0..DENSE_SAMPLES)
+        .map(|i| {
+            let x = lo + (hi - lo) * i as f32 / last;
+            (x, comparison.b.evaluate(x) - comparison.a.evaluate(x))
+        })
+        .collect();
 
-                    for i in 0..n - 2 {
-                        let next_b;
-                        if h[i] != 0.0 {
-                            next_b = b_coeffs[i] + 2.0 * c_coeffs[i] * h[i];
-                        } else {
-                            next_b = 0.0;
-                        }
-
-                        if i + 1 < b_coeffs.len() {
-                            b_coeffs[i + 1] = next_b;
-                        }
-
-                        if i + 1 < c_coeffs.len() && h[i + 1] != 0.0 {
-                            c_coeffs[i + 1] = (a_coeffs[i + 2] - a_coeffs[i + 1] - b_coeffs[i + 1] * h[i + 1]) / (h[i + 1] * h[i + 1]);
-                        }
-                    }
+    // The largest deviation fills the strip, however small it is.
+    let baseline = rect.bottom() + rect.h() * 0.12;
+    let peak = differences.iter().map(|&(_, d)| d.abs()).fold(f32::EPSILON, f32::max);
+    let scale = rect.h() * 0.08 / peak;
+    draw.line()
+        .start(pt2(lo, baseline))
+        .end(pt2(hi, baseline))
+        .weight(1.0)
+        .color(rgb_u32(0x6688AA));
+    draw.polyline()
+        .weight(2.0)
+        .points(differences.iter().map(|&(x, d)| pt2(x, baseline + d * scale)))
+        .color(rgb_u32(0xFFCC00));
+}
 
-                    if n == 2 && h[0] != 0.0 {
-                        b_coeffs[0] = (a_coeffs[1] - a_coeffs[0]) / h[0];
-                        c_coeffs[0] = 0.0;
-                    }
-                }
-            }
-            SplineType::Cubic => {
-                let mut c_internal = vec![0.0; n];
+// The fixed instructions, in the order they are listed, as locale keys.
+const INSTRUCTIONS: &[&str] = &[
+    "instructions.add_point",
+    "instructions.move_point",
+    "instructions.toggle_points",
+    "instructions.reset_points",
+    "instructions.random_points",
+    "instructions.presets",
+    "instructions.sample_count",
+    "instructions.runge",
+    "instructions.adaptive_fit",
+    "instructions.smoothing",
+    "instructions.robust_smoothing",
+    "instructions.data_noise",
+    "instructions.confidence_band",
+    "instructions.live_signal",
+    "instructions.clear_points",
+    "instructions.share_string",
+    "instructions.save_session",
+    "instructions.bake",
+    "instructions.linear",
+    "instructions.quadratic",
+    "instructions.cubic",
+    "instructions.next_method",
+    "instructions.led_output",
+    "instructions.log_scale",
+    "instructions.zoom",
+    "instructions.angle_mode",
+    "instructions.limits",
+    "instructions.shape_constraint",
+    "instructions.master_points",
+    "instructions.detail_layers",
+    "instructions.groups",
+    "instructions.annotations",
+    "instructions.tags",
+    "instructions.ruler",
+    "instructions.channels",
+    "instructions.gradient",
+    "instructions.camera",
+    "instructions.glyph",
+    "instructions.tiling",
+    "instructions.spectrum",
+    "instructions.easing",
+    "instructions.terrain",
+    "instructions.quantize",
+    "instructions.noise",
+];
 
-                let mut alpha = vec![0.0; n - 1];
-                for i in 1..n - 1 {
-                    alpha[i] = 3.0 * ((a_coeffs[i + 1] - a_coeffs[i]) / h[i] - (a_coeffs[i] - a_coeffs[i - 1]) / h[i - 1]);
-                }
+fn spline_type_key(spline_type: SplineType) -> &'static str {
+    match spline_type {
+        SplineType::Linear => "spline_types.linear",
+        SplineType::Quadratic => "spline_types.quadratic",
+        SplineType::Cubic => "spline_types.cubic",
+    }
+}
 
-                let mut l = vec![0.0; n];
-                let mut mu = vec![0.0; n];
-                let mut z = vec![0.0; n];
+// The gradient designer, camera editor and glyph sketcher each draw over the
+// whole window and return their HUD lines.
+fn view_gradient(draw: &Draw, rect: Rect, model: &Model, designer: &GradientDesigner) -> Vec<String> {
+    draw_gradient_designer(draw, rect, model, designer);
+    let t = &model.locale;
+    let space = format!("{:?}", designer.space);
+    let spline_type = t.get(spline_type_key(model.current_spline_type));
+    let mut lines = vec![
+        t.format("hud.gradient", &[("space", &space), ("type", &spline_type)]),
+        t.get("hud.gradient_mouse").to_string(),
+        t.get("hud.gradient_keys").to_string(),
+    ];
+    if let Some(i) = designer.selected {
+        let [r, g, b] = designer.stops[i].color.map(|c| (c * 255.0).round() as u8);
+        lines.push(t.format(
+            "hud.gradient_stop",
+            &[
+                ("position", &format!("{:.1}", designer.stops[i].position * 100.0)),
+                ("color", &format!("{:02x}{:02x}{:02x}", r, g, b)),
+                ("hex", &designer.hex),
+            ],
+        ));
+    }
+    lines
+}
 
-                l[0] = 1.0;
+fn view_camera(draw: &Draw, rect: Rect, model: &Model, editor: &CameraEditor, time: f32) -> Vec<String> {
+    let key = frame_key(rect, model);
+    let plan = model.frame_cache.camera.get(key, || camera_plan(editor, model.current_spline_type));
+    let plan = Option::as_ref(&plan);
+    let path = plan.map(|plan| &plan.path);
+    draw_camera_plan(draw, editor, plan, time);
+    let t = &model.locale;
+    let spline_type = t.get(spline_type_key(model.current_spline_type));
+    let mut lines = vec![
+        t.format(
+            "hud.camera",
+            &[("count", &editor.keyframes.len()), ("seconds", &KEYFRAME_SECONDS), ("type", &spline_type)],
+        ),
+        t.get("hud.camera_plan").to_string(),
+        t.get("hud.camera_mouse").to_string(),
+        t.get("hud.camera_keys").to_string(),
+    ];
+    if let Some((i, is_target)) = editor.selected {
+        let keyframe = &editor.keyframes[i];
+        let [x, y, z] = if is_target { keyframe.target } else { keyframe.position };
+        let key = if is_target { "hud.camera_target" } else { "hud.camera_position" };
+        let position = format!("({:.2}, {:.2}, {:.2})", x, y, z);
+        lines.push(t.format(key, &[("time", &keyframe.time), ("position", &position)]));
+    }
+    if let (Some(path), Some(started)) = (path, editor.preview_start) {
+        let viewport = Rect::from_w_h(480.0, 270.0).bottom_right_of(rect.pad(20.0));
+        draw_camera_preview(draw, viewport, path, preview_time(path, started, time));
+    }
+    lines
+}
 
-                for i in 1..n - 1 {
-                    l[i] = 2.0 * (x_coords[i + 1] - x_coords[i - 1]) - h[i - 1] * mu[i - 1];
-                    if l[i] == 0.0 {
-                        panic!("Division by zero in cubic spline calculation (l[i])");
-                    }
-                    mu[i] = h[i] / l[i];
-                    z[i] = (alpha[i] - h[i - 1] * z[i - 1]) / l[i];
-                }
+fn view_glyph(draw: &Draw, model: &Model, sketch: &GlyphSketch) -> Vec<String> {
+    draw_glyph_sketch(draw, sketch);
+    let glyph = &sketch.glyph;
+    let t = &model.locale;
+    let unicode = glyph.unicode.map_or(String::new(), |c| format!("U+{:04X}", c as u32));
+    let mut lines = vec![
+        t.format(
+            "hud.glyph",
+            &[
+                ("name", &glyph.name),
+                ("unicode", &unicode),
+                ("count", &glyph.contours.len()),
+                ("advance", &glyph.advance),
+                ("units", &sketch.em.units_per_em),
+            ],
+        ),
+        t.get("hud.glyph_mouse").to_string(),
+        t.get("hud.glyph_keys").to_string(),
+        t.get("hud.glyph_boolean").to_string(),
+    ];
+    if let Some((op, loops)) = &sketch.boolean {
+        let op = format!("{:?}", op);
+        lines.push(t.format("hud.glyph_preview", &[("op", &op), ("count", &loops.len())]));
+    }
+    lines
+}
 
-                l[n - 1] = 1.0;
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
 
-                for j in (0..n - 1).rev() {
-                    c_internal[j] = z[j] - mu[j] * c_internal[j + 1];
+    draw.background().color(rgb_u32(0x123456));
 
-                    c_coeffs[j] = c_internal[j];
-                    b_coeffs[j] = (a_coeffs[j + 1] - a_coeffs[j]) / h[j] - h[j] * (c_internal[j + 1] + 2.0 * c_internal[j]) / 3.0;
-                    d_coeffs[j] = (c_internal[j + 1] - c_internal[j]) / (3.0 * h[j]);
-                }
-            }
-        }
+    if let Some(comparison) = &model.comparison {
+        let rect = app.window_rect();
+        draw_comparison(&draw, rect, comparison);
 
-        Spline {
-            points: sorted_points,
-            spline_type,
-            a_coeffs,
-            b_coeffs,
-            c_coeffs,
-            d_coeffs,
+        let (a, b, t) = (&comparison.a, &comparison.b, &model.locale);
+        let describe = |key, name: &String, spline: &Spline| {
+            let spline_type = t.get(spline_type_key(spline.spline_type()));
+            t.format(key, &[("name", name), ("count", &spline.points().len()), ("type", &spline_type)])
+        };
+        let delta = format!("{:+}", b.points().len() as isize - a.points().len() as isize);
+        let mut lines = vec![
+            describe("hud.compare_a", &comparison.names.0, a),
+            describe("hud.compare_b", &comparison.names.1, b),
+            t.format("hud.compare_delta", &[("delta", &delta)]),
+        ];
+        lines.push(match comparison.metrics {
+            Some(metrics) => t.format(
+                "hud.compare_deviation",
+                &[("max", &format!("{:.4}", metrics.max)), ("rms", &format!("{:.4}", metrics.rms))],
+            ),
+            None => t.get("hud.compare_disjoint").to_string(),
+        });
+        for (i, line) in lines.iter().enumerate() {
+            draw.text(line)
+                .x_y(0.0, rect.top() - 30.0 - i as f32 * 22.0)
+                .w(rect.w())
+                .color(WHITE)
+                .font_size(16);
         }
+        draw.to_frame(app, &frame).unwrap();
+        return;
     }
 
-    fn evaluate(&self, x: f32) -> f32 {
-        if self.points.is_empty() {
-            return 0.0;
+    let rect = app.window_rect();
+    let mode_hud = match current_scope(model) {
+        Scope::Gradient => model.gradient.as_ref().map(|designer| view_gradient(&draw, rect, model, designer)),
+        Scope::Camera => model.camera.as_ref().map(|editor| view_camera(&draw, rect, model, editor, app.time)),
+        Scope::Glyph => model.glyph.as_ref().map(|sketch| view_glyph(&draw, model, sketch)),
+        Scope::Canvas => None,
+    };
+    if let Some(mut lines) = mode_hud {
+        lines.extend(model.status.clone());
+        for (i, line) in lines.iter().enumerate() {
+            draw.text(line)
+                .x_y(0.0, rect.top() - 30.0 - i as f32 * 22.0)
+                .w(rect.w())
+                .color(WHITE)
+                .font_size(16);
         }
-        if self.points.len() == 1 {
-            return self.points[0].y;
+        if let Some(palette) = &model.palette {
+            draw_palette(&draw, rect, model, palette);
         }
+        draw.to_frame(app, &frame).unwrap();
+        return;
+    }
 
-        let mut i = 0;
-        while i < self.points.len() - 2 && x > self.points[i + 1].x {
-            i += 1;
-        }
+    if let Some(live) = &model.live {
+        draw_live_signal(&draw, app.window_rect(), live, app.time);
+        let top = app.window_rect().top();
+        let t = &model.locale;
+        draw.text(t.get("hud.live_signal"))
+            .x_y(0.0, top - 30.0)
+            .color(WHITE)
+            .font_size(16);
+        let lambda = format!("{:.1e}", live.lambda);
+        let smoothing_text = match &live.smoothing {
+            Some(smoothing) => t.format(
+                "hud.live_smoothing",
+                &[("lambda", &lambda), ("edf", &format!("{:.1}", smoothing.edf()))],
+            ),
+            None => t.format("hud.live_lambda", &[("lambda", &lambda)]),
+        };
+        draw.text(&smoothing_text)
+            .x_y(0.0, top - 55.0)
+            .w(app.window_rect().w())
+            .color(WHITE)
+            .font_size(14);
+        draw.to_frame(app, &frame).unwrap();
+        return;
+    }
+
+    // The Runge demo lays its points out on its own [-1, 1] scale.
+    if model.runge_point_count.is_none() {
+        draw_axes(&draw, app.window_rect(), &model.x_axis, &model.y_axis);
+    }
 
-        if x < self.points[0].x {
-            if self.spline_type == SplineType::Linear && !self.b_coeffs.is_empty() {
-                return self.a_coeffs[0] + self.b_coeffs[0] * (x - self.points[0].x);
+    if let Some(ground_truth) = &model.ground_truth {
+        for (i, p) in ground_truth.points.iter().enumerate() {
+            if model.outliers.get(i) == Some(&true) {
+                draw.ellipse().x_y(p.x, p.y).radius(4.0).color(rgb_u32(0xFF3333));
+            } else {
+                draw.ellipse().x_y(p.x, p.y).radius(1.5).color(rgb_u32(0x6688AA));
             }
-            return self.a_coeffs[0];
         }
-        if x > self.points[self.points.len() - 1].x {
-            let last_segment_idx = self.points.len().saturating_sub(2);
-            if self.spline_type == SplineType::Linear && last_segment_idx < self.b_coeffs.len() {
-                let dx_last = self.points[last_segment_idx + 1].x - self.points[last_segment_idx].x;
-                if dx_last != 0.0 {
-                    let slope = self.b_coeffs[last_segment_idx];
-                    return self.a_coeffs[last_segment_idx + 1] + slope * (x - self.points[last_segment_idx + 1].x);
-                }
-            }
-            return self.a_coeffs[self.points.len() - 1];
+    }
+
+    if let Some(smoothing) = &model.smoothing {
+        if model.show_confidence_band {
+            draw_confidence_band(&draw, smoothing, model.resolution);
         }
-        if x == self.points[self.points.len() - 1].x {
-            return self.a_coeffs[self.points.len() - 1];
+
+        let smoothed_points: Vec<Vec2> = smoothing
+            .spline()
+            .samples(model.resolution + 1)
+            .iter()
+            .map(|p| pt2(p.x, p.y))
+            .collect();
+        draw.polyline()
+            .weight(2.0)
+            .points(smoothed_points)
+            .color(rgb_u32(0xFFCC00));
+    }
+
+    if model.runge_point_count.is_some() && model.control_points.len() >= 2 {
+        draw_runge_overlay(&draw, app.window_rect(), &model.control_points);
+    }
+
+    draw_inactive_groups(&draw, model);
+    let active_hidden = model.groups[model.active_group].hidden;
+
+    if active_hidden {
+        // Neither the curve nor its points are drawn.
+    } else if let Some(layered) = &model.layered {
+        draw.polyline()
+            .weight(3.0)
+            .points(layered.points().iter().map(|p| pt2(p.x, p.y)))
+            .color(rgb_u32(0x00FFAA));
+    } else if model.angle_mode.is_some() {
+        draw_angle_curve(&draw, app.window_rect(), model);
+    } else if let Some(ref spline) = model.spline {
+        if model.limit_mode != LimitMode::Off && model.runge_point_count.is_none() {
+            draw_limit_lines(&draw, app.window_rect(), model);
         }
 
-        let dx = x - self.points[i].x;
+        let samples = curve_samples(app.window_rect(), model, spline);
+        let curve_points: Vec<Vec2> = samples.iter().map(|p| pt2(p.x, p.y)).collect();
 
-        let mut val = self.a_coeffs[i];
-        if i < self.b_coeffs.len() {
-            val += self.b_coeffs[i] * dx;
+        if curve_points.len() >= 2 {
+            draw.polyline()
+                .weight(3.0)
+                .points(curve_points)
+                .color(rgb_u32(model.groups[model.active_group].color.unwrap_or(0x00FFAA)));
         }
+    }
 
-        if self.spline_type == SplineType::Quadratic || self.spline_type == SplineType::Cubic {
-            if i < self.c_coeffs.len() {
-                val += self.c_coeffs[i] * dx * dx;
-            }
+    if let Some(constraint) = &model.shape_constraint {
+        if model.angle_mode.is_none() && model.runge_point_count.is_none() {
+            draw_shape_constraint(&draw, app.window_rect(), model, constraint);
         }
+    }
 
-        if self.spline_type == SplineType::Cubic {
-            if i < self.d_coeffs.len() {
-                val += self.d_coeffs[i] * dx * dx * dx;
-            }
+    if !active_hidden {
+        for annotation in &model.annotations {
+            draw_annotation(&draw, &model.control_points, annotation, &annotation.text);
+        }
+        if let Some(draft) = &model.annotation_draft {
+            draw_annotation(&draw, &model.control_points, draft, &format!("{}|", draft.text));
         }
-        val
+        draw_tags(&draw, model);
     }
-}
 
-struct Model {
-    control_points: Vec<Point>,
-    spline: Option<Spline>,
-    dragging_point: Option<usize>,
-    show_control_points: bool,
-    resolution: usize,
-    current_spline_type: SplineType,
-}
+    if is_channel_mode(model) {
+        draw_channel_tabs(&draw, app.window_rect(), model);
+    }
 
-fn model(app: &App) -> Model {
-    app.new_window()
-        .size(1600, 1200)
-        .title("Spline Visualization")
-        .view(view)
-        .mouse_pressed(mouse_pressed)
-        .mouse_released(mouse_released)
-        .mouse_moved(mouse_moved)
-        .key_pressed(key_pressed)
-        .build()
-        .unwrap();
+    if let (Some(tiling), Some(spline), false) = (model.tiling, &model.spline, active_hidden) {
+        draw_tiling(&draw, app.window_rect(), tiling, spline);
+    }
 
-    let control_points = vec![
-        Point::new(-300.0, 0.0),
-        Point::new(-150.0, 100.0),
-        Point::new(0.0, -100.0),
-        Point::new(150.0, 100.0),
-        Point::new(300.0, 0.0),
-    ];
+    if let (Some(max_grade), false) = (model.max_grade, active_hidden) {
+        draw_grades(&draw, app.window_rect(), model, max_grade);
+    }
 
-    let current_spline_type = SplineType::Cubic;
+    if let (Some(noise), Some(spline), None) = (&model.noise, &model.spline, model.runge_point_count) {
+        if !active_hidden {
+            draw_noisy_curve(&draw, app.window_rect(), model, spline, noise);
+        }
+    }
 
-    let spline = if control_points.len() >= 2 {
-        Some(Spline::new(&control_points, current_spline_type))
-    } else {
-        None
+    let runs = match (model.quantizer, &model.spline, model.runge_point_count) {
+        (Some(quantizer), Some(spline), None) => Some(model.frame_cache.quantized_runs.get(
+            frame_key(app.window_rect(), model),
+            || quantized_runs(app.window_rect(), model, spline, quantizer),
+        )),
+        _ => None,
     };
-
-    Model {
-        control_points,
-        spline,
-        dragging_point: None,
-        show_control_points: true,
-        resolution: 400,
-        current_spline_type,
+    if let (Some(runs), false) = (&runs, active_hidden) {
+        draw_staircase(&draw, app.window_rect(), model, runs);
     }
-}
 
-fn update(_app: &App, model: &mut Model, _update: Update) {
-    if model.control_points.len() >= 2 {
-        model.spline = Some(Spline::new(&model.control_points, model.current_spline_type));
-    } else {
-        model.spline = None;
+    let cached_spectrum = model.show_spectrum.then(|| {
+        let key = frame_key(app.window_rect(), model);
+        model.frame_cache.spectrum.get(key, || spectrum(app.window_rect(), model))
+    });
+    let harmonics = cached_spectrum.as_deref().and_then(Option::as_ref);
+    if let Some(harmonics) = harmonics {
+        draw_spectrum(&draw, app.window_rect(), &model.locale, harmonics);
     }
-}
 
-fn view(app: &App, model: &Model, frame: Frame) {
-    let draw = app.draw();
-
-    draw.background().color(rgb_u32(0x123456));
-
-    if let Some(ref spline) = model.spline {
-        let min_x = model.control_points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
-        let max_x = model.control_points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+    if let Some(easing) = &model.easing {
+        draw_easing(&draw, app.window_rect(), model, easing, app.time);
+    }
 
-        let step = (max_x - min_x) / model.resolution as f32;
-        let mut curve_points = Vec::with_capacity(model.resolution + 1);
+    if let Some(overview) = &model.overview {
+        draw_overview(&draw, app.window_rect(), model, overview);
+    }
 
-        for i in 0..=model.resolution {
-            let x = min_x + step * i as f32;
-            let y = spline.evaluate(x);
-            curve_points.push(pt2(x, y));
-        }
+    if let Some(picks) = &model.ruler {
+        draw_ruler(&draw, model, picks);
+    }
 
-        if curve_points.len() >= 2 {
-            draw.polyline()
-                .weight(3.0)
-                .points(curve_points)
-                .color(rgb_u32(0x00FFAA));
-        }
+    if let (Some(focus), false) = (model.keyboard_focus, active_hidden) {
+        draw_keyboard_focus(&draw, app.window_rect(), model, focus);
     }
 
-    if model.show_control_points {
+    if model.show_control_points && !active_hidden {
         for (i, point) in model.control_points.iter().enumerate() {
             let is_selected = model.dragging_point == Some(i);
             let color = if is_selected { rgb_u32(0xFF3366) } else { rgb_u32(0xFFFFFF) };
@@ -285,18 +4105,233 @@ fn view(app: &App, model: &Model, frame: Frame) {
         }
     }
 
-    let mut instructions = vec![
-        "Click - Add Point",
-        "Click+Drag - Move Point",
-        "H - Toggle Control Points",
-        "R - Reset Points",
-        "C - Clear Points",
-        "1 - Linear Spline",
-        "2 - Quadratic Spline",
-        "3 - Cubic Spline (Natural)",
-    ];
-    let current_spline_type_text = format!("Current Type: {:?}", model.current_spline_type);
+    let t = &model.locale;
+    let mut instructions: Vec<&str> = INSTRUCTIONS.iter().map(|key| t.get(key)).collect();
+    let palette_text = t.format("instructions.palette", &[("key", &shortcut_name(model, Action::CommandPalette))]);
+    instructions.push(&palette_text);
+    let keyboard_text = t.format("instructions.keyboard", &[("key", &shortcut_name(model, Action::KeyboardEditing))]);
+    instructions.push(&keyboard_text);
+    let language_text = t.format("instructions.language", &[("language", &t.name())]);
+    instructions.push(&language_text);
+    let spline_type = t.get(spline_type_key(model.current_spline_type));
+    let current_spline_type_text = match &model.custom_method {
+        Some(method) => t.format("hud.custom_method", &[("method", method), ("type", &spline_type)]),
+        None => t.format("hud.spline_type", &[("type", &spline_type)]),
+    };
     instructions.push(&current_spline_type_text);
+    let shape_text;
+    if let Some(constraint) = &model.shape_constraint {
+        let state = match Spline::try_new(&data_points(app.window_rect(), model), model.current_spline_type) {
+            Ok(spline) => match spline.shape_violations(&[*constraint]).len() {
+                0 => t.get("hud.shape_met").to_string(),
+                count => t.format("hud.shape_violations", &[("count", &count)]),
+            },
+            Err(_) => t.get("hud.shape_not_applied").to_string(),
+        };
+        shape_text = t.format(
+            "hud.shape",
+            &[
+                ("shape", &format!("{:?}", constraint.shape)),
+                ("min", &format!("{:.3}", constraint.min_x)),
+                ("max", &format!("{:.3}", constraint.max_x)),
+                ("state", &state),
+            ],
+        );
+        instructions.push(&shape_text);
+    }
+    let group = &model.groups[model.active_group];
+    let group_text = t.format(
+        "hud.group",
+        &[
+            ("name", &group.name),
+            ("number", &(model.active_group + 1)),
+            ("count", &model.groups.len()),
+            ("hidden", &if group.hidden { t.get("hud.group_hidden") } else { "" }),
+            ("locked", &if group.locked { t.get("hud.group_locked") } else { "" }),
+        ],
+    );
+    if model.groups.len() > 1 || group.hidden || group.locked {
+        instructions.push(&group_text);
+    }
+    let ruler_text;
+    match model.ruler.as_deref() {
+        Some([a, b]) => {
+            ruler_text = ruler_readout(app.window_rect(), model, *a, *b);
+            instructions.push(&ruler_text);
+        }
+        Some(_) => instructions.push(t.get("hud.ruler_prompt")),
+        None => {}
+    }
+    let spectrum_text;
+    if let Some(harmonics) = harmonics {
+        spectrum_text = t.format(
+            "hud.spectrum",
+            &[
+                ("dc", &format!("{:.4}", harmonics[0])),
+                ("fundamental", &format!("{:.4}", harmonics[1])),
+                ("thd", &format!("{:.1}", 100.0 * total_harmonic_distortion(harmonics))),
+            ],
+        );
+        instructions.push(&spectrum_text);
+    }
+    let tiling_text;
+    if let Some(tiling) = model.tiling {
+        tiling_text = match seam_mismatch(app.window_rect(), model, tiling) {
+            Some((value, slope)) => t.format(
+                "hud.tiling",
+                &[
+                    ("both", &if tiling.vertical { t.get("hud.tiling_both") } else { "" }),
+                    ("mirrored", &if tiling.mirror { t.get("hud.tiling_mirrored") } else { "" }),
+                    ("value", &format!("{:.4}", value)),
+                    ("slope", &format!("{:.4}", slope)),
+                    (
+                        "seamless",
+                        &if value < 1e-4 && slope < 1e-4 { t.get("hud.tiling_seamless") } else { "" },
+                    ),
+                ],
+            ),
+            None => t.get("hud.tiling_too_few").to_string(),
+        };
+        instructions.push(&tiling_text);
+    }
+    let terrain_text;
+    if let Some(max_grade) = model.max_grade {
+        let spline = terrain_spline(app.window_rect(), model);
+        let grades = spline.as_ref().map_or_else(Vec::new, segment_grades);
+        let over = grades.iter().filter(|g| g.2.abs() > max_grade).count();
+        let steepest = grades.iter().map(|g| g.2.abs()).fold(0.0, f32::max);
+        terrain_text = t.format(
+            "hud.terrain",
+            &[
+                ("max", &max_grade),
+                ("steepest", &format!("{:.1}", steepest)),
+                ("over", &over),
+                ("count", &grades.len()),
+            ],
+        );
+        instructions.push(&terrain_text);
+    }
+    let quantize_text;
+    if let (Some(quantizer), Some(runs)) = (model.quantizer, &runs) {
+        let steps = match quantizer.steps {
+            Some(steps) => t.format("hud.quantize_steps", &[("steps", &steps)]),
+            None => t.get("hud.quantize_free").to_string(),
+        };
+        quantize_text = t.format(
+            "hud.quantize",
+            &[("levels", &quantizer.levels), ("steps", &steps), ("runs", &runs.len())],
+        );
+        instructions.push(&quantize_text);
+    }
+    let noise_text;
+    if let Some(noise) = &model.noise {
+        noise_text = t.format(
+            "hud.noise",
+            &[
+                ("seed", &noise.seed()),
+                ("amplitude", &format!("{:.4}", noise.amplitude)),
+                ("frequency", &format!("{:.2}", noise.frequency)),
+                (
+                    "target",
+                    &t.get(if model.noise_in_outputs { "hud.noise_in_outputs" } else { "hud.noise_preview" }),
+                ),
+            ],
+        );
+        instructions.push(&noise_text);
+    }
+    let detail_text;
+    if !model.detail_layers.is_empty() {
+        let layers: Vec<String> = model
+            .detail_layers
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                let state = t.get(if l.enabled { "hud.layer_on" } else { "hud.layer_off" });
+                t.format("hud.detail_layer", &[("number", &(i + 1)), ("level", &l.level()), ("state", &state)])
+            })
+            .collect();
+        detail_text = t.format("hud.detail_layers", &[("layers", &layers.join(", "))]);
+        instructions.push(&detail_text);
+    }
+    let limits_text;
+    if model.limit_mode != LimitMode::Off {
+        limits_text = t.format(
+            "hud.limits",
+            &[("limits", &describe_limits(&model.limits)), ("mode", &format!("{:?}", model.limit_mode))],
+        );
+        instructions.push(&limits_text);
+    }
+    let cursor_text;
+    if model.runge_point_count.is_none() {
+        let mouse = app.mouse.position();
+        let p = screen_to_data(app.window_rect(), &model.x_axis, &model.y_axis, Point::new(mouse.x, mouse.y));
+        let with_unit = |value: f32, axis: &Axis| match &axis.unit {
+            Some(unit) => format!("{:.4} {}", value, unit),
+            None => format!("{:.4}", value),
+        };
+        cursor_text = t.format(
+            "hud.cursor",
+            &[("x", &with_unit(p.x, &model.x_axis)), ("y", &with_unit(p.y, &model.y_axis))],
+        );
+        instructions.push(&cursor_text);
+    }
+    let seed_text;
+    if let Some(seed) = model.seed {
+        seed_text = t.format("hud.seed", &[("seed", &seed)]);
+        instructions.push(&seed_text);
+    }
+    let preset_text;
+    if let Some(preset) = model.preset {
+        preset_text = t.format("hud.preset", &[("name", &preset.name()), ("count", &model.sample_point_count)]);
+        instructions.push(&preset_text);
+    }
+    let mut runge_text = Vec::new();
+    if let Some(point_count) = model.runge_point_count {
+        runge_text.push(t.format("hud.runge", &[("count", &point_count)]));
+        if model.control_points.len() >= 2 {
+            let rect = app.window_rect();
+            let errors = model
+                .frame_cache
+                .runge_errors
+                .get(frame_key(rect, model), || runge_errors(rect, &model.control_points));
+            for (method, error) in errors.iter() {
+                let error = format!("{:.4}", error);
+                runge_text.push(t.format("hud.runge_error", &[("method", &method), ("error", &error)]));
+            }
+        }
+    }
+    instructions.extend(runge_text.iter().map(|s| s.as_str()));
+    let error_text;
+    if let (Some(ground_truth), Some(spline)) = (&model.ground_truth, &model.spline) {
+        let rect = app.window_rect();
+        let metrics = model
+            .frame_cache
+            .ground_truth_error
+            .get(frame_key(rect, model), || ground_truth_error(rect, model, ground_truth, spline));
+        error_text = t.format(
+            "hud.error",
+            &[
+                ("count", &ground_truth.points.len()),
+                ("rms", &format!("{:.4}", metrics.rms)),
+                ("max", &format!("{:.4}", metrics.max)),
+            ],
+        );
+        instructions.push(&error_text);
+    }
+    if let Some(status) = &model.status {
+        instructions.push(status);
+    }
+    let led_status_text;
+    if let Some(output) = &model.led_output {
+        led_status_text = t.format(
+            "hud.led",
+            &[
+                ("state", &t.get(if model.led_enabled { "hud.led_on" } else { "hud.led_off" })),
+                ("count", &output.led_count()),
+            ],
+        );
+        instructions.push(&led_status_text);
+    }
 
     for (i, text) in instructions.iter().enumerate() {
         draw.text(text)
@@ -305,12 +4340,78 @@ fn view(app: &App, model: &Model, frame: Frame) {
             .font_size(14);
     }
 
+    if let Some(warning) = &model.warning {
+        let rect = app.window_rect();
+        draw.rect()
+            .x_y(0.0, rect.bottom() + 20.0)
+            .w_h(rect.w(), 40.0)
+            .color(rgba(0.8, 0.1, 0.1, 0.85));
+        draw.text(warning)
+            .x_y(0.0, rect.bottom() + 20.0)
+            .w(rect.w() - 40.0)
+            .color(WHITE)
+            .font_size(16);
+    }
+
+    if let Some(palette) = &model.palette {
+        draw_palette(&draw, app.window_rect(), model, palette);
+    }
+
     draw.to_frame(app, &frame).unwrap();
 }
 
 fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    model.revision += 1;
+    if model.gradient.is_some() {
+        if button == MouseButton::Left {
+            pick_gradient_stop(app.window_rect(), model, app.mouse.position());
+        }
+        return;
+    }
+    if model.camera.is_some() {
+        if button == MouseButton::Left {
+            pick_camera_handle(model, app.mouse.position());
+        }
+        return;
+    }
+    if model.glyph.is_some() {
+        if button == MouseButton::Left {
+            pick_glyph_point(model, app.mouse.position());
+        }
+        return;
+    }
+
+    let in_overview = overview_strip(app.window_rect()).contains(app.mouse.position());
+    if model.overview.is_some() && in_overview {
+        if button == MouseButton::Left {
+            grab_overview(app.window_rect(), model, app.mouse.position());
+        }
+        return;
+    }
+
+    if button == MouseButton::Left {
+        if let Some(i) = channel_tab_at(app.window_rect(), model, app.mouse.position()) {
+            switch_group(model, i);
+            return;
+        }
+    }
+
+    let in_ease_panel = ease_panel(app.window_rect()).contains(app.mouse.position());
+    if let (Some(easing), true) = (&mut model.easing, in_ease_panel) {
+        match button {
+            MouseButton::Left => pick_ease_point(app.window_rect(), easing, app.mouse.position()),
+            MouseButton::Right => remove_ease_point(app.window_rect(), easing, app.mouse.position()),
+            _ => {}
+        }
+        return;
+    }
+
     match button {
-        MouseButton::Left => {
+        MouseButton::Left if model.ruler.is_some() => {
+            let mouse_pos = app.mouse.position();
+            pick_ruler_point(model, Point::new(mouse_pos.x, mouse_pos.y));
+        }
+        MouseButton::Left if can_edit_group(model) => {
             let mouse_pos = app.mouse.position();
             let point = Point::new(mouse_pos.x, mouse_pos.y);
 
@@ -326,62 +4427,382 @@ fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
                 }
             }
 
-            if !clicked_on_point {
-                model.control_points.push(point);
+            if !clicked_on_point && model.layered.is_none() {
+                add_point(model, point);
             }
         }
+        MouseButton::Right if !model.detail_layers.is_empty() => {
+            model.editing_detail = true;
+            drag_detail(model, app.mouse.position());
+        }
         _ => {}
     }
 }
 
 fn mouse_released(_app: &App, model: &mut Model, _button: MouseButton) {
+    model.revision += 1;
     model.dragging_point = None;
+    model.editing_detail = false;
+    if let Some(designer) = &mut model.gradient {
+        designer.dragging = false;
+    }
+    if let Some(editor) = &mut model.camera {
+        editor.dragging = false;
+    }
+    if let Some(sketch) = &mut model.glyph {
+        sketch.dragging = None;
+    }
+    if let Some(easing) = &mut model.easing {
+        easing.dragging = None;
+    }
+    if let Some(overview) = &mut model.overview {
+        overview.grab = None;
+    }
 }
 
+// Only drags change anything, so only they bump the revision.
 fn mouse_moved(app: &App, model: &mut Model, pos: Vec2) {
+    if let Some(designer) = &mut model.gradient {
+        drag_gradient_stop(app.window_rect(), designer, pos.x);
+        return;
+    }
+    if let Some(editor) = model.camera.as_mut().filter(|e| e.dragging) {
+        if let Some(handle) = selected_handle(editor) {
+            handle[0] = pos.x / PLAN_SCALE;
+            handle[2] = -pos.y / PLAN_SCALE;
+        }
+        model.revision += 1;
+        return;
+    }
+    if let Some(sketch) = &mut model.glyph {
+        drag_glyph_point(sketch, pos);
+        return;
+    }
+    if let Some(easing) = model.easing.as_mut().filter(|e| e.dragging.is_some()) {
+        drag_ease_point(app.window_rect(), easing, pos);
+        model.revision += 1;
+        return;
+    }
+    if model.overview.as_ref().is_some_and(|o| o.grab.is_some()) {
+        pan_overview(app.window_rect(), model, pos);
+        model.revision += 1;
+        return;
+    }
+    if model.editing_detail {
+        drag_detail(model, pos);
+    }
     if let Some(idx) = model.dragging_point {
-        model.control_points[idx].x = pos.x;
-        model.control_points[idx].y = pos.y;
+        if let Some(layered) = &mut model.layered {
+            layered.set_master(idx, pos.y);
+            model.control_points = layered.masters();
+            model.revision += 1;
+        } else if app.keys.mods.alt() {
+            move_group(model, idx, Point::new(pos.x, pos.y));
+        } else {
+            model.dragging_point = Some(move_point(model, idx, Point::new(pos.x, pos.y)));
+        }
     }
 }
 
 fn key_pressed(app: &App, model: &mut Model, key: Key) {
-    match key {
-        Key::H => {
+    model.revision += 1;
+    if model.annotation_draft.is_some() {
+        edit_annotation_draft(model, key);
+        return;
+    }
+    if model.tag_draft.is_some() {
+        edit_tag_draft(model, key);
+        return;
+    }
+    if model.palette.is_some() {
+        edit_palette(app, model, key);
+        return;
+    }
+    // Backspace edits the hex colour being typed, like the palette's query.
+    if let Some(designer) = model.gradient.as_mut().filter(|_| key == Key::Back) {
+        designer.hex.pop();
+        return;
+    }
+    // Ctrl and Cmd shortcuts, and keys keyboard editing mode doesn't use,
+    // keep their usual actions.
+    let shift = app.keys.mods.shift();
+    let ctrl = app.keys.mods.ctrl() || app.keys.mods.logo();
+    let scope = current_scope(model);
+    if scope == Scope::Canvas && !ctrl && model.keyboard_focus.is_some() && edit_with_keyboard(model, key, shift) {
+        return;
+    }
+    if let Some(action) = model.config.action_for(scope, key, shift, ctrl) {
+        perform(app, model, action);
+    }
+}
+
+// The modes that take the keyboard over, in the order they're checked.
+fn current_scope(model: &Model) -> Scope {
+    if model.gradient.is_some() {
+        Scope::Gradient
+    } else if model.camera.is_some() {
+        Scope::Camera
+    } else if model.glyph.is_some() {
+        Scope::Glyph
+    } else {
+        Scope::Canvas
+    }
+}
+
+fn perform(app: &App, model: &mut Model, action: Action) {
+    match current_scope(model) {
+        Scope::Gradient => edit_gradient(model, action),
+        Scope::Camera => edit_camera(app, model, action),
+        Scope::Glyph => edit_glyph(model, action),
+        Scope::Canvas => perform_on_canvas(app, model, action),
+    }
+}
+
+fn perform_on_canvas(app: &App, model: &mut Model, action: Action) {
+    match action {
+        Action::CommandPalette => model.palette = Some(Palette::new()),
+        Action::ToggleControlPoints => {
             model.show_control_points = !model.show_control_points;
         }
-        Key::R => {
-            model.control_points = vec![
-                Point::new(-300.0, 0.0),
-                Point::new(-150.0, 100.0),
-                Point::new(0.0, -100.0),
-                Point::new(150.0, 100.0),
-                Point::new(300.0, 0.0),
-            ];
+        Action::RandomPoints => {
+            load_random_points(app.window_rect(), model, fresh_seed());
+        }
+        Action::ResetPoints => {
+            set_control_points(
+                model,
+                vec![
+                    Point::new(-300.0, 0.0),
+                    Point::new(-150.0, 100.0),
+                    Point::new(0.0, -100.0),
+                    Point::new(150.0, 100.0),
+                    Point::new(300.0, 0.0),
+                ],
+            );
+        }
+        Action::CopyShareString => {
+            copy_share_string(model);
+        }
+        Action::NextLanguage => model.locale.next(),
+        Action::PasteShareString => {
+            paste_share_string(model);
+        }
+        Action::Export if is_channel_mode(model) => export_channels(app.window_rect(), model),
+        Action::Export => export_group(model),
+        Action::RemoveAnnotation => remove_nearest_annotation(model, app.mouse.position()),
+        Action::Annotate => start_annotation(model, app.mouse.position()),
+        Action::ClearTags => clear_tags(model, app.mouse.position()),
+        Action::TagPoint => start_tag(model, app.mouse.position()),
+        Action::NewGroup => new_group(model),
+        Action::RgbChannels => toggle_channels(app.window_rect(), model),
+        Action::Ruler => model.ruler = if model.ruler.is_some() { None } else { Some(Vec::new()) },
+        Action::NextGroup => switch_group(model, (model.active_group + 1) % model.groups.len()),
+        Action::HideGroup => {
+            let group = &mut model.groups[model.active_group];
+            group.hidden = !group.hidden;
             model.dragging_point = None;
         }
-        Key::C => {
-            model.control_points.clear();
+        Action::LockGroup => {
+            let group = &mut model.groups[model.active_group];
+            group.locked = !group.locked;
             model.dragging_point = None;
         }
-        Key::Key1 => {
-            model.current_spline_type = SplineType::Linear;
+        Action::SaveSession => {
+            save_session(model);
         }
-        Key::Key2 => {
-            model.current_spline_type = SplineType::Quadratic;
+        Action::Bake => export_baked(app.window_rect(), model),
+        Action::ClearPoints => {
+            set_control_points(model, Vec::new());
         }
-        Key::Key3 => {
-            model.current_spline_type = SplineType::Cubic;
+        Action::LoadPreset(preset) => load_preset(app.window_rect(), model, preset),
+        Action::GradientDesigner => {
+            model.gradient = Some(GradientDesigner::new());
+            model.status = None;
         }
-        Key::Escape => {
+        Action::CameraEditor => {
+            model.camera = Some(CameraEditor::new());
+            model.status = None;
+        }
+        Action::GlyphSketcher => {
+            model.glyph = Some(GlyphSketch::new());
+            model.status = None;
+        }
+        Action::MirrorTiles => {
+            if let Some(tiling) = &mut model.tiling {
+                tiling.mirror = !tiling.mirror;
+            }
+        }
+        Action::Tiling => cycle_tiling(model),
+        Action::Spectrum => model.show_spectrum = !model.show_spectrum,
+        Action::Easing => model.easing = if model.easing.is_some() { None } else { Some(Easing::new(app.time)) },
+        // Both would replace the masters or move the data under them.
+        Action::FewerSamplePoints | Action::MoreSamplePoints | Action::LogScaleX | Action::LogScaleY
+            if model.layered.is_some() =>
+        {
+            model.status = Some(model.locale.get("status.master_first").to_string());
+        }
+        Action::FewerSamplePoints | Action::MoreSamplePoints => {
+            if action == Action::FewerSamplePoints {
+                model.sample_point_count = model.sample_point_count.saturating_sub(1).max(2);
+            } else {
+                model.sample_point_count += 1;
+            }
+            if let Some(preset) = model.preset {
+                load_preset(app.window_rect(), model, preset);
+            } else if model.runge_point_count.is_none() {
+                if let Some(ground_truth) = &model.ground_truth {
+                    model.control_points = subsample(&ground_truth.points, model.sample_point_count);
+                    model.dragging_point = None;
+                    rebuild_spline(model);
+                }
+            }
+        }
+        Action::AdaptiveFit => {
+            fit_adaptive_to_ground_truth(app.window_rect(), model);
+        }
+        Action::Smooth | Action::RobustSmooth => {
+            smooth_ground_truth(model, action == Action::RobustSmooth);
+        }
+        Action::LiveSignal => {
+            model.live = match model.live {
+                Some(_) => None,
+                None => Some(LiveSignal::new(app.time)),
+            };
+        }
+        Action::ConfidenceBand => {
+            model.show_confidence_band = !model.show_confidence_band;
+        }
+        Action::AddDataNoise => {
+            add_noise_to_ground_truth(model, fresh_seed());
+        }
+        Action::RungeDemo => {
+            if model.runge_point_count.is_some() {
+                model.runge_point_count = None;
+            } else {
+                load_runge_demo(app.window_rect(), model, 11);
+            }
+        }
+        Action::Increase | Action::Decrease | Action::IncreaseSteps | Action::DecreaseSteps => {
+            let up = matches!(action, Action::Increase | Action::IncreaseSteps);
+            let steps = matches!(action, Action::IncreaseSteps | Action::DecreaseSteps);
+            adjust_mode(app, model, up, steps);
+        }
+        Action::ToggleDetailLayer(number) => toggle_detail_layer(model, number - 1),
+        Action::Linear => select_interpolator(model, "linear"),
+        Action::Quadratic => select_interpolator(model, "quadratic"),
+        Action::Cubic => select_interpolator(model, "cubic"),
+        Action::NextInterpolator => next_interpolator(model),
+        Action::LogScaleX if model.runge_point_count.is_none() => toggle_log_scale(app.window_rect(), model, true),
+        Action::LogScaleY if model.runge_point_count.is_none() => toggle_log_scale(app.window_rect(), model, false),
+        Action::ZoomIn if model.runge_point_count.is_none() => {
+            zoom(app.window_rect(), model, ZOOM_STEP, app.mouse.position())
+        }
+        Action::ZoomOut if model.runge_point_count.is_none() => {
+            zoom(app.window_rect(), model, 1.0 / ZOOM_STEP, app.mouse.position())
+        }
+        Action::AngleMode => toggle_angle_mode(model),
+        Action::Terrain => toggle_terrain_mode(model),
+        Action::ExportSteps => export_staircase(app.window_rect(), model),
+        Action::Quantize => toggle_quantize(model),
+        Action::NoiseToOutputs => {
+            model.noise_in_outputs = !model.noise_in_outputs;
+        }
+        Action::ReseedNoise => {
+            if let Some(noise) = &mut model.noise {
+                reseed_noise(noise);
+            }
+        }
+        Action::Noise => toggle_noise(model),
+        Action::NoiseAmplitudeUp | Action::NoiseAmplitudeDown => {
+            if let Some(noise) = &mut model.noise {
+                noise.amplitude *= if action == Action::NoiseAmplitudeUp { 1.25 } else { 0.8 };
+            }
+        }
+        Action::NoiseFrequencyUp | Action::NoiseFrequencyDown => {
+            if let Some(noise) = &mut model.noise {
+                let range = (model.x_axis.max - model.x_axis.min).abs();
+                let frequency = noise.frequency * if action == Action::NoiseFrequencyUp { 2.0 } else { 0.5 };
+                noise.frequency = frequency.clamp(0.5 / range, 64.0 / range);
+            }
+        }
+        Action::ClearDetailLayers => model.detail_layers.clear(),
+        Action::AddDetailLayer => add_detail_layer(model),
+        Action::MasterEditing => toggle_master_editing(model),
+        Action::ExportPoints => export_points(app.window_rect(), model),
+        Action::ShapeConstraint => cycle_shape_constraint(model),
+        Action::MarkConstraintEdge => mark_constraint_edge(app.window_rect(), model, app.mouse.position()),
+        Action::ApplyShapeConstraint => apply_shape_constraint(app.window_rect(), model),
+        Action::OutputLimits => model.limit_mode = model.limit_mode.next(),
+        Action::LedOutput => {
+            if model.led_output.is_some() {
+                model.led_enabled = !model.led_enabled;
+            }
+        }
+        Action::KeyboardEditing => toggle_keyboard_editing(model),
+        Action::Quit => {
             app.quit();
         }
-        _ => {}
+        Action::LogScaleX
+        | Action::LogScaleY
+        | Action::ZoomIn
+        | Action::ZoomOut => {}
+        // Only the designer, editor and sketcher have these.
+        Action::LeaveMode
+        | Action::NextColorSpace
+        | Action::SetStopColor
+        | Action::DeleteSelected
+        | Action::PreviewCamera
+        | Action::CloseContour
+        | Action::UndoGlyphPoint
+        | Action::RemoveContour
+        | Action::UnionContours
+        | Action::IntersectContours
+        | Action::SubtractContours
+        | Action::ApplyBoolean
+        | Action::NarrowerGlyph
+        | Action::WiderGlyph => {}
     }
 }
 
-fn main() {
+// Up and Down adjust the quantizer, the terrain's max grade, the live
+// signal's smoothing or the Runge demo's point count, whichever is on.
+// `steps` (Shift) moves the quantizer's steps instead of its levels.
+fn adjust_mode(app: &App, model: &mut Model, up: bool, steps: bool) {
+    if model.live.is_none() && model.runge_point_count.is_none() {
+        if let Some(quantizer) = &mut model.quantizer {
+            adjust_quantizer(quantizer, up, steps);
+            return;
+        }
+        if let Some(max_grade) = &mut model.max_grade {
+            let step = if up { 0.5 } else { -0.5 };
+            *max_grade = (*max_grade + step).max(0.5);
+            return;
+        }
+    }
+    if let Some(live) = &mut model.live {
+        let factor = if up { 10f32.sqrt() } else { 1.0 / 10f32.sqrt() };
+        live.lambda = (live.lambda * factor).clamp(1e-6, 1e2);
+        live.refit();
+    } else if let Some(point_count) = model.runge_point_count {
+        let point_count = if up { (point_count + 1).min(41) } else { (point_count - 1).max(3) };
+        load_runge_demo(app.window_rect(), model, point_count);
+    }
+}
+
+thread_local! {
+    // The methods given to `run`, until `model` takes them.
+    static INTERPOLATORS: RefCell<Option<InterpolatorRegistry>> = const { RefCell::new(None) };
+}
+
+/// Opens the editor with `interpolators` offered by 4, `--method` and the
+/// remote API. A build with its own methods registers them on
+/// `InterpolatorRegistry::default()` and calls this from its `main`.
+pub fn run(interpolators: InterpolatorRegistry) {
+    INTERPOLATORS.with(|cell| *cell.borrow_mut() = Some(interpolators));
     nannou::app(model)
         .update(update)
         .run();
 }
+
+fn main() {
+    run(InterpolatorRegistry::default());
+}
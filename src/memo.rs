@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A value derived from inputs that change far less often than it is read,
+/// such as a polyline redrawn every frame from points that only move on
+/// edits. Reading with the key it was computed for returns it as is; any
+/// other key recomputes it. The value sits in a `RefCell`, so a `Memo` can
+/// be filled from `&self` but is not `Sync`.
+pub struct Memo<K, V> {
+    entry: RefCell<Option<(K, Rc<V>)>>,
+}
+
+impl<K, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            entry: RefCell::new(None),
+        }
+    }
+
+    /// Drops the kept value, so the next read recomputes it whatever its key.
+    pub fn clear(&self) {
+        self.entry.borrow_mut().take();
+    }
+}
+
+impl<K: PartialEq, V> Memo<K, V> {
+    /// The value for `key`, from `compute` unless it was the last key read.
+    pub fn get(&self, key: K, compute: impl FnOnce() -> V) -> Rc<V> {
+        if let Some((kept, value)) = &*self.entry.borrow() {
+            if *kept == key {
+                return Rc::clone(value);
+            }
+        }
+        let value = Rc::new(compute());
+        *self.entry.borrow_mut() = Some((key, Rc::clone(&value)));
+        value
+    }
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
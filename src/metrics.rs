@@ -0,0 +1,47 @@
+use crate::Point;
+
+/// The Runge function `1 / (1 + 25x²)`, the textbook case where equispaced
+/// polynomial interpolation diverges on `[-1, 1]`.
+pub fn runge(x: f32) -> f32 {
+    1.0 / (1.0 + 25.0 * x * x)
+}
+
+/// Largest absolute difference between `f` and `g` over `samples` evenly
+/// spaced x values across `domain`.
+pub fn max_error(f: impl Fn(f32) -> f32, g: impl Fn(f32) -> f32, domain: (f32, f32), samples: usize) -> f32 {
+    let (min_x, max_x) = domain;
+    let last = samples.saturating_sub(1).max(1) as f32;
+
+    (0..samples)
+        .map(|i| {
+            let x = min_x + (max_x - min_x) * (i as f32 / last);
+            (f(x) - g(x)).abs()
+        })
+        .fold(0.0, f32::max)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ErrorMetrics {
+    pub rms: f32,
+    pub max: f32,
+}
+
+/// RMS and maximum of `|f(x) - y|` over the data points.
+pub fn error_metrics(data: &[Point], f: impl Fn(f32) -> f32) -> ErrorMetrics {
+    if data.is_empty() {
+        return ErrorMetrics { rms: 0.0, max: 0.0 };
+    }
+
+    let mut sum_squares = 0.0f64;
+    let mut max = 0.0f32;
+    for p in data {
+        let error = (f(p.x) - p.y).abs();
+        sum_squares += (error as f64) * (error as f64);
+        max = max.max(error);
+    }
+
+    ErrorMetrics {
+        rms: (sum_squares / data.len() as f64).sqrt() as f32,
+        max,
+    }
+}
@@ -0,0 +1,45 @@
+use crate::Rng;
+
+// Lattice gradients before the pattern repeats.
+const PERIOD: usize = 256;
+
+/// Seeded one-dimensional Perlin noise, for layering over a curve's output
+/// to make it a little organic. It is smooth, zero at every lattice point,
+/// and the same seed always gives the same noise.
+#[derive(Clone, Debug)]
+pub struct Noise {
+    /// The largest offset the noise adds or takes away.
+    pub amplitude: f32,
+    /// Lattice points per unit of x, so about how many bumps a unit holds.
+    pub frequency: f32,
+    seed: u64,
+    gradients: Vec<f32>,
+}
+
+impl Noise {
+    pub fn new(seed: u64, amplitude: f32, frequency: f32) -> Self {
+        let mut rng = Rng::new(seed);
+        Noise {
+            amplitude,
+            frequency,
+            seed,
+            gradients: (0..PERIOD).map(|_| rng.range(-1.0, 1.0)).collect(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The offset at `x`, within `[-amplitude, amplitude]`.
+    pub fn at(&self, x: f32) -> f32 {
+        let t = x * self.frequency;
+        let cell = t.floor();
+        let f = t - cell;
+        let gradient = |i: f32| self.gradients[(i as i64).rem_euclid(PERIOD as i64) as usize];
+        let (from_left, from_right) = (gradient(cell) * f, gradient(cell + 1.0) * (f - 1.0));
+        let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+        // Unit gradients reach at most half a unit between lattice points.
+        2.0 * self.amplitude * (from_left + fade * (from_right - from_left))
+    }
+}
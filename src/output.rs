@@ -0,0 +1,135 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+const SACN_PORT: u16 = 5568;
+const SACN_HEADER_LEN: usize = 126;
+const DMX_SLOTS: usize = 512;
+
+pub enum LedTarget {
+    Serial(File),
+    Sacn {
+        socket: UdpSocket,
+        dest: SocketAddr,
+        universe: u16,
+        sequence: u8,
+    },
+}
+
+pub struct LedOutput {
+    target: LedTarget,
+    led_count: usize,
+    frame: Vec<u8>,
+}
+
+impl LedOutput {
+    /// Adalight framing over a serial device, as understood by WLED and the
+    /// Adafruit sketches. The port is expected to be configured already
+    /// (e.g. `stty -F /dev/ttyUSB0 115200 raw`).
+    pub fn serial(path: &str, led_count: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().write(true).open(path)?;
+        Ok(LedOutput {
+            target: LedTarget::Serial(file),
+            led_count,
+            frame: Vec::new(),
+        })
+    }
+
+    /// E1.31 (sACN) to `host`, or to the universe's multicast group when no
+    /// host is given. Each LED takes three DMX slots, so a universe holds 170.
+    pub fn sacn(host: Option<&str>, universe: u16, led_count: usize) -> io::Result<Self> {
+        let dest = match host {
+            Some(host) => (host, SACN_PORT)
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "could not resolve sACN host"))?,
+            None => {
+                let [hi, lo] = universe.to_be_bytes();
+                SocketAddr::from((Ipv4Addr::new(239, 255, hi, lo), SACN_PORT))
+            }
+        };
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        Ok(LedOutput {
+            target: LedTarget::Sacn {
+                socket,
+                dest,
+                universe,
+                sequence: 0,
+            },
+            led_count: led_count.min(DMX_SLOTS / 3),
+            frame: Vec::new(),
+        })
+    }
+
+    pub fn led_count(&self) -> usize {
+        self.led_count
+    }
+
+    /// Sends one frame of per-LED intensities; each level is written as a
+    /// white (r = g = b) pixel.
+    pub fn write_levels(&mut self, levels: &[u8]) -> io::Result<()> {
+        let levels = &levels[..levels.len().min(self.led_count)];
+        self.frame.clear();
+
+        match &mut self.target {
+            LedTarget::Serial(file) => {
+                let count = levels.len().saturating_sub(1) as u16;
+                let [hi, lo] = count.to_be_bytes();
+                self.frame.extend_from_slice(&[b'A', b'd', b'a', hi, lo, hi ^ lo ^ 0x55]);
+                for &level in levels {
+                    self.frame.extend_from_slice(&[level, level, level]);
+                }
+                file.write_all(&self.frame)?;
+                file.flush()
+            }
+            LedTarget::Sacn {
+                socket,
+                dest,
+                universe,
+                sequence,
+            } => {
+                let slots = levels.len() * 3;
+                write_sacn_header(&mut self.frame, *universe, *sequence, slots);
+                for &level in levels {
+                    self.frame.extend_from_slice(&[level, level, level]);
+                }
+                *sequence = sequence.wrapping_add(1);
+                socket.send_to(&self.frame, *dest).map(|_| ())
+            }
+        }
+    }
+}
+
+fn write_sacn_header(buf: &mut Vec<u8>, universe: u16, sequence: u8, slots: usize) {
+    let total = SACN_HEADER_LEN + slots;
+    let pdu_len = |offset: usize| (0x7000 | (total - offset) as u16).to_be_bytes();
+
+    // Root layer
+    buf.extend_from_slice(&0x0010u16.to_be_bytes());
+    buf.extend_from_slice(&0x0000u16.to_be_bytes());
+    buf.extend_from_slice(b"ASC-E1.17\0\0\0");
+    buf.extend_from_slice(&pdu_len(16));
+    buf.extend_from_slice(&0x0000_0004u32.to_be_bytes());
+    buf.extend_from_slice(b"splines-led-out\0");
+
+    // Framing layer
+    buf.extend_from_slice(&pdu_len(38));
+    buf.extend_from_slice(&0x0000_0002u32.to_be_bytes());
+    let mut source_name = [0u8; 64];
+    source_name[..7].copy_from_slice(b"splines");
+    buf.extend_from_slice(&source_name);
+    buf.push(100);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf.push(sequence);
+    buf.push(0);
+    buf.extend_from_slice(&universe.to_be_bytes());
+
+    // DMP layer
+    buf.extend_from_slice(&pdu_len(115));
+    buf.push(0x02);
+    buf.push(0xa1);
+    buf.extend_from_slice(&0x0000u16.to_be_bytes());
+    buf.extend_from_slice(&0x0001u16.to_be_bytes());
+    buf.extend_from_slice(&(slots as u16 + 1).to_be_bytes());
+    buf.push(0x00);
+}
@@ -0,0 +1,49 @@
+/// The command palette's state: what has been typed to search by, and
+/// which of the matches is picked.
+pub struct Palette {
+    pub query: String,
+    pub selected: usize,
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Palette {
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// The indices of the `labels` the query matches, best first, with
+    /// equally good matches kept in order.
+    pub fn ranked(&self, labels: &[String]) -> Vec<usize> {
+        let mut scored: Vec<(usize, i32)> = labels
+            .iter()
+            .enumerate()
+            .filter_map(|(i, label)| fuzzy_score(&self.query, label).map(|score| (i, score)))
+            .collect();
+        scored.sort_by_key(|&(_, score)| -score);
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+}
+
+/// How well `query` matches `text` with its letters in order but not
+/// necessarily together, ignoring case and spaces in the query, or `None`
+/// if it doesn't. Runs of consecutive letters and letters that start a
+/// word count for more.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let mut score = 0;
+    let mut from = 0;
+    for q in query.chars().filter(|c| !c.is_whitespace()).map(|c| c.to_ascii_lowercase()) {
+        let i = (from..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if i > 0 && i == from {
+            score += 4;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        from = i + 1;
+    }
+    Some(score)
+}
@@ -0,0 +1,143 @@
+use crate::Spline;
+
+/// Evaluates `coeffs[0] + coeffs[1]·dx + coeffs[2]·dx² + ...` by Horner's rule.
+pub(crate) fn horner(coeffs: &[f32], dx: f32) -> f32 {
+    coeffs.iter().rev().fold(0.0, |acc, &c| acc * dx + c)
+}
+
+/// A polynomial of any degree on each interval between consecutive breaks,
+/// each by ascending powers of `x - breaks[i]`. Pieces may differ in length;
+/// missing higher coefficients count as zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PiecewisePolynomial {
+    breaks: Vec<f32>,
+    pieces: Vec<Vec<f32>>,
+}
+
+impl PiecewisePolynomial {
+    /// Needs at least two ascending `breaks` and one piece between each pair.
+    pub fn new(breaks: Vec<f32>, pieces: Vec<Vec<f32>>) -> Self {
+        assert!(breaks.len() >= 2, "a piecewise polynomial needs at least two breaks");
+        assert_eq!(pieces.len(), breaks.len() - 1, "a piecewise polynomial needs one piece per interval");
+        assert!(
+            breaks.windows(2).all(|w| w[0] < w[1]),
+            "a piecewise polynomial's breaks must ascend"
+        );
+        PiecewisePolynomial { breaks, pieces }
+    }
+
+    pub fn breaks(&self) -> &[f32] {
+        &self.breaks
+    }
+
+    pub fn pieces(&self) -> &[Vec<f32>] {
+        &self.pieces
+    }
+
+    /// The highest degree of any piece.
+    pub fn degree(&self) -> usize {
+        self.pieces.iter().map(|p| p.len().saturating_sub(1)).max().unwrap_or(0)
+    }
+
+    /// Outside the breaks the first and last pieces carry on as they are.
+    /// A query on an interior break belongs to the piece on its left.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let i = self.breaks[1..self.breaks.len() - 1].partition_point(|&b| b < x);
+        horner(&self.pieces[i], x - self.breaks[i])
+    }
+
+    /// The derivative, one degree lower.
+    pub fn derivative(&self) -> PiecewisePolynomial {
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|piece| piece.iter().enumerate().skip(1).map(|(k, &c)| k as f32 * c).collect())
+            .collect();
+        PiecewisePolynomial::new(self.breaks.clone(), pieces)
+    }
+
+    /// The integral from the first break, one degree higher and continuous
+    /// across the breaks.
+    pub fn antiderivative(&self) -> PiecewisePolynomial {
+        let mut constant = 0.0;
+        let mut pieces = Vec::with_capacity(self.pieces.len());
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let mut integral = vec![constant];
+            integral.extend(piece.iter().enumerate().map(|(k, &c)| c / (k + 1) as f32));
+            constant = horner(&integral, self.breaks[i + 1] - self.breaks[i]);
+            pieces.push(integral);
+        }
+        PiecewisePolynomial::new(self.breaks.clone(), pieces)
+    }
+
+    /// The same curve with every piece written to `degree`, padding with
+    /// zero coefficients. Pieces already of a higher degree are kept whole.
+    pub fn elevate(&self, degree: usize) -> PiecewisePolynomial {
+        let pieces = self
+            .pieces
+            .iter()
+            .map(|piece| {
+                let mut piece = piece.clone();
+                if piece.len() < degree + 1 {
+                    piece.resize(degree + 1, 0.0);
+                }
+                piece
+            })
+            .collect();
+        PiecewisePolynomial::new(self.breaks.clone(), pieces)
+    }
+}
+
+impl Spline {
+    /// The spline's pieces between its knots. Unlike [`Spline::evaluate`],
+    /// the result extends its end pieces past the knots for every type.
+    pub fn to_piecewise(&self) -> PiecewisePolynomial {
+        PiecewisePolynomial::new(self.points().iter().map(|p| p.x).collect(), self.pieces().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, SplineType};
+
+    #[test]
+    fn antiderivative_is_continuous_and_differentiates_back() {
+        let points = [
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 3.0),
+            Point::new(2.5, 2.0),
+            Point::new(4.0, 0.0),
+        ];
+        let curve = Spline::new(&points, SplineType::Cubic).to_piecewise();
+        let integral = curve.antiderivative();
+        assert_eq!(integral.degree(), 4);
+        assert_eq!(integral.evaluate(0.0), 0.0);
+        for &b in &curve.breaks()[1..3] {
+            let (left, right) = (integral.evaluate(b), integral.evaluate(b + 1e-4));
+            assert!((right - left - curve.evaluate(b) * 1e-4).abs() < 1e-4, "jump at {}", b);
+        }
+        assert_eq!(integral.derivative(), curve);
+    }
+
+    #[test]
+    fn antiderivative_of_a_constant_is_a_line() {
+        let constant = PiecewisePolynomial::new(vec![0.0, 1.0, 3.0], vec![vec![2.0], vec![2.0]]);
+        let integral = constant.antiderivative();
+        for x in [0.0, 0.5, 1.0, 2.0, 3.0] {
+            assert_eq!(integral.evaluate(x), 2.0 * x);
+        }
+    }
+
+    #[test]
+    fn elevate_keeps_the_curve() {
+        let line = PiecewisePolynomial::new(vec![0.0, 1.0, 2.0], vec![vec![1.0, 2.0], vec![3.0, -1.0, 0.5]]);
+        let elevated = line.elevate(5);
+        assert_eq!(elevated.degree(), 5);
+        assert!(elevated.pieces().iter().all(|piece| piece.len() == 6));
+        for x in [-1.0, 0.0, 0.3, 1.0, 1.7, 2.0, 3.0] {
+            assert_eq!(elevated.evaluate(x), line.evaluate(x));
+        }
+        assert_eq!(line.elevate(1), line);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::Point;
+
+/// The single polynomial through every point, evaluated in barycentric form.
+/// Included mainly as the classic contrast to piecewise interpolation.
+pub struct PolynomialInterpolant {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl PolynomialInterpolant {
+    pub fn new(points: &[Point]) -> Self {
+        let xs: Vec<f64> = points.iter().map(|p| p.x as f64).collect();
+        let ys: Vec<f64> = points.iter().map(|p| p.y as f64).collect();
+        let weights = (0..xs.len())
+            .map(|j| {
+                let product: f64 = (0..xs.len()).filter(|&k| k != j).map(|k| xs[j] - xs[k]).product();
+                1.0 / product
+            })
+            .collect();
+
+        PolynomialInterpolant { xs, ys, weights }
+    }
+
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let x = x as f64;
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+
+        for j in 0..self.xs.len() {
+            if x == self.xs[j] {
+                return self.ys[j] as f32;
+            }
+            let term = self.weights[j] / (x - self.xs[j]);
+            numerator += term * self.ys[j];
+            denominator += term;
+        }
+        (numerator / denominator) as f32
+    }
+
+    /// The slope at `x`, from the barycentric form's derivative (Berrut and
+    /// Trefethen, 2004), which has its own expression at the nodes.
+    pub fn derivative(&self, x: f32) -> f32 {
+        let x = x as f64;
+        if let Some(i) = self.xs.iter().position(|&xi| xi == x) {
+            let slope: f64 = (0..self.xs.len())
+                .filter(|&j| j != i)
+                .map(|j| self.weights[j] / self.weights[i] * (self.ys[j] - self.ys[i]) / (self.xs[i] - self.xs[j]))
+                .sum();
+            return slope as f32;
+        }
+        let value = self.evaluate(x as f32) as f64;
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+        for j in 0..self.xs.len() {
+            let term = self.weights[j] / (x - self.xs[j]);
+            numerator += term * (value - self.ys[j]) / (x - self.xs[j]);
+            denominator += term;
+        }
+        (numerator / denominator) as f32
+    }
+
+    /// The x-range spanned by the points, as `(min_x, max_x)`.
+    pub fn domain(&self) -> (f32, f32) {
+        let min = self.xs.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = self.xs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        (min as f32, max as f32)
+    }
+}
@@ -0,0 +1,75 @@
+use std::f32::consts::TAU;
+
+use crate::Point;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Preset {
+    Sine,
+    DampedOscillation,
+    StepResponse,
+    Sigmoid,
+    HeartRate,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::Sine,
+        Preset::DampedOscillation,
+        Preset::StepResponse,
+        Preset::Sigmoid,
+        Preset::HeartRate,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Sine => "Sine Wave",
+            Preset::DampedOscillation => "Damped Oscillation",
+            Preset::StepResponse => "Step Response",
+            Preset::Sigmoid => "Sigmoid",
+            Preset::HeartRate => "Heart Rate Trace",
+        }
+    }
+
+    /// The shape over `t` in `[0, 1]`, with values roughly within `[-1, 1]`.
+    pub fn value(&self, t: f32) -> f32 {
+        match self {
+            Preset::Sine => (2.0 * TAU * t).sin(),
+            Preset::DampedOscillation => (-4.0 * t).exp() * (4.0 * TAU * t).cos(),
+            Preset::StepResponse => {
+                // Underdamped second-order system, zeta = 0.3, over ten time constants.
+                let zeta: f32 = 0.3;
+                let time = 10.0 * t;
+                let damped = (1.0 - zeta * zeta).sqrt();
+                let envelope = (-zeta * time).exp();
+                let response = 1.0 - envelope * ((damped * time).cos() + zeta / damped * (damped * time).sin());
+                1.2 * response - 0.8
+            }
+            Preset::Sigmoid => (10.0 * (t - 0.5)).tanh(),
+            Preset::HeartRate => {
+                // Three beats, each a sum of Gaussian P, Q, R, S and T waves.
+                let phase = (3.0 * t).fract();
+                let wave = |center: f32, width: f32, height: f32| {
+                    height * (-((phase - center) / width).powi(2) / 2.0).exp()
+                };
+                let p = wave(0.2, 0.025, 0.15);
+                let qrs = wave(0.37, 0.01, -0.15) + wave(0.4, 0.012, 1.0) + wave(0.43, 0.012, -0.25);
+                let t_wave = wave(0.65, 0.04, 0.3);
+                p + qrs + t_wave - 0.2
+            }
+        }
+    }
+
+    /// Samples the shape at `n` evenly spaced x values across `domain`,
+    /// scaled vertically by `amplitude`.
+    pub fn points(&self, n: usize, domain: (f32, f32), amplitude: f32) -> Vec<Point> {
+        let (min_x, max_x) = domain;
+        let last = n.saturating_sub(1).max(1) as f32;
+
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / last;
+                Point::new(min_x + (max_x - min_x) * t, amplitude * self.value(t))
+            })
+            .collect()
+    }
+}
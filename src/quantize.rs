@@ -0,0 +1,113 @@
+/// Snaps a curve's output to evenly spaced levels and, optionally, holds it
+/// over evenly spaced steps in x, for stepped hardware parameters and step
+/// sequencers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantizer {
+    /// Output values, spread evenly over a range including both ends. At
+    /// least 2 are used.
+    pub levels: usize,
+    /// When set, the domain is cut into this many equal steps and each one
+    /// holds the value at its start.
+    pub steps: Option<usize>,
+}
+
+impl Quantizer {
+    pub fn new(levels: usize) -> Self {
+        Quantizer { levels, steps: None }
+    }
+
+    /// The level over `range` nearest to `y`, which is clamped into it. An
+    /// empty range has just the one level.
+    pub fn level(&self, y: f32, range: (f32, f32)) -> f32 {
+        let (min, max) = range;
+        if min == max {
+            return min;
+        }
+        let last = (self.levels.max(2) - 1) as f32;
+        let t = ((y - min) / (max - min)).clamp(0.0, 1.0);
+        min + (max - min) * (t * last).round() / last
+    }
+
+    /// Where the value at `x` is read from: the start of its step across
+    /// `domain`, or `x` itself without steps or with an empty domain.
+    pub fn hold_x(&self, x: f32, domain: (f32, f32)) -> f32 {
+        let (lo, hi) = domain;
+        match self.steps {
+            Some(steps) if steps > 0 && lo != hi => {
+                let width = (hi - lo) / steps as f32;
+                let k = ((x - lo) / width).floor().clamp(0.0, (steps - 1) as f32);
+                lo + width * k
+            }
+            _ => x,
+        }
+    }
+
+    /// `f` held and snapped at `x`.
+    pub fn apply(&self, f: impl Fn(f32) -> f32, x: f32, domain: (f32, f32), range: (f32, f32)) -> f32 {
+        self.level(f(self.hold_x(x, domain)), range)
+    }
+
+    /// The staircase across `domain` as flat runs of `(start, end, level)`.
+    /// With steps there is a run per step; without, `f` is sampled at
+    /// `resolution` points and a run ends halfway between the samples
+    /// either side of a change in level.
+    pub fn runs(
+        &self,
+        f: impl Fn(f32) -> f32,
+        domain: (f32, f32),
+        range: (f32, f32),
+        resolution: usize,
+    ) -> Vec<(f32, f32, f32)> {
+        let (lo, hi) = domain;
+        if let Some(steps) = self.steps.filter(|&s| s > 0) {
+            let width = (hi - lo) / steps as f32;
+            return (0..steps)
+                .map(|k| {
+                    let start = lo + width * k as f32;
+                    let end = if k + 1 == steps { hi } else { start + width };
+                    (start, end, self.level(f(start), range))
+                })
+                .collect();
+        }
+
+        let n = resolution.max(2);
+        let mut runs = Vec::new();
+        let (mut start, mut previous_x) = (lo, lo);
+        let mut current = self.level(f(lo), range);
+        for i in 1..n {
+            let x = lo + (hi - lo) * i as f32 / (n - 1) as f32;
+            let level = self.level(f(x), range);
+            if level != current {
+                let change = (previous_x + x) / 2.0;
+                runs.push((start, change, current));
+                start = change;
+                current = level;
+            }
+            previous_x = x;
+        }
+        runs.push((start, hi, current));
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_snaps_to_the_nearest_step() {
+        let quantizer = Quantizer::new(5);
+        assert_eq!(quantizer.level(0.3, (0.0, 1.0)), 0.25);
+        assert_eq!(quantizer.level(0.4, (0.0, 1.0)), 0.5);
+        assert_eq!(quantizer.level(2.0, (0.0, 1.0)), 1.0);
+        assert_eq!(quantizer.level(-2.0, (0.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn empty_range_and_domain_stay_finite() {
+        let quantizer = Quantizer { levels: 4, steps: Some(3) };
+        assert_eq!(quantizer.level(0.7, (2.0, 2.0)), 2.0);
+        assert_eq!(quantizer.hold_x(1.5, (1.0, 1.0)), 1.5);
+        assert_eq!(quantizer.apply(|x| x, 1.0, (1.0, 1.0), (0.5, 0.5)), 0.5);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::Point;
+
+/// SplitMix64. Kept in-crate rather than taken from `rand` so that a seed
+/// reproduces the same points regardless of dependency versions.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * self.next_f32()
+    }
+
+    /// Standard normal, by the Box–Muller transform.
+    pub fn gaussian(&mut self) -> f32 {
+        let u1 = 1.0 - self.next_f32();
+        let u2 = self.next_f32();
+        (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+    }
+}
+
+/// Generates `n` points whose x values are drawn one from each of `n` equal
+/// slices of `domain`, so they come out sorted and distinct, with y uniform
+/// in `[-amplitude, amplitude]`.
+pub fn random_points(seed: u64, n: usize, domain: (f32, f32), amplitude: f32) -> Vec<Point> {
+    let mut rng = Rng::new(seed);
+    let (min_x, max_x) = domain;
+    let width = (max_x - min_x) / n as f32;
+
+    (0..n)
+        .map(|i| {
+            let x = min_x + width * (i as f32 + rng.next_f32());
+            let y = rng.range(-amplitude, amplitude);
+            Point::new(x, y)
+        })
+        .collect()
+}
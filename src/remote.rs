@@ -0,0 +1,216 @@
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use splines::{Point, SplineType};
+use tungstenite::Message;
+
+// How long a connection waits for a client message before passing on
+// replies and change events.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A client request, with points in data units.
+pub enum Request {
+    SetPoints(Vec<Point>),
+    SetType(SplineType),
+    GetPoints,
+    Sample(usize),
+    Subscribe,
+}
+
+/// A request waiting for the app to answer it on `reply`, which also
+/// carries the change events of a connection that subscribed.
+pub struct Call {
+    pub request: Request,
+    pub reply: Sender<String>,
+    id: Option<Value>,
+}
+
+impl Call {
+    /// Sends `{"ok": true, ...fields}` or `{"ok": false, "error": ...}`,
+    /// echoing the request's `id` when it had one.
+    pub fn answer(&self, result: Result<Value, String>) {
+        send_reply(&self.reply, self.id.as_ref(), result);
+    }
+}
+
+fn send_reply(reply: &Sender<String>, id: Option<&Value>, result: Result<Value, String>) {
+    let mut message = Map::new();
+    match result {
+        Ok(Value::Object(fields)) => {
+            message.insert("ok".to_string(), Value::Bool(true));
+            message.extend(fields);
+        }
+        Ok(_) => {
+            message.insert("ok".to_string(), Value::Bool(true));
+        }
+        Err(e) => {
+            message.insert("ok".to_string(), Value::Bool(false));
+            message.insert("error".to_string(), Value::String(e));
+        }
+    }
+    if let Some(id) = id {
+        message.insert("id".to_string(), id.clone());
+    }
+    // The connection may have closed since asking.
+    let _ = reply.send(Value::Object(message).to_string());
+}
+
+/// A WebSocket server taking one JSON object per text message:
+///
+/// - `{"command": "set_points", "points": [[x, y], ...]}`
+/// - `{"command": "set_type", "spline_type": "linear" | "quadratic" | "cubic"}`
+/// - `{"command": "get_points"}`
+/// - `{"command": "sample", "count": n}`
+/// - `{"command": "subscribe"}`, after which every change to the curve is
+///   sent as `{"event": "changed", "points": ..., "spline_type": ...}`
+pub struct RemoteServer {
+    address: SocketAddr,
+    calls: Receiver<Call>,
+    subscribers: Vec<Sender<String>>,
+    last_state: Option<Value>,
+}
+
+impl RemoteServer {
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let (sender, calls) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                thread::spawn(move || serve(stream, sender));
+            }
+        });
+        Ok(RemoteServer {
+            address,
+            calls,
+            subscribers: Vec::new(),
+            last_state: None,
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn next_call(&self) -> Option<Call> {
+        self.calls.try_recv().ok()
+    }
+
+    pub fn subscribe(&mut self, call: &Call) {
+        self.subscribers.push(call.reply.clone());
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Sends `state` to the subscribers as a change event if it differs
+    /// from the last state published, dropping any that have disconnected.
+    pub fn publish(&mut self, state: Value) {
+        if self.last_state.as_ref() == Some(&state) {
+            return;
+        }
+        let mut event = json!({ "event": "changed" });
+        if let (Value::Object(event), Value::Object(fields)) = (&mut event, state.clone()) {
+            event.extend(fields);
+        }
+        let event = event.to_string();
+        self.subscribers.retain(|s| s.send(event.clone()).is_ok());
+        self.last_state = Some(state);
+    }
+}
+
+pub fn type_name(spline_type: SplineType) -> &'static str {
+    match spline_type {
+        SplineType::Linear => "linear",
+        SplineType::Quadratic => "quadratic",
+        SplineType::Cubic => "cubic",
+    }
+}
+
+// One thread per connection, alternating between reading the client and
+// writing what the app has queued for it.
+fn serve(stream: TcpStream, calls: Sender<Call>) {
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    if socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)).is_err() {
+        return;
+    }
+    let (reply, replies) = channel();
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let message: Result<Value, String> = serde_json::from_str(&text).map_err(|e| e.to_string());
+                let id = message.as_ref().ok().and_then(|m| m.get("id").cloned());
+                match message.and_then(|m| parse_request(&m)) {
+                    Ok(request) => {
+                        let call = Call { request, reply: reply.clone(), id };
+                        if calls.send(call).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => send_reply(&reply, id.as_ref(), Err(e)),
+                }
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        while let Ok(text) = replies.try_recv() {
+            if socket.send(Message::Text(text)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn parse_request(message: &Value) -> Result<Request, String> {
+    let command = message
+        .get("command")
+        .and_then(Value::as_str)
+        .ok_or("expected an object with a `command`")?;
+    match command {
+        "set_points" => {
+            let points = message
+                .get("points")
+                .and_then(Value::as_array)
+                .ok_or("`set_points` expects `points`: [[x, y], ...]")?;
+            points
+                .iter()
+                .map(|p| match p.as_array().map(Vec::as_slice) {
+                    Some([x, y]) => match (x.as_f64(), y.as_f64()) {
+                        (Some(x), Some(y)) => Ok(Point::new(x as f32, y as f32)),
+                        _ => Err("point coordinates must be numbers".to_string()),
+                    },
+                    _ => Err("each point must be an [x, y] pair".to_string()),
+                })
+                .collect::<Result<_, _>>()
+                .map(Request::SetPoints)
+        }
+        "set_type" => match message.get("spline_type").and_then(Value::as_str) {
+            Some("linear") => Ok(Request::SetType(SplineType::Linear)),
+            Some("quadratic") => Ok(Request::SetType(SplineType::Quadratic)),
+            Some("cubic") => Ok(Request::SetType(SplineType::Cubic)),
+            _ => Err("`set_type` expects `spline_type`: linear, quadratic or cubic".to_string()),
+        },
+        "get_points" => Ok(Request::GetPoints),
+        "sample" => message
+            .get("count")
+            .and_then(Value::as_u64)
+            .filter(|&n| (2..=100_000).contains(&n))
+            .map(|n| Request::Sample(n as usize))
+            .ok_or_else(|| "`sample` expects `count` between 2 and 100000".to_string()),
+        "subscribe" => Ok(Request::Subscribe),
+        _ => Err(format!("unknown command `{}`", command)),
+    }
+}
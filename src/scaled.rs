@@ -0,0 +1,153 @@
+use crate::{Point, Scale, Spline, SplineError, SplineType};
+
+/// A spline fitted with log-transformed x and/or y: each log axis goes
+/// through `ln` before fitting and back through `exp` on evaluation, so
+/// points and results stay in linear units. A straight segment on a log-log
+/// (Bode-style) plot comes out as a power law.
+pub struct ScaledSpline {
+    spline: Spline,
+    points: Vec<Point>,
+    x_scale: Scale,
+    y_scale: Scale,
+}
+
+impl ScaledSpline {
+    /// Panics where [`ScaledSpline::try_new`] would return an error.
+    pub fn new(points: &[Point], spline_type: SplineType, x_scale: Scale, y_scale: Scale) -> Self {
+        ScaledSpline::try_new(points, spline_type, x_scale, y_scale).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fits through `points`, which must be positive along each log axis.
+    pub fn try_new(
+        points: &[Point],
+        spline_type: SplineType,
+        x_scale: Scale,
+        y_scale: Scale,
+    ) -> Result<Self, SplineError> {
+        if let Some(i) = points.iter().position(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return Err(SplineError::NonFinite(i));
+        }
+        let positive = |scale: Scale, v: f32| scale == Scale::Linear || v > 0.0;
+        if let Some(i) = points.iter().position(|p| !positive(x_scale, p.x) || !positive(y_scale, p.y)) {
+            return Err(SplineError::NonPositive(i));
+        }
+
+        let transformed: Vec<Point> = points
+            .iter()
+            .map(|p| Point::new(x_scale.forward(p.x), y_scale.forward(p.y)))
+            .collect();
+        let spline = Spline::try_new(&transformed, spline_type).map_err(|e| match e {
+            SplineError::DuplicateX(x) => SplineError::DuplicateX(x_scale.inverse(x)),
+            e => e,
+        })?;
+
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Ok(ScaledSpline {
+            spline,
+            points,
+            x_scale,
+            y_scale,
+        })
+    }
+
+    /// Evaluates in linear units. NaN for `x <= 0` on a log x axis.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        if self.x_scale == Scale::Log && x <= 0.0 {
+            return f32::NAN;
+        }
+        self.y_scale.inverse(self.spline.evaluate(self.x_scale.forward(x)))
+    }
+
+    /// The knots in linear units, in ascending x order.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// The underlying spline, fitted in transformed coordinates.
+    pub fn spline(&self) -> &Spline {
+        &self.spline
+    }
+
+    pub fn x_scale(&self) -> Scale {
+        self.x_scale
+    }
+
+    pub fn y_scale(&self) -> Scale {
+        self.y_scale
+    }
+
+    pub fn domain(&self) -> (f32, f32) {
+        (self.points[0].x, self.points[self.points.len() - 1].x)
+    }
+
+    /// `n` samples in linear units, evenly spaced along the transformed x
+    /// axis (so logarithmically spaced on a log x axis), for export.
+    pub fn samples(&self, n: usize) -> Vec<Point> {
+        self.spline
+            .samples(n)
+            .iter()
+            .map(|p| Point::new(self.x_scale.inverse(p.x), self.y_scale.inverse(p.y)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: [SplineType; 3] = [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic];
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() <= 1e-5 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn log_axes_round_trip_through_the_knots() {
+        let points = [
+            Point::new(0.5, 3.0),
+            Point::new(2.0, 0.1),
+            Point::new(10.0, 40.0),
+            Point::new(100.0, 7.0),
+        ];
+        for (x_scale, y_scale) in [(Scale::Log, Scale::Linear), (Scale::Linear, Scale::Log), (Scale::Log, Scale::Log)] {
+            let spline = ScaledSpline::new(&points, SplineType::Cubic, x_scale, y_scale);
+            assert_eq!(spline.points(), &points[..]);
+            assert_eq!(spline.domain(), (0.5, 100.0));
+            for p in &points {
+                assert!(close(spline.evaluate(p.x), p.y), "{:?}/{:?} at x = {}", x_scale, y_scale, p.x);
+            }
+            let samples = spline.samples(9);
+            assert!(close(samples[0].x, 0.5) && close(samples[0].y, 3.0));
+            assert!(close(samples[8].x, 100.0) && close(samples[8].y, 7.0));
+        }
+    }
+
+    #[test]
+    fn non_positive_values_on_a_log_axis_are_rejected() {
+        let try_new = |points: &[Point], x_scale, y_scale| {
+            ScaledSpline::try_new(points, SplineType::Linear, x_scale, y_scale).err()
+        };
+        let zero_x = [Point::new(1.0, 1.0), Point::new(0.0, 2.0), Point::new(2.0, 3.0)];
+        let negative_y = [Point::new(1.0, 1.0), Point::new(2.0, 2.0), Point::new(3.0, -3.0)];
+
+        assert_eq!(try_new(&zero_x, Scale::Log, Scale::Linear), Some(SplineError::NonPositive(1)));
+        assert_eq!(try_new(&negative_y, Scale::Linear, Scale::Log), Some(SplineError::NonPositive(2)));
+        // Only the log axis has to be positive.
+        assert_eq!(try_new(&zero_x, Scale::Linear, Scale::Log), None);
+        assert_eq!(try_new(&negative_y, Scale::Log, Scale::Linear), None);
+    }
+
+    #[test]
+    fn power_law_is_exact_on_log_log_axes() {
+        let power_law = |x: f32| 2.5 * x.powf(1.7);
+        let points: Vec<Point> = [1.0, 3.0, 4.0, 20.0, 50.0].iter().map(|&x| Point::new(x, power_law(x))).collect();
+        for spline_type in TYPES {
+            let spline = ScaledSpline::new(&points, spline_type, Scale::Log, Scale::Log);
+            for i in 0..=100 {
+                let x = 1.0 + 49.0 * i as f32 / 100.0;
+                assert!(close(spline.evaluate(x), power_law(x)), "{:?} at x = {}", spline_type, x);
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::piecewise::horner;
+
+/// One polynomial piece of a spline, `coeffs[0] + coeffs[1]·dx +
+/// coeffs[2]·dx² + ...` with `dx = x - x0`, valid on `[x0, x1]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment<'a> {
+    pub x0: f32,
+    pub x1: f32,
+    pub coeffs: &'a [f32],
+}
+
+/// Where a segment's slope is largest in magnitude, and that slope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Steepest {
+    pub x: f32,
+    pub slope: f32,
+}
+
+const GAUSS_LEGENDRE_5: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_08),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_08),
+];
+
+impl Segment<'_> {
+    pub fn eval(&self, x: f32) -> f32 {
+        horner(self.coeffs, x - self.x0)
+    }
+
+    /// The coefficient of `dx^k`, zero above the piece's degree.
+    pub fn coefficient(&self, k: usize) -> f32 {
+        self.coeffs.get(k).copied().unwrap_or(0.0)
+    }
+
+    pub fn derivative(&self, x: f32) -> f32 {
+        let dx = x - self.x0;
+        self.coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .rev()
+            .fold(0.0, |acc, (k, &c)| acc * dx + k as f32 * c)
+    }
+
+    /// The slope of largest magnitude on `[x0, x1]`, which occurs at an end
+    /// or where the curvature changes sign in between.
+    pub fn steepest(&self) -> Steepest {
+        let mut candidates = vec![self.x0, self.x1];
+        let curvature = Segment {
+            coeffs: &derivative(&derivative(self.coeffs)),
+            ..*self
+        };
+        candidates.extend(curvature.roots().into_iter().filter(|&x| x > self.x0 && x < self.x1));
+        candidates
+            .into_iter()
+            .map(|x| Steepest { x, slope: self.derivative(x) })
+            .fold(Steepest { x: self.x0, slope: 0.0 }, |best, c| {
+                if c.slope.abs() > best.slope.abs() {
+                    c
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// The x positions in `[x0, x1]` where the piece crosses or touches zero,
+    /// in ascending order. A piece that is identically zero reports none.
+    pub fn roots(&self) -> Vec<f32> {
+        let coeffs: Vec<f64> = self.coeffs.iter().map(|&c| c as f64).collect();
+        if coeffs.iter().all(|&c| c == 0.0) {
+            return Vec::new();
+        }
+        let h = (self.x1 - self.x0) as f64;
+        let mut roots = roots_between(&coeffs, h);
+        roots.dedup_by(|r, prev| (*r - *prev).abs() <= 1e-9 * h.max(1.0));
+        roots.into_iter().map(|t| self.x0 + t as f32).collect()
+    }
+
+    /// Length of the graph of the piece over `[x0, x1]`.
+    pub fn arc_length(&self) -> f32 {
+        self.arc_length_between(self.x0, self.x1)
+    }
+
+    // Length of the graph over `[lo, hi]`, inside `[x0, x1]`.
+    pub(crate) fn arc_length_between(&self, lo: f32, hi: f32) -> f32 {
+        const PANELS: usize = 8;
+
+        let slope: Vec<f64> = derivative(self.coeffs).into_iter().map(|c| c as f64).collect();
+        let start = (lo - self.x0) as f64;
+        let panel = (hi - lo) as f64 / PANELS as f64;
+
+        let mut length = 0.0;
+        for k in 0..PANELS {
+            let mid = start + panel * (k as f64 + 0.5);
+            for &(node, weight) in &GAUSS_LEGENDRE_5 {
+                let t = mid + 0.5 * panel * node;
+                let slope = slope.iter().rev().fold(0.0, |acc, &c| acc * t + c);
+                length += weight * (1.0 + slope * slope).sqrt();
+            }
+        }
+        (length * 0.5 * panel) as f32
+    }
+}
+
+/// The coefficients of the derivative of `coeffs`, one fewer.
+pub(crate) fn derivative(coeffs: &[f32]) -> Vec<f32> {
+    coeffs.iter().enumerate().skip(1).map(|(k, &c)| k as f32 * c).collect()
+}
+
+// The roots in `[0, h]` of the polynomial `coeffs` in `t`, ascending and
+// possibly repeated. Between consecutive roots of the derivative the
+// polynomial is monotone, so each interval holds at most one root and
+// bisection is enough.
+fn roots_between(coeffs: &[f64], h: f64) -> Vec<f64> {
+    let degree = coeffs.iter().rposition(|&c| c != 0.0).unwrap_or(0);
+    let coeffs = &coeffs[..=degree];
+    match degree {
+        0 => return Vec::new(),
+        1 => {
+            let t = -coeffs[0] / coeffs[1];
+            return if (0.0..=h).contains(&t) { vec![t] } else { Vec::new() };
+        }
+        _ => {}
+    }
+    let p = |t: f64| coeffs.iter().rev().fold(0.0, |acc, &c| acc * t + c);
+    let slope: Vec<f64> = coeffs.iter().enumerate().skip(1).map(|(k, &c)| k as f64 * c).collect();
+
+    let mut breaks = vec![0.0];
+    breaks.extend(roots_between(&slope, h).into_iter().filter(|&t| t > 0.0 && t < h));
+    breaks.push(h);
+
+    let mut roots: Vec<f64> = Vec::new();
+    for w in breaks.windows(2) {
+        let (mut lo, mut hi) = (w[0], w[1]);
+        let (mut f_lo, f_hi) = (p(lo), p(hi));
+
+        if f_lo == 0.0 {
+            roots.push(lo);
+        }
+        if f_hi == 0.0 {
+            roots.push(hi);
+        }
+        if f_lo == 0.0 || f_hi == 0.0 || (f_lo < 0.0) == (f_hi < 0.0) {
+            continue;
+        }
+
+        for _ in 0..64 {
+            let mid = 0.5 * (lo + hi);
+            let f_mid = p(mid);
+            if f_mid == 0.0 {
+                lo = mid;
+                hi = mid;
+                break;
+            }
+            if (f_mid < 0.0) == (f_lo < 0.0) {
+                lo = mid;
+                f_lo = f_mid;
+            } else {
+                hi = mid;
+            }
+        }
+        roots.push(0.5 * (lo + hi));
+    }
+    roots.sort_by(|l, r| l.total_cmp(r));
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quartic_roots_and_steepest() {
+        // (t - 1)(t - 2)(t - 3)(t - 4) on [0, 5].
+        let coeffs = [24.0, -50.0, 35.0, -10.0, 1.0];
+        let segment = Segment {
+            x0: 10.0,
+            x1: 15.0,
+            coeffs: &coeffs,
+        };
+        let roots = segment.roots();
+        assert_eq!(roots.len(), 4);
+        for (root, expected) in roots.iter().zip([11.0, 12.0, 13.0, 14.0]) {
+            assert!((root - expected).abs() < 1e-4, "{:?}", roots);
+        }
+        // The slope is steepest at the ends, -50 at t = 0 and 50 at t = 5.
+        let Steepest { x, slope } = segment.steepest();
+        assert!(
+            (x == 10.0 && slope == -50.0) || (x == 15.0 && slope == 50.0),
+            "{} {}",
+            x,
+            slope
+        );
+    }
+
+    #[test]
+    fn line_arc_length() {
+        let segment = Segment {
+            x0: 0.0,
+            x1: 3.0,
+            coeffs: &[1.0, 1.0],
+        };
+        assert!((segment.arc_length() - 3.0 * 2f32.sqrt()).abs() < 1e-5);
+        assert!((segment.arc_length_between(1.0, 2.0) - 2f32.sqrt()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::segment::derivative;
+use crate::{Point, Segment, Spline, SplineError, SplineType};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Increasing,
+    Decreasing,
+    Convex,
+    Concave,
+}
+
+/// A shape the curve must have between `min_x` and `max_x`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeConstraint {
+    pub shape: Shape,
+    pub min_x: f32,
+    pub max_x: f32,
+}
+
+impl ShapeConstraint {
+    pub fn new(shape: Shape, min_x: f32, max_x: f32) -> Self {
+        ShapeConstraint {
+            shape,
+            min_x: min_x.min(max_x),
+            max_x: min_x.max(max_x),
+        }
+    }
+
+    fn overlaps(&self, x0: f32, x1: f32) -> bool {
+        x0 < self.max_x && x1 > self.min_x
+    }
+
+    fn sign(&self) -> f32 {
+        match self.shape {
+            Shape::Increasing | Shape::Convex => 1.0,
+            Shape::Decreasing | Shape::Concave => -1.0,
+        }
+    }
+
+    // The derivative the shape needs to be non-negative on a piece: the
+    // first for monotonicity, the second for convexity, negated for the
+    // decreasing and concave cases.
+    fn condition(&self, segment: &Segment) -> Vec<f32> {
+        let slope = derivative(segment.coeffs);
+        let condition = match self.shape {
+            Shape::Increasing | Shape::Decreasing => slope,
+            Shape::Convex | Shape::Concave => derivative(&slope),
+        };
+        condition.into_iter().map(|c| self.sign() * c).collect()
+    }
+}
+
+impl Spline {
+    /// The x-intervals where the curve breaks any of `constraints`, merged
+    /// and in ascending order. Changes of slope at a knot count too, so a
+    /// linear spline that bends the wrong way flags the pieces either side.
+    pub fn shape_violations(&self, constraints: &[ShapeConstraint]) -> Vec<(f32, f32)> {
+        let points = self.points();
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| (lo.min(p.y), hi.max(p.y)));
+        let (min_x, max_x) = self.domain();
+        let width = (max_x - min_x).max(f32::EPSILON);
+        // Fitted constraints hold to rounding, which this keeps from showing.
+        let tolerance = |shape: Shape| {
+            let slope = 1e-4 * (max_y - min_y).max(f32::EPSILON) / width;
+            match shape {
+                Shape::Increasing | Shape::Decreasing => slope,
+                Shape::Convex | Shape::Concave => slope / width,
+            }
+        };
+
+        let segments: Vec<Segment> = self.segments().collect();
+        let mut intervals = Vec::new();
+        for constraint in constraints {
+            let tolerance = tolerance(constraint.shape);
+            for (i, segment) in segments.iter().enumerate() {
+                if !constraint.overlaps(segment.x0, segment.x1) {
+                    continue;
+                }
+                let (lo, hi) = (segment.x0.max(constraint.min_x), segment.x1.min(constraint.max_x));
+                let coeffs = constraint.condition(segment);
+                let condition = Segment {
+                    coeffs: &coeffs,
+                    ..*segment
+                };
+
+                let mut breaks = vec![lo];
+                breaks.extend(condition.roots().into_iter().filter(|&x| x > lo && x < hi));
+                breaks.push(hi);
+                for w in breaks.windows(2) {
+                    if condition.eval(0.5 * (w[0] + w[1])) < -tolerance {
+                        intervals.push((w[0], w[1]));
+                    }
+                }
+
+                // A kink where this piece meets the next has to turn the right
+                // way for convexity; for monotonicity the pieces cover it.
+                let is_convexity = matches!(constraint.shape, Shape::Convex | Shape::Concave);
+                if let Some(next) = segments.get(i + 1).filter(|_| is_convexity) {
+                    let turn = constraint.sign() * (next.derivative(next.x0) - segment.derivative(next.x0));
+                    let inside = next.x0 >= constraint.min_x && next.x0 <= constraint.max_x;
+                    if inside && turn < -tolerance * width {
+                        intervals.push((lo, next.x1.min(constraint.max_x)));
+                    }
+                }
+            }
+        }
+
+        intervals.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut merged: Vec<(f32, f32)> = Vec::new();
+        for (lo, hi) in intervals {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        merged
+    }
+
+    /// The cubic curve closest to the interpolating cubic spline of `data`
+    /// that satisfies `constraints`.
+    ///
+    /// The curve is a cubic Hermite spline whose knot values and slopes are
+    /// the point closest to the unconstrained spline's, in a least-squares
+    /// sense with slopes weighted by the mean knot spacing, subject to
+    /// linear conditions on each constrained piece: Bézier control points
+    /// in order for monotonicity, the second derivative's sign at both ends
+    /// for convexity. That quadratic program is solved with Hildreth's
+    /// method. A spline that already has the shape comes back unchanged;
+    /// otherwise the curve gives up passing through some of the data.
+    ///
+    /// Fails where [`Spline::try_new`] would.
+    pub fn fit_shape_constrained(data: &[Point], constraints: &[ShapeConstraint]) -> Result<Spline, SplineError> {
+        let reference = Spline::try_new(data, SplineType::Cubic)?;
+        let points = reference.points();
+        let n = points.len();
+        let h: Vec<f64> = points.windows(2).map(|w| (w[1].x - w[0].x) as f64).collect();
+        let last = reference.segment(n - 2);
+
+        // Unknowns are the knot values followed by the knot slopes.
+        let mut z: Vec<f64> = points.iter().map(|p| p.y as f64).collect();
+        z.extend((0..n).map(|i| {
+            if i < n - 1 {
+                reference.segment(i).coefficient(1) as f64
+            } else {
+                last.derivative(last.x1) as f64
+            }
+        }));
+        let mean_spacing = h.iter().sum::<f64>() / h.len() as f64;
+        let inverse_weight: Vec<f64> = (0..2 * n)
+            .map(|k| if k < n { 1.0 } else { 1.0 / (mean_spacing * mean_spacing) })
+            .collect();
+
+        let rows = constraint_rows(points, &h, constraints);
+        let norms: Vec<f64> = rows
+            .iter()
+            .map(|row| row.iter().map(|&(k, a)| a * a * inverse_weight[k]).sum())
+            .collect();
+        let scale = z.iter().fold(1.0f64, |m, v| m.max(v.abs()));
+
+        let mut multipliers = vec![0.0; rows.len()];
+        for _ in 0..MAX_SWEEPS {
+            let mut largest_step = 0.0f64;
+            for (r, row) in rows.iter().enumerate() {
+                if norms[r] == 0.0 {
+                    continue;
+                }
+                let residual: f64 = row.iter().map(|&(k, a)| a * z[k]).sum();
+                let multiplier = (multipliers[r] - residual / norms[r]).max(0.0);
+                let step = multiplier - multipliers[r];
+                if step != 0.0 {
+                    for &(k, a) in row {
+                        z[k] += inverse_weight[k] * a * step;
+                        largest_step = largest_step.max((inverse_weight[k] * a * step).abs());
+                    }
+                    multipliers[r] = multiplier;
+                }
+            }
+            if largest_step < 1e-10 * scale {
+                break;
+            }
+        }
+
+        let knots: Vec<Point> = points
+            .iter()
+            .zip(&z)
+            .map(|(p, &y)| Point::new(p.x, y as f32))
+            .collect();
+        let pieces = (0..n - 1)
+            .map(|i| {
+                let delta = (z[i + 1] - z[i]) / h[i];
+                let (m0, m1) = (z[n + i], z[n + i + 1]);
+                let (c, d) = ((3.0 * delta - 2.0 * m0 - m1) / h[i], (m0 + m1 - 2.0 * delta) / (h[i] * h[i]));
+                vec![z[i] as f32, m0 as f32, c as f32, d as f32]
+            })
+            .collect();
+        Ok(Spline::from_pieces(knots, SplineType::Cubic, pieces))
+    }
+}
+
+const MAX_SWEEPS: usize = 20_000;
+
+// Sparse rows `a` of the conditions `a · z >= 0` on the Hermite unknowns, one
+// set per piece that a constraint overlaps.
+fn constraint_rows(points: &[Point], h: &[f64], constraints: &[ShapeConstraint]) -> Vec<Vec<(usize, f64)>> {
+    let n = points.len();
+    let mut rows = Vec::new();
+    for constraint in constraints {
+        let sign = constraint.sign() as f64;
+        for i in 0..n - 1 {
+            if !constraint.overlaps(points[i].x, points[i + 1].x) {
+                continue;
+            }
+            let (v0, v1, m0, m1) = (i, i + 1, n + i, n + i + 1);
+            let third = h[i] / 3.0;
+            let pieces: Vec<Vec<(usize, f64)>> = match constraint.shape {
+                // The Bézier control points v0, v0 + h·m0/3, v1 - h·m1/3, v1
+                // in order; a cubic is monotone wherever they are.
+                Shape::Increasing | Shape::Decreasing => vec![
+                    vec![(m0, third)],
+                    vec![(v1, 1.0), (v0, -1.0), (m0, -third), (m1, -third)],
+                    vec![(m1, third)],
+                ],
+                // The second derivative is linear on a piece, so its sign at
+                // both ends decides convexity; these are h/2 times it.
+                Shape::Convex | Shape::Concave => vec![
+                    vec![(v1, 3.0 / h[i]), (v0, -3.0 / h[i]), (m0, -2.0), (m1, -1.0)],
+                    vec![(v0, 3.0 / h[i]), (v1, -3.0 / h[i]), (m0, 1.0), (m1, 2.0)],
+                ],
+            };
+            rows.extend(
+                pieces
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|(k, a)| (k, sign * a)).collect()),
+            );
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coords: &[(f32, f32)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    #[test]
+    fn a_curve_with_the_shape_comes_back_unchanged() {
+        let data = points(&[(0.0, 0.0), (1.0, 1.0), (2.0, 1.5), (3.0, 3.0)]);
+        let constraint = ShapeConstraint::new(Shape::Increasing, 0.0, 3.0);
+        let reference = Spline::new(&data, SplineType::Cubic);
+        assert!(reference.shape_violations(&[constraint]).is_empty());
+
+        let fitted = Spline::fit_shape_constrained(&data, &[constraint]).unwrap();
+        for i in 0..=30 {
+            let x = i as f32 * 0.1;
+            assert!((fitted.evaluate(x) - reference.evaluate(x)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn monotone_fit_removes_the_overshoot() {
+        // A step, which the natural cubic overshoots on both sides.
+        let data = points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 1.0), (4.0, 1.0), (5.0, 1.0)]);
+        let constraint = ShapeConstraint::new(Shape::Increasing, 0.0, 5.0);
+        assert!(!Spline::new(&data, SplineType::Cubic)
+            .shape_violations(&[constraint])
+            .is_empty());
+
+        let fitted = Spline::fit_shape_constrained(&data, &[constraint]).unwrap();
+        assert!(fitted.shape_violations(&[constraint]).is_empty());
+        let samples = fitted.samples(501);
+        assert!(samples.windows(2).all(|w| w[1].y >= w[0].y - 1e-5));
+    }
+
+    #[test]
+    fn convex_fit_turns_upward_throughout() {
+        let data = points(&[(0.0, 2.0), (1.0, 0.5), (2.0, 0.8), (3.0, 0.0), (4.0, 1.0), (5.0, 3.0)]);
+        let constraint = ShapeConstraint::new(Shape::Convex, 0.0, 5.0);
+        let fitted = Spline::fit_shape_constrained(&data, &[constraint]).unwrap();
+        assert!(fitted.shape_violations(&[constraint]).is_empty());
+
+        let slopes: Vec<f32> = (0..=50).map(|i| fitted.derivative(i as f32 * 0.1)).collect();
+        assert!(slopes.windows(2).all(|w| w[1] >= w[0] - 1e-4));
+    }
+
+    #[test]
+    fn too_few_points_is_an_error() {
+        let constraint = ShapeConstraint::new(Shape::Increasing, 0.0, 1.0);
+        assert_eq!(
+            Spline::fit_shape_constrained(&points(&[(0.0, 0.0)]), &[constraint]).err(),
+            Some(SplineError::TooFewPoints)
+        );
+    }
+}
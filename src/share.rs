@@ -0,0 +1,280 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::encoding::{type_code, type_from_code, Reader};
+use crate::{Anchor, Annotation, DecodeError, Point, PointTag, SplineType};
+
+const PREFIX: &str = "spline:";
+// Version 2 adds annotations and 3 adds point tags; designs without them
+// are still written with the earlier versions.
+const VERSION: u8 = 1;
+const VERSION_WITH_ANNOTATIONS: u8 = 2;
+const VERSION_WITH_TAGS: u8 = 3;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The points and settings of a curve design, which round-trip through a
+/// short text string that can be pasted into chat or a bug report.
+#[derive(Clone, Debug)]
+pub struct Design {
+    pub points: Vec<Point>,
+    pub spline_type: SplineType,
+    pub annotations: Vec<Annotation>,
+    pub tags: Vec<PointTag>,
+}
+
+impl Design {
+    /// `spline:` followed by URL-safe, unpadded base64 of
+    /// `version u8 | spline type u8 | point count u32 | (x f32, y f32)*`,
+    /// little-endian. Version 2 follows that with
+    /// `annotation count u32 | annotation*`, each one
+    /// `anchor u8 | (point index u32 | x f32, y f32) | offset x f32, y f32 |
+    /// text length u32 | UTF-8 text`, with anchor 0 for a point and 1 for a
+    /// canvas position. Version 3 adds `tag count u32 | tag*` after the
+    /// annotations, each one
+    /// `point index u32 | key length u32 | key | value length u32 | value`.
+    pub fn to_share_string(&self) -> String {
+        let version = if !self.tags.is_empty() {
+            VERSION_WITH_TAGS
+        } else if !self.annotations.is_empty() {
+            VERSION_WITH_ANNOTATIONS
+        } else {
+            VERSION
+        };
+        let mut bytes = vec![version, type_code(self.spline_type)];
+        bytes.extend_from_slice(&(self.points.len() as u32).to_le_bytes());
+        for p in &self.points {
+            write_point(&mut bytes, *p);
+        }
+
+        if version >= VERSION_WITH_ANNOTATIONS {
+            bytes.extend_from_slice(&(self.annotations.len() as u32).to_le_bytes());
+            for annotation in &self.annotations {
+                match annotation.anchor {
+                    Anchor::Point(i) => {
+                        bytes.push(0);
+                        bytes.extend_from_slice(&(i as u32).to_le_bytes());
+                    }
+                    Anchor::Position(p) => {
+                        bytes.push(1);
+                        write_point(&mut bytes, p);
+                    }
+                }
+                write_point(&mut bytes, annotation.offset);
+                bytes.extend_from_slice(&(annotation.text.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(annotation.text.as_bytes());
+            }
+        }
+        if version == VERSION_WITH_TAGS {
+            bytes.extend_from_slice(&(self.tags.len() as u32).to_le_bytes());
+            for tag in &self.tags {
+                bytes.extend_from_slice(&(tag.point as u32).to_le_bytes());
+                write_text(&mut bytes, &tag.key);
+                write_text(&mut bytes, &tag.value);
+            }
+        }
+        format!("{}{}", PREFIX, base64_encode(&bytes))
+    }
+
+    /// Parses a string from [`Design::to_share_string`], ignoring
+    /// surrounding whitespace.
+    pub fn from_share_string(text: &str) -> Result<Design, DecodeError> {
+        let encoded = text.trim().strip_prefix(PREFIX).ok_or(DecodeError::BadMagic)?;
+        let bytes = base64_decode(encoded).ok_or(DecodeError::InvalidText)?;
+        let mut reader = Reader::new(&bytes);
+
+        let version = reader.u8()?;
+        if !(VERSION..=VERSION_WITH_TAGS).contains(&version) {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let spline_type = type_from_code(reader.u8()?)?;
+        let n = reader.u32()?;
+        let points = (0..n)
+            .map(|_| read_point(&mut reader))
+            .collect::<Result<Vec<Point>, DecodeError>>()?;
+        if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return Err(DecodeError::InvalidKnots);
+        }
+
+        let mut annotations = Vec::new();
+        if version >= VERSION_WITH_ANNOTATIONS {
+            for _ in 0..reader.u32()? {
+                let anchor = match reader.u8()? {
+                    0 => Anchor::Point(reader.u32()? as usize),
+                    1 => Anchor::Position(read_point(&mut reader)?),
+                    _ => return Err(DecodeError::InvalidAnnotation),
+                };
+                let offset = read_point(&mut reader)?;
+                let len = reader.u32()? as usize;
+                let text = String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| DecodeError::InvalidAnnotation)?;
+                let annotation = Annotation { text, anchor, offset };
+                let label = annotation.label_position(&points);
+                if !label.is_some_and(|p| p.x.is_finite() && p.y.is_finite()) {
+                    return Err(DecodeError::InvalidAnnotation);
+                }
+                annotations.push(annotation);
+            }
+        }
+
+        let mut tags = Vec::new();
+        if version == VERSION_WITH_TAGS {
+            for _ in 0..reader.u32()? {
+                let point = reader.u32()? as usize;
+                let key = read_text(&mut reader)?;
+                let value = read_text(&mut reader)?;
+                if point >= points.len() {
+                    return Err(DecodeError::InvalidTag);
+                }
+                tags.push(PointTag { point, key, value });
+            }
+        }
+        reader.finish()?;
+
+        Ok(Design {
+            points,
+            spline_type,
+            annotations,
+            tags,
+        })
+    }
+
+    /// Saves the design as a session file holding its share string, so a
+    /// saved session can also be pasted or passed to `--share`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_share_string() + "\n")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Design> {
+        let text = fs::read_to_string(path)?;
+        Design::from_share_string(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn write_point(bytes: &mut Vec<u8>, p: Point) {
+    bytes.extend_from_slice(&p.x.to_le_bytes());
+    bytes.extend_from_slice(&p.y.to_le_bytes());
+}
+
+fn read_point(reader: &mut Reader) -> Result<Point, DecodeError> {
+    Ok(Point::new(reader.f32()?, reader.f32()?))
+}
+
+fn write_text(bytes: &mut Vec<u8>, text: &str) {
+    bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+fn read_text(reader: &mut Reader) -> Result<String, DecodeError> {
+    let len = reader.u32()? as usize;
+    String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| DecodeError::InvalidTag)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &b)| group | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            text.push(ALPHABET[(group >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    text
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 6 | value) & 0xFFFF;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn design(annotations: Vec<Annotation>, tags: Vec<PointTag>) -> Design {
+        Design {
+            points: vec![Point::new(-1.5, 2.0), Point::new(0.0, -0.25), Point::new(3.0, 1e-3)],
+            spline_type: SplineType::Quadratic,
+            annotations,
+            tags,
+        }
+    }
+
+    fn round_trip(design: &Design) -> (u8, Design) {
+        let text = design.to_share_string();
+        let version = base64_decode(&text[PREFIX.len()..]).unwrap()[0];
+        (version, Design::from_share_string(&format!("  {}\n", text)).unwrap())
+    }
+
+    #[test]
+    fn designs_round_trip_at_the_lowest_version_that_holds_them() {
+        let annotations = vec![
+            Annotation {
+                text: "peak ✓".into(),
+                anchor: Anchor::Point(1),
+                offset: Point::new(10.0, -4.0),
+            },
+            Annotation {
+                text: String::new(),
+                anchor: Anchor::Position(Point::new(0.5, 0.5)),
+                offset: Point::new(0.0, 20.0),
+            },
+        ];
+        let tags = vec![PointTag {
+            point: 2,
+            key: "marker".into(),
+            value: "beat-4".into(),
+        }];
+
+        for (design, expected) in [
+            (design(Vec::new(), Vec::new()), VERSION),
+            (design(annotations.clone(), Vec::new()), VERSION_WITH_ANNOTATIONS),
+            (design(annotations, tags), VERSION_WITH_TAGS),
+        ] {
+            let (version, decoded) = round_trip(&design);
+            assert_eq!(version, expected);
+            assert_eq!(decoded.points, design.points);
+            assert_eq!(decoded.spline_type, design.spline_type);
+            assert_eq!(decoded.annotations, design.annotations);
+            assert_eq!(decoded.tags, design.tags);
+        }
+    }
+
+    #[test]
+    fn base64_round_trips_every_tail_length() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..8 {
+            assert_eq!(base64_decode(&base64_encode(&bytes[..len])).unwrap(), &bytes[..len]);
+        }
+        assert_eq!(base64_decode(&base64_encode(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn malformed_strings_are_an_error() {
+        let text = design(Vec::new(), Vec::new()).to_share_string();
+        let decode = |text: &str| Design::from_share_string(text).err();
+        assert_eq!(decode(&text[PREFIX.len()..]), Some(DecodeError::BadMagic));
+        assert_eq!(decode(&format!("{}+", text)), Some(DecodeError::InvalidText));
+        assert_eq!(decode(&text[..text.len() - 4]), Some(DecodeError::Truncated));
+        assert_eq!(decode(&format!("{}{}", PREFIX, base64_encode(&[9, 0]))), Some(DecodeError::UnsupportedVersion(9)));
+
+        let tag = PointTag {
+            point: 3,
+            key: "k".into(),
+            value: "v".into(),
+        };
+        let text = design(Vec::new(), vec![tag]).to_share_string();
+        assert_eq!(decode(&text), Some(DecodeError::InvalidTag));
+    }
+}
@@ -0,0 +1,635 @@
+use crate::spline::is_finite;
+use crate::{Point, Spline, SplineError, SplineType};
+
+/// A cubic smoothing spline: the curve minimising
+/// `Σ (yᵢ - g(xᵢ))² + λ ∫ g''(x)² dx`, which is the natural cubic spline
+/// through the fitted values at the data's x positions.
+///
+/// The fit keeps the diagonal of its influence matrix and the residual
+/// variance, which is what pointwise confidence bands are built from.
+pub struct SmoothingSpline {
+    lambda: f32,
+    edf: f32,
+    fitted: Vec<Point>,
+    spline: Spline,
+    leverages: Vec<f32>,
+    residual_variance: f32,
+    standard_error: Spline,
+}
+
+impl SmoothingSpline {
+    /// Fits with a fixed smoothing parameter. Samples sharing an x value are
+    /// merged into their mean.
+    ///
+    /// Fails if a sample is NaN or infinite, or if fewer than 2 distinct x
+    /// values are given.
+    pub fn fit(data: &[Point], lambda: f32) -> Result<Self, SplineError> {
+        let problem = Problem::new(data, None)?;
+        let solution = problem.solve(lambda as f64);
+        Ok(SmoothingSpline::from_solution(&problem, lambda, &solution))
+    }
+
+    /// Fits with the smoothing parameter that minimises the generalized
+    /// cross-validation score `n·RSS / (n - tr A)²`. Fails like
+    /// [`SmoothingSpline::fit`].
+    pub fn fit_gcv(data: &[Point]) -> Result<Self, SplineError> {
+        let problem = Problem::new(data, None)?;
+        let lambda = problem.gcv_lambda();
+        let solution = problem.solve(lambda);
+        Ok(SmoothingSpline::from_solution(&problem, lambda as f32, &solution))
+    }
+
+    fn from_solution(problem: &Problem, lambda: f32, solution: &Solution) -> Self {
+        let fitted: Vec<Point> = problem
+            .x
+            .iter()
+            .zip(&solution.fitted)
+            .map(|(&x, &y)| Point::new(x as f32, y as f32))
+            .collect();
+
+        // Wahba's Bayesian estimate: Var ĝ(xᵢ) = σ² Aᵢᵢ, with σ² = RSS / (n - tr A).
+        let n = fitted.len() as f64;
+        let residual_variance = if n - solution.trace > 1e-9 { solution.rss / (n - solution.trace) } else { 0.0 };
+        let standard_errors: Vec<Point> = fitted
+            .iter()
+            .zip(&solution.leverages)
+            .map(|(p, &leverage)| Point::new(p.x, (residual_variance * leverage.max(0.0)).sqrt() as f32))
+            .collect();
+
+        SmoothingSpline {
+            lambda,
+            edf: solution.trace as f32,
+            spline: Spline::new(&fitted, SplineType::Cubic),
+            fitted,
+            leverages: solution.leverages.iter().map(|&l| l as f32).collect(),
+            residual_variance: residual_variance as f32,
+            standard_error: Spline::new(&standard_errors, SplineType::Linear),
+        }
+    }
+
+    pub fn lambda(&self) -> f32 {
+        self.lambda
+    }
+
+    /// Effective degrees of freedom, the trace of the influence matrix.
+    pub fn edf(&self) -> f32 {
+        self.edf
+    }
+
+    /// The smoothed values at each distinct data x.
+    pub fn fitted(&self) -> &[Point] {
+        &self.fitted
+    }
+
+    pub fn spline(&self) -> &Spline {
+        &self.spline
+    }
+
+    pub fn evaluate(&self, x: f32) -> f32 {
+        self.spline.evaluate(x)
+    }
+
+    /// The diagonal of the influence matrix, one entry per fitted value.
+    pub fn leverages(&self) -> &[f32] {
+        &self.leverages
+    }
+
+    /// Estimated noise variance, `RSS / (n - edf)`.
+    pub fn residual_variance(&self) -> f32 {
+        self.residual_variance
+    }
+
+    /// Pointwise standard error of the fit, interpolated linearly between
+    /// the data x positions.
+    pub fn standard_error(&self, x: f32) -> f32 {
+        self.standard_error.evaluate(x)
+    }
+
+    /// The band `ĝ(x) ± z·se(x)`; `z = 1.96` gives approximate 95% coverage.
+    pub fn confidence_band(&self, x: f32, z: f32) -> (f32, f32) {
+        let center = self.evaluate(x);
+        let half_width = z * self.standard_error(x);
+        (center - half_width, center + half_width)
+    }
+}
+
+/// A smoothing spline fitted by iteratively reweighted least squares with
+/// Huber weights, so isolated spikes in the data stop dragging the curve.
+pub struct RobustFit {
+    smoothing: SmoothingSpline,
+    weights: Vec<f32>,
+}
+
+impl RobustFit {
+    const HUBER_K: f32 = 1.345;
+    const OUTLIER_WEIGHT: f32 = 0.5;
+
+    /// Each pass refits with GCV, then gives samples whose residual exceeds
+    /// `k·σ̂` the weight `k·σ̂ / |r|`, with σ̂ the scaled median absolute
+    /// deviation of the residuals. Fails like [`SmoothingSpline::fit`].
+    pub fn fit(data: &[Point]) -> Result<Self, SplineError> {
+        let mut weights = vec![1.0f32; data.len()];
+        let mut smoothing = SmoothingSpline::fit_gcv(data)?;
+
+        for _ in 0..20 {
+            let residuals: Vec<f32> = data.iter().map(|p| p.y - smoothing.evaluate(p.x)).collect();
+            let scale = 1.4826 * median_absolute_deviation(&residuals);
+            if scale <= f32::EPSILON {
+                break;
+            }
+
+            let threshold = Self::HUBER_K * scale;
+            let next_weights: Vec<f32> = residuals
+                .iter()
+                .map(|r| if r.abs() <= threshold { 1.0 } else { threshold / r.abs() })
+                .collect();
+            let change = weights
+                .iter()
+                .zip(&next_weights)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f32::max);
+            weights = next_weights;
+
+            let problem = Problem::new(data, Some(&weights))?;
+            let lambda = problem.gcv_lambda();
+            smoothing = SmoothingSpline::from_solution(&problem, lambda as f32, &problem.solve(lambda));
+
+            if change < 1e-4 {
+                break;
+            }
+        }
+
+        Ok(RobustFit { smoothing, weights })
+    }
+
+    pub fn smoothing(&self) -> &SmoothingSpline {
+        &self.smoothing
+    }
+
+    pub fn into_smoothing(self) -> SmoothingSpline {
+        self.smoothing
+    }
+
+    /// Final weight of each input sample, in input order.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Whether sample `i` ended up with less than half weight, i.e. a
+    /// residual beyond roughly 2.7σ̂.
+    pub fn is_outlier(&self, i: usize) -> bool {
+        self.weights[i] < Self::OUTLIER_WEIGHT
+    }
+}
+
+fn median_absolute_deviation(values: &[f32]) -> f32 {
+    let median = |values: &mut Vec<f32>| {
+        values.sort_by(|a, b| a.total_cmp(b));
+        let len = values.len();
+        (values[(len - 1) / 2] + values[len / 2]) / 2.0
+    };
+
+    let mut sorted = values.to_vec();
+    let center = median(&mut sorted);
+    let mut deviations: Vec<f32> = values.iter().map(|v| (v - center).abs()).collect();
+    median(&mut deviations)
+}
+
+// The Reinsch formulation: with Q the (n × n-2) second-difference matrix and
+// R the (n-2 × n-2) tridiagonal Gram matrix, the interior second derivatives
+// γ solve (R + λQᵀW⁻¹Q)γ = Qᵀy and the fitted values are y - λW⁻¹Qγ.
+// Everything is banded, so each solve is O(n).
+struct Problem {
+    x: Vec<f64>,
+    y: Vec<f64>,
+    w: Vec<f64>,
+    h: Vec<f64>,
+}
+
+struct Solution {
+    fitted: Vec<f64>,
+    trace: f64,
+    rss: f64,
+    leverages: Vec<f64>,
+}
+
+impl Problem {
+    // Samples sharing an x are merged into their weighted mean, carrying the
+    // summed weight, which leaves the criterion unchanged up to a constant.
+    fn new(data: &[Point], weights: Option<&[f32]>) -> Result<Self, SplineError> {
+        if let Some(i) = data.iter().position(|p| !is_finite(p)) {
+            return Err(SplineError::NonFinite(i));
+        }
+        let mut order: Vec<usize> = (0..data.len()).collect();
+        order.sort_by(|&a, &b| data[a].x.total_cmp(&data[b].x));
+
+        let mut x: Vec<f64> = Vec::with_capacity(data.len());
+        let mut y: Vec<f64> = Vec::with_capacity(data.len());
+        let mut w: Vec<f64> = Vec::with_capacity(data.len());
+        for i in order {
+            let p = data[i];
+            let weight = weights.map_or(1.0, |weights| weights[i] as f64);
+            match x.last() {
+                Some(&last) if last == p.x as f64 => {
+                    let j = y.len() - 1;
+                    y[j] = (y[j] * w[j] + p.y as f64 * weight) / (w[j] + weight);
+                    w[j] += weight;
+                }
+                _ => {
+                    x.push(p.x as f64);
+                    y.push(p.y as f64);
+                    w.push(weight);
+                }
+            }
+        }
+
+        if x.len() < 2 {
+            return Err(SplineError::TooFewPoints);
+        }
+
+        let h = x.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        Ok(Problem { x, y, w, h })
+    }
+
+    fn interior(&self) -> usize {
+        self.x.len() - 2
+    }
+
+    // Nonzeros of column j of Q, at rows j, j + 1 and j + 2.
+    fn q(&self, j: usize) -> [f64; 3] {
+        let (h0, h1) = (self.h[j], self.h[j + 1]);
+        [1.0 / h0, -1.0 / h0 - 1.0 / h1, 1.0 / h1]
+    }
+
+    fn system(&self, lambda: f64) -> Banded {
+        let m = self.interior();
+        let mut band = Banded::zeros(m);
+
+        for j in 0..m {
+            let qj = self.q(j);
+            let w = &self.w[j..j + 3];
+
+            band.d0[j] = (self.h[j] + self.h[j + 1]) / 3.0
+                + lambda * (qj[0] * qj[0] / w[0] + qj[1] * qj[1] / w[1] + qj[2] * qj[2] / w[2]);
+            if j + 1 < m {
+                let qk = self.q(j + 1);
+                band.d1[j] = self.h[j + 1] / 6.0 + lambda * (qj[1] * qk[0] / w[1] + qj[2] * qk[1] / w[2]);
+            }
+            if j + 2 < m {
+                let qk = self.q(j + 2);
+                band.d2[j] = lambda * qj[2] * qk[0] / w[2];
+            }
+        }
+        band
+    }
+
+    fn solve(&self, lambda: f64) -> Solution {
+        let n = self.x.len();
+        let m = self.interior();
+        if m == 0 {
+            return Solution {
+                fitted: self.y.clone(),
+                trace: n as f64,
+                rss: 0.0,
+                leverages: vec![1.0; n],
+            };
+        }
+
+        let mut factor = self.system(lambda);
+        factor.factorize();
+
+        let qty: Vec<f64> = (0..m)
+            .map(|j| {
+                let q = self.q(j);
+                q[0] * self.y[j] + q[1] * self.y[j + 1] + q[2] * self.y[j + 2]
+            })
+            .collect();
+        let gamma = factor.solve(&qty);
+
+        let mut fitted = self.y.clone();
+        for (j, &g) in gamma.iter().enumerate() {
+            let q = self.q(j);
+            for k in 0..3 {
+                fitted[j + k] -= lambda * q[k] * g / self.w[j + k];
+            }
+        }
+
+        // tr A = 2 + tr(B⁻¹R), which only needs the band of B⁻¹.
+        let inverse = factor.inverse_band();
+        let mut trace = 2.0;
+        for j in 0..m {
+            trace += inverse.d0[j] * (self.h[j] + self.h[j + 1]) / 3.0;
+            if j + 1 < m {
+                trace += 2.0 * inverse.d1[j] * self.h[j + 1] / 6.0;
+            }
+        }
+
+        // Aᵣᵣ = 1 - λ/wᵣ (QΣQᵀ)ᵣᵣ, where row r of Q touches columns r-2..=r.
+        let leverages = (0..n)
+            .map(|r| {
+                let columns = r.saturating_sub(2)..=r.min(m - 1);
+                let mut quadratic = 0.0;
+                for j in columns.clone() {
+                    for k in columns.clone() {
+                        quadratic += self.q(j)[r - j] * inverse.get(j, k) * self.q(k)[r - k];
+                    }
+                }
+                1.0 - lambda / self.w[r] * quadratic
+            })
+            .collect();
+
+        let rss = (0..n).map(|i| self.w[i] * (self.y[i] - fitted[i]).powi(2)).sum();
+        Solution {
+            fitted,
+            trace,
+            rss,
+            leverages,
+        }
+    }
+
+    fn gcv_score(&self, lambda: f64) -> f64 {
+        let n = self.x.len() as f64;
+        let solution = self.solve(lambda);
+        let denominator = (1.0 - solution.trace / n).powi(2);
+        if denominator <= 0.0 {
+            return f64::INFINITY;
+        }
+        solution.rss / n / denominator
+    }
+
+    // Coarse scan over log λ around the scale where the roughness and
+    // penalty terms balance, then golden-section refinement of the best
+    // bracket.
+    fn gcv_lambda(&self) -> f64 {
+        let m = self.interior();
+        if m == 0 {
+            return 0.0;
+        }
+
+        let reference = self.system(1.0);
+        let penalty_scale: f64 = (0..m).map(|j| (self.h[j] + self.h[j + 1]) / 3.0).sum();
+        let data_scale: f64 = reference.d0.iter().sum::<f64>() - penalty_scale;
+        let center = (penalty_scale / data_scale).log10();
+
+        const STEP: f64 = 0.25;
+        let grid: Vec<f64> = (-40..=40).map(|k| center + k as f64 * STEP).collect();
+        let (best, _) = grid
+            .iter()
+            .map(|&log_lambda| self.gcv_score(10f64.powf(log_lambda)))
+            .enumerate()
+            .fold((0, f64::INFINITY), |best, (i, score)| if score < best.1 { (i, score) } else { best });
+
+        let (mut lo, mut hi) = (grid[best] - STEP, grid[best] + STEP);
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..30 {
+            let a = hi - ratio * (hi - lo);
+            let b = lo + ratio * (hi - lo);
+            if self.gcv_score(10f64.powf(a)) < self.gcv_score(10f64.powf(b)) {
+                hi = b;
+            } else {
+                lo = a;
+            }
+        }
+        10f64.powf((lo + hi) / 2.0)
+    }
+}
+
+// A symmetric pentadiagonal matrix by its three upper diagonals, factorized
+// in place into LDLᵀ (d0 holding D, d1 and d2 the sub-diagonals of L).
+struct Banded {
+    d0: Vec<f64>,
+    d1: Vec<f64>,
+    d2: Vec<f64>,
+}
+
+impl Banded {
+    fn zeros(m: usize) -> Self {
+        Banded {
+            d0: vec![0.0; m],
+            d1: vec![0.0; m],
+            d2: vec![0.0; m],
+        }
+    }
+
+    fn factorize(&mut self) {
+        let m = self.d0.len();
+        for i in 0..m {
+            if i >= 1 {
+                self.d0[i] -= self.d1[i - 1] * self.d1[i - 1] * self.d0[i - 1];
+            }
+            if i >= 2 {
+                self.d0[i] -= self.d2[i - 2] * self.d2[i - 2] * self.d0[i - 2];
+            }
+            if i >= 1 && i + 1 < m {
+                self.d1[i] -= self.d1[i - 1] * self.d0[i - 1] * self.d2[i - 1];
+            }
+            self.d1[i] /= self.d0[i];
+            self.d2[i] /= self.d0[i];
+        }
+    }
+
+    fn solve(&self, rhs: &[f64]) -> Vec<f64> {
+        let m = self.d0.len();
+        let mut x = rhs.to_vec();
+        for i in 0..m {
+            if i >= 1 {
+                x[i] -= self.d1[i - 1] * x[i - 1];
+            }
+            if i >= 2 {
+                x[i] -= self.d2[i - 2] * x[i - 2];
+            }
+        }
+        for (value, d) in x.iter_mut().zip(&self.d0) {
+            *value /= d;
+        }
+        for i in (0..m).rev() {
+            if i + 1 < m {
+                x[i] -= self.d1[i] * x[i + 1];
+            }
+            if i + 2 < m {
+                x[i] -= self.d2[i] * x[i + 2];
+            }
+        }
+        x
+    }
+
+    // Entry (i, j) of the symmetric matrix, zero outside the band.
+    fn get(&self, i: usize, j: usize) -> f64 {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        match j - i {
+            0 => self.d0[i],
+            1 => self.d1[i],
+            2 => self.d2[i],
+            _ => 0.0,
+        }
+    }
+
+    // The entries of the inverse within the band, from the factorization, by
+    // the Hutchinson–de Hoog recurrence Σ = D⁻¹L⁻¹ + (I - Lᵀ)Σ.
+    fn inverse_band(&self) -> Banded {
+        let m = self.d0.len();
+        let mut inverse = Banded::zeros(m);
+
+        for i in (0..m).rev() {
+            for offset in (0..3).rev() {
+                let j = i + offset;
+                if j >= m {
+                    continue;
+                }
+                let mut value = if offset == 0 { 1.0 / self.d0[i] } else { 0.0 };
+                if i + 1 < m {
+                    value -= self.d1[i] * inverse.get(i + 1, j);
+                }
+                if i + 2 < m {
+                    value -= self.d2[i] * inverse.get(i + 2, j);
+                }
+                match offset {
+                    0 => inverse.d0[i] = value,
+                    1 => inverse.d1[i] = value,
+                    _ => inverse.d2[i] = value,
+                }
+            }
+        }
+        inverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rng;
+
+    fn noisy_sine(seed: u64) -> (Vec<Point>, Vec<f32>) {
+        let mut rng = Rng::new(seed);
+        let xs: Vec<f32> = (0..100).map(|i| i as f32 * 0.1).collect();
+        let data = xs
+            .iter()
+            .map(|&x| Point::new(x, x.sin() + 0.1 * rng.gaussian()))
+            .collect();
+        (data, xs.iter().map(|x| x.sin()).collect())
+    }
+
+    #[test]
+    fn lambda_runs_from_interpolation_to_a_straight_line() {
+        let (data, _) = noisy_sine(1);
+
+        let tight = SmoothingSpline::fit(&data, 1e-9).unwrap();
+        for p in &data {
+            assert!((tight.evaluate(p.x) - p.y).abs() < 1e-3);
+        }
+
+        let stiff = SmoothingSpline::fit(&data, 1e9).unwrap();
+        assert!((stiff.edf() - 2.0).abs() < 0.01, "edf {}", stiff.edf());
+        let slope = |x: f32| (stiff.evaluate(x + 0.5) - stiff.evaluate(x)) / 0.5;
+        assert!((slope(1.0) - slope(7.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn gcv_recovers_the_underlying_curve() {
+        let (data, truth) = noisy_sine(2);
+        let smoothing = SmoothingSpline::fit_gcv(&data).unwrap();
+
+        let rms = |ys: &mut dyn Iterator<Item = f32>| (ys.map(|e| e * e).sum::<f32>() / truth.len() as f32).sqrt();
+        let noise = rms(&mut data.iter().zip(&truth).map(|(p, t)| p.y - t));
+        let error = rms(&mut data.iter().zip(&truth).map(|(p, t)| smoothing.evaluate(p.x) - t));
+        assert!(error < 0.5 * noise, "error {} against noise {}", error, noise);
+
+        let (low, high) = smoothing.confidence_band(5.0, 1.96);
+        assert!(low < smoothing.evaluate(5.0) && smoothing.evaluate(5.0) < high);
+    }
+
+    #[test]
+    fn confidence_band_covers_the_underlying_curve() {
+        let (data, truth) = noisy_sine(4);
+        let smoothing = SmoothingSpline::fit_gcv(&data).unwrap();
+
+        let leverages = smoothing.leverages();
+        assert!(leverages.iter().all(|&l| (0.0..=1.0).contains(&l)));
+        assert!((leverages.iter().sum::<f32>() - smoothing.edf()).abs() < 1e-2);
+        // The noise has standard deviation 0.1.
+        assert!((smoothing.residual_variance() - 0.01).abs() < 0.005, "{}", smoothing.residual_variance());
+
+        let covered = data
+            .iter()
+            .zip(&truth)
+            .filter(|(p, &t)| {
+                let (low, high) = smoothing.confidence_band(p.x, 1.96);
+                low <= t && t <= high
+            })
+            .count();
+        assert!(covered >= 80, "{} of 100 covered", covered);
+
+        let tight = SmoothingSpline::fit(&data, 1e-9).unwrap();
+        assert!(tight.standard_error(5.0) < 1e-3);
+    }
+
+    #[test]
+    fn robust_fit_marks_spikes_and_ignores_them() {
+        let (mut data, truth) = noisy_sine(3);
+        let spikes = [20, 55, 80];
+        for &i in &spikes {
+            data[i].y += 5.0;
+        }
+        let fit = RobustFit::fit(&data).unwrap();
+
+        // A spike's neighbours can be pulled just past the threshold too.
+        for i in 0..data.len() {
+            let nearest = spikes.iter().map(|&s| s.abs_diff(i)).min().unwrap();
+            match nearest {
+                0 => assert!(fit.is_outlier(i), "spike {} weight {}", i, fit.weights()[i]),
+                1 => {}
+                _ => assert!(!fit.is_outlier(i), "sample {} weight {}", i, fit.weights()[i]),
+            }
+        }
+        let plain = SmoothingSpline::fit_gcv(&data).unwrap();
+        let error = |s: &SmoothingSpline, i: usize| (s.evaluate(data[i].x) - truth[i]).abs();
+        for &i in &spikes {
+            assert!(error(fit.smoothing(), i) < 0.2, "robust error {} at {}", error(fit.smoothing(), i), i);
+            assert!(error(fit.smoothing(), i) < error(&plain, i));
+        }
+    }
+
+    #[test]
+    fn robust_fit_of_clean_data_keeps_full_weights() {
+        let data: Vec<Point> = (0..10).map(|i| Point::new(i as f32, 2.0 * i as f32)).collect();
+        let fit = RobustFit::fit(&data).unwrap();
+        assert!(fit.weights().iter().all(|&w| w == 1.0));
+        assert!((fit.smoothing().evaluate(4.5) - 9.0).abs() < 1e-3);
+        assert_eq!(RobustFit::fit(&data[..1]).err(), Some(SplineError::TooFewPoints));
+    }
+
+    #[test]
+    fn repeated_x_values_merge_into_their_mean() {
+        let data = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 3.0),
+            Point::new(2.0, 0.0),
+        ];
+        let smoothing = SmoothingSpline::fit(&data, 1e-9).unwrap();
+        assert_eq!(smoothing.fitted().len(), 3);
+        assert!((smoothing.evaluate(1.0) - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn unusable_data_is_an_error() {
+        assert_eq!(SmoothingSpline::fit(&[], 1.0).err(), Some(SplineError::TooFewPoints));
+        assert_eq!(
+            SmoothingSpline::fit_gcv(&[Point::new(1.0, 0.0), Point::new(1.0, 2.0)]).err(),
+            Some(SplineError::TooFewPoints)
+        );
+        assert_eq!(
+            SmoothingSpline::fit(
+                &[
+                    Point::new(0.0, 0.0),
+                    Point::new(1.0, f32::INFINITY),
+                    Point::new(2.0, 0.0)
+                ],
+                1.0
+            )
+            .err(),
+            Some(SplineError::NonFinite(1))
+        );
+        assert!(SmoothingSpline::fit_gcv(&[Point::new(0.0, 0.0), Point::new(1.0, 1.0)]).is_ok());
+    }
+}
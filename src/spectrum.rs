@@ -0,0 +1,119 @@
+use crate::Spline;
+
+impl Spline {
+    /// The curve read as one cycle of a periodic waveform: the amplitude of
+    /// each harmonic, from the DC offset at index 0 up to the Nyquist limit,
+    /// found by an FFT of `samples` evenly spaced values across the domain
+    /// (rounded up to a power of two, and at least 2).
+    ///
+    /// A sine of amplitude `a` spanning the domain once shows `a` at index 1.
+    pub fn harmonic_spectrum(&self, samples: usize) -> Vec<f32> {
+        let n = samples.max(2).next_power_of_two();
+        let (min, max) = self.domain();
+        // The end of the cycle is the start of the next, so it is left out.
+        let mut re: Vec<f32> = (0..n)
+            .map(|i| self.evaluate(min + (max - min) * i as f32 / n as f32))
+            .collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+
+        (0..=n / 2)
+            .map(|k| {
+                let magnitude = re[k].hypot(im[k]) / n as f32;
+                if k == 0 || k == n / 2 {
+                    magnitude
+                } else {
+                    2.0 * magnitude
+                }
+            })
+            .collect()
+    }
+}
+
+/// Total harmonic distortion: the RMS of harmonics 2 and up relative to the
+/// fundamental, for a spectrum from [`Spline::harmonic_spectrum`]. Zero when
+/// there is no fundamental.
+pub fn total_harmonic_distortion(spectrum: &[f32]) -> f32 {
+    match spectrum.get(1) {
+        Some(&fundamental) if fundamental > 0.0 => {
+            spectrum[2..].iter().map(|a| a * a).sum::<f32>().sqrt() / fundamental
+        }
+        _ => 0.0,
+    }
+}
+
+// In-place iterative radix-2 Cooley–Tukey; `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f32::consts::TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let (tr, ti) = (re[b] * cos - im[b] * sin, re[b] * sin + im[b] * cos);
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, Rng, SplineType};
+    use std::f32::consts::TAU;
+
+    #[test]
+    fn fft_matches_a_direct_transform() {
+        let mut rng = Rng::new(5);
+        let input: Vec<f32> = (0..16).map(|_| rng.gaussian()).collect();
+        let (mut re, mut im) = (input.clone(), vec![0.0; 16]);
+        fft(&mut re, &mut im);
+        for k in 0..16 {
+            let (mut sum_re, mut sum_im) = (0.0, 0.0);
+            for (i, &v) in input.iter().enumerate() {
+                let (sin, cos) = (-TAU * (k * i) as f32 / 16.0).sin_cos();
+                sum_re += v * cos;
+                sum_im += v * sin;
+            }
+            assert!((re[k] - sum_re).abs() < 1e-4 && (im[k] - sum_im).abs() < 1e-4, "bin {}", k);
+        }
+    }
+
+    #[test]
+    fn sine_shows_at_the_fundamental() {
+        let points: Vec<Point> = (0..=64)
+            .map(|i| {
+                let x = i as f32 / 64.0;
+                Point::new(x, 0.5 + 2.0 * (TAU * x).sin())
+            })
+            .collect();
+        let spectrum = Spline::new(&points, SplineType::Cubic).harmonic_spectrum(100);
+        assert_eq!(spectrum.len(), 65);
+        assert!((spectrum[0] - 0.5).abs() < 1e-3, "DC {}", spectrum[0]);
+        assert!((spectrum[1] - 2.0).abs() < 1e-2, "fundamental {}", spectrum[1]);
+        assert!(total_harmonic_distortion(&spectrum) < 1e-2);
+    }
+
+    #[test]
+    fn distortion_is_relative_to_the_fundamental() {
+        assert_eq!(total_harmonic_distortion(&[0.0, 2.0, 0.6, 0.8]), 0.5);
+        assert_eq!(total_harmonic_distortion(&[1.0, 0.0, 3.0]), 0.0);
+        assert_eq!(total_harmonic_distortion(&[1.0]), 0.0);
+    }
+}
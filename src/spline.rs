@@ -0,0 +1,479 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::piecewise::horner;
+use crate::Segment;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Point { x, y }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplineType {
+    Linear,
+    Quadratic,
+    Cubic,
+}
+
+impl SplineType {
+    /// The degree of the polynomial pieces.
+    pub fn degree(&self) -> usize {
+        match self {
+            SplineType::Linear => 1,
+            SplineType::Quadratic => 2,
+            SplineType::Cubic => 3,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplineError {
+    TooFewPoints,
+    DuplicateX(f32),
+    /// The point at this index (in the order given, or the knot being moved)
+    /// has a NaN or infinite coordinate.
+    NonFinite(usize),
+    /// The point at this index has a coordinate `<= 0` on a log axis.
+    NonPositive(usize),
+    /// The curve turns back at this x, so it has no inverse.
+    NotMonotone(f32),
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SplineError::TooFewPoints => write!(f, "need at least 2 points to interpolate"),
+            SplineError::DuplicateX(x) => write!(f, "x values must be distinct, but {} repeats", x),
+            SplineError::NonFinite(i) => write!(f, "point {} has a NaN or infinite coordinate", i),
+            SplineError::NonPositive(i) => write!(f, "point {} is not positive on a log axis", i),
+            SplineError::NotMonotone(x) => write!(f, "curve is not strictly monotone, it turns at x = {}", x),
+        }
+    }
+}
+
+impl Error for SplineError {}
+
+pub(crate) fn is_finite(p: &Point) -> bool {
+    p.x.is_finite() && p.y.is_finite()
+}
+
+// A segment starting at `y`, its other coefficients left to the fit.
+fn new_piece(y: f32, spline_type: SplineType) -> Vec<f32> {
+    let mut piece = vec![0.0; spline_type.degree() + 1];
+    piece[0] = y;
+    piece
+}
+
+// `points` sorted by x, once checked to be at least two finite points with
+// distinct x values.
+pub(crate) fn sorted_knots(points: &[Point]) -> Result<Vec<Point>, SplineError> {
+    if points.len() < 2 {
+        return Err(SplineError::TooFewPoints);
+    }
+    if let Some(i) = points.iter().position(|p| !is_finite(p)) {
+        return Err(SplineError::NonFinite(i));
+    }
+    let mut sorted_points = points.to_vec();
+    sorted_points.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    for pair in sorted_points.windows(2) {
+        if pair[1].x == pair[0].x {
+            return Err(SplineError::DuplicateX(pair[0].x));
+        }
+    }
+    Ok(sorted_points)
+}
+
+pub struct Spline {
+    points: Vec<Point>,
+    spline_type: SplineType,
+    // One per segment, by ascending powers of `x - x0`, so `degree() + 1`
+    // long; the constant term is always the segment's left knot's y.
+    pieces: Vec<Vec<f32>>,
+}
+
+impl Spline {
+    /// Panics where [`Spline::try_new`] would return an error.
+    pub fn new(points: &[Point], spline_type: SplineType) -> Self {
+        Spline::try_new(points, spline_type).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fits a spline through `points`, which may be given in any order.
+    pub fn try_new(points: &[Point], spline_type: SplineType) -> Result<Self, SplineError> {
+        let sorted_points = sorted_knots(points)?;
+        let mut spline = Spline {
+            pieces: sorted_points[..sorted_points.len() - 1]
+                .iter()
+                .map(|p| new_piece(p.y, spline_type))
+                .collect(),
+            points: sorted_points,
+            spline_type,
+        };
+        spline.fit();
+        Ok(spline)
+    }
+
+    // Rebuilds a spline from already-fitted pieces without refitting. The
+    // caller guarantees the knots are sorted and distinct and that there are
+    // `points.len() - 1` pieces. Each piece's constant term is taken from its
+    // left knot, and it is padded or cut to the type's degree.
+    pub(crate) fn from_pieces(points: Vec<Point>, spline_type: SplineType, mut pieces: Vec<Vec<f32>>) -> Self {
+        for (piece, knot) in pieces.iter_mut().zip(&points) {
+            piece.resize(spline_type.degree() + 1, 0.0);
+            piece[0] = knot.y;
+        }
+        Spline {
+            pieces,
+            points,
+            spline_type,
+        }
+    }
+
+    /// Moves knot `i` (in ascending x order) to `p` and returns its new index,
+    /// which differs from `i` when the move carries it past a neighbour.
+    /// Fails, leaving the spline as it was, if `p` is not finite or another
+    /// knot already has its x.
+    pub fn set_point(&mut self, i: usize, p: Point) -> Result<usize, SplineError> {
+        if !is_finite(&p) {
+            return Err(SplineError::NonFinite(i));
+        }
+        let n = self.points.len();
+        let stays_in_order = (i == 0 || self.points[i - 1].x < p.x) && (i + 1 == n || p.x < self.points[i + 1].x);
+
+        if stays_in_order {
+            self.points[i] = p;
+            if let Some(piece) = self.pieces.get_mut(i) {
+                piece[0] = p.y;
+            }
+            self.refit_around(i);
+            return Ok(i);
+        }
+
+        // Out of order, `p.x` can only match a knot other than `i`.
+        let j = self.insertion_index(p.x)?;
+        let j = if j > i { j - 1 } else { j };
+        self.points.remove(i);
+        self.points.insert(j, p);
+        for (piece, knot) in self.pieces.iter_mut().zip(&self.points) {
+            piece[0] = knot.y;
+        }
+        self.fit();
+        Ok(j)
+    }
+
+    /// Adds a knot at `p` and returns its index in ascending x order. Fails,
+    /// leaving the spline as it was, if `p` is not finite or a knot already
+    /// has its x.
+    pub fn insert_point(&mut self, p: Point) -> Result<usize, SplineError> {
+        if !is_finite(&p) {
+            return Err(SplineError::NonFinite(0));
+        }
+        let j = self.insertion_index(p.x)?;
+        self.points.insert(j, p);
+
+        // Appending past the last knot adds the segment on its left instead.
+        let segment = j.min(self.pieces.len());
+        let piece = new_piece(self.points[segment].y, self.spline_type);
+        self.pieces.insert(segment, piece);
+
+        self.refit_around(j);
+        Ok(j)
+    }
+
+    fn insertion_index(&self, x: f32) -> Result<usize, SplineError> {
+        let j = self.points.partition_point(|p| p.x < x);
+        if j < self.points.len() && self.points[j].x == x {
+            return Err(SplineError::DuplicateX(x));
+        }
+        Ok(j)
+    }
+
+    fn h(&self, i: usize) -> f32 {
+        self.points[i + 1].x - self.points[i].x
+    }
+
+    fn fit(&mut self) {
+        match self.spline_type {
+            SplineType::Linear => {
+                for i in 0..self.points.len() - 1 {
+                    self.fit_linear_segment(i);
+                }
+            }
+            SplineType::Quadratic => self.fit_quadratic_from(0),
+            SplineType::Cubic => self.fit_cubic(),
+        }
+    }
+
+    // A knot only enters the two segments either side of it for linear
+    // splines; the quadratic recurrence carries the change to every segment
+    // on its right, and the cubic system couples all of them.
+    fn refit_around(&mut self, knot: usize) {
+        let first = knot.saturating_sub(1);
+        match self.spline_type {
+            SplineType::Linear => {
+                for i in first..=knot.min(self.points.len() - 2) {
+                    self.fit_linear_segment(i);
+                }
+            }
+            SplineType::Quadratic => self.fit_quadratic_from(first),
+            SplineType::Cubic => self.fit_cubic(),
+        }
+    }
+
+    fn fit_linear_segment(&mut self, i: usize) {
+        self.pieces[i][1] = (self.points[i + 1].y - self.points[i].y) / self.h(i);
+    }
+
+    fn fit_quadratic_from(&mut self, first: usize) {
+        for i in first..self.points.len() - 1 {
+            let h = self.h(i);
+            let rise = self.points[i + 1].y - self.points[i].y;
+            if i == 0 {
+                self.pieces[0][1] = rise / h;
+                self.pieces[0][2] = 0.0;
+            } else {
+                let previous = &self.pieces[i - 1];
+                let b = previous[1] + 2.0 * previous[2] * self.h(i - 1);
+                self.pieces[i][1] = b;
+                self.pieces[i][2] = (rise - b * h) / (h * h);
+            }
+        }
+    }
+
+    fn fit_cubic(&mut self) {
+        let n = self.points.len();
+        let h: Vec<f32> = (0..n - 1).map(|i| self.h(i)).collect();
+        let a_coeffs: Vec<f32> = self.points.iter().map(|p| p.y).collect();
+
+        let mut c_internal = vec![0.0; n];
+
+        let mut alpha = vec![0.0; n - 1];
+        for i in 1..n - 1 {
+            alpha[i] = 3.0 * ((a_coeffs[i + 1] - a_coeffs[i]) / h[i] - (a_coeffs[i] - a_coeffs[i - 1]) / h[i - 1]);
+        }
+
+        let mut l = vec![0.0; n];
+        let mut mu = vec![0.0; n];
+        let mut z = vec![0.0; n];
+
+        l[0] = 1.0;
+
+        for i in 1..n - 1 {
+            l[i] = 2.0 * (self.points[i + 1].x - self.points[i - 1].x) - h[i - 1] * mu[i - 1];
+            if l[i] == 0.0 {
+                panic!("Division by zero in cubic spline calculation (l[i])");
+            }
+            mu[i] = h[i] / l[i];
+            z[i] = (alpha[i] - h[i - 1] * z[i - 1]) / l[i];
+        }
+
+        l[n - 1] = 1.0;
+
+        for j in (0..n - 1).rev() {
+            c_internal[j] = z[j] - mu[j] * c_internal[j + 1];
+
+            let piece = &mut self.pieces[j];
+            piece[2] = c_internal[j];
+            piece[1] = (a_coeffs[j + 1] - a_coeffs[j]) / h[j] - h[j] * (c_internal[j + 1] + 2.0 * c_internal[j]) / 3.0;
+            piece[3] = (c_internal[j + 1] - c_internal[j]) / (3.0 * h[j]);
+        }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn spline_type(&self) -> SplineType {
+        self.spline_type
+    }
+
+    /// The x-range covered by the knots, as `(min_x, max_x)`.
+    pub fn domain(&self) -> (f32, f32) {
+        (self.points[0].x, self.points[self.points.len() - 1].x)
+    }
+
+    /// Evaluates `n` evenly spaced samples across `domain()`. The x values are
+    /// non-decreasing and the first and last samples land exactly on the
+    /// domain bounds.
+    pub fn iter_samples(&self, n: usize) -> impl Iterator<Item = Point> + '_ {
+        (0..n).map(move |i| {
+            let x = self.sample_x(i, n);
+            Point::new(x, self.evaluate(x))
+        })
+    }
+
+    pub(crate) fn sample_x(&self, i: usize, n: usize) -> f32 {
+        let (min_x, max_x) = self.domain();
+        let last = n.saturating_sub(1);
+
+        if i == 0 {
+            min_x
+        } else if i == last {
+            max_x
+        } else {
+            (min_x + (max_x - min_x) * (i as f32 / last as f32)).min(max_x)
+        }
+    }
+
+    /// The polynomial pieces between consecutive knots, in ascending x order.
+    pub fn segments(&self) -> impl Iterator<Item = Segment<'_>> + '_ {
+        (0..self.points.len() - 1).map(move |i| self.segment(i))
+    }
+
+    pub(crate) fn segment(&self, i: usize) -> Segment<'_> {
+        Segment {
+            x0: self.points[i].x,
+            x1: self.points[i + 1].x,
+            coeffs: &self.pieces[i],
+        }
+    }
+
+    /// The coefficients of each segment's polynomial, by ascending powers of
+    /// `x - x0`, in ascending x order.
+    pub fn pieces(&self) -> &[Vec<f32>] {
+        &self.pieces
+    }
+
+    /// Length of the curve between `x0` and `x1`, in either order, with both
+    /// clamped into `domain()`.
+    pub fn arc_length(&self, x0: f32, x1: f32) -> f32 {
+        let (min, max) = self.domain();
+        let (lo, hi) = (x0.min(x1).clamp(min, max), x0.max(x1).clamp(min, max));
+        self.segments()
+            .filter(|s| s.x1 > lo && s.x0 < hi)
+            .map(|s| s.arc_length_between(s.x0.max(lo), s.x1.min(hi)))
+            .sum()
+    }
+
+    /// The slope at `x`, matching how [`Spline::evaluate`] extends the curve
+    /// past its ends: along the end slopes for linear splines, flat otherwise.
+    pub fn derivative(&self, x: f32) -> f32 {
+        let (min, max) = self.domain();
+        if (x < min || x > max) && self.spline_type != SplineType::Linear {
+            return 0.0;
+        }
+        let last = self.points.len() - 2;
+        let i = self.segments().position(|s| x <= s.x1).unwrap_or(last);
+        self.segment(i).derivative(x.clamp(min, max))
+    }
+
+    pub fn evaluate(&self, x: f32) -> f32 {
+        if self.points.is_empty() {
+            return 0.0;
+        }
+        if self.points.len() == 1 {
+            return self.points[0].y;
+        }
+
+        let mut i = 0;
+        while i < self.points.len() - 2 && x > self.points[i + 1].x {
+            i += 1;
+        }
+
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if x < first.x {
+            if self.spline_type == SplineType::Linear {
+                return horner(&self.pieces[0], x - first.x);
+            }
+            return first.y;
+        }
+        if x > last.x {
+            if self.spline_type == SplineType::Linear {
+                let slope = self.pieces[self.pieces.len() - 1][1];
+                return last.y + slope * (x - last.x);
+            }
+            return last.y;
+        }
+        if x == last.x {
+            return last.y;
+        }
+
+        horner(&self.pieces[i], x - self.points[i].x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: [SplineType; 3] = [SplineType::Linear, SplineType::Quadratic, SplineType::Cubic];
+
+    fn knots() -> Vec<Point> {
+        vec![
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 3.0),
+            Point::new(2.5, -1.0),
+            Point::new(3.0, 0.5),
+            Point::new(5.0, 2.0),
+        ]
+    }
+
+    // The edited spline must be the one a fresh fit through its knots gives.
+    fn assert_refit(spline: &Spline) {
+        let fresh = Spline::new(spline.points(), spline.spline_type());
+        assert_eq!(spline.points(), fresh.points());
+        for (piece, expected) in spline.pieces().iter().zip(fresh.pieces()) {
+            for (c, e) in piece.iter().zip(expected) {
+                assert!((c - e).abs() < 1e-4, "{:?}: {:?} against {:?}", spline.spline_type(), piece, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn set_point_matches_a_fresh_fit() {
+        let moves = [
+            (2, Point::new(2.2, 4.0), 2),
+            (0, Point::new(-0.5, -2.0), 0),
+            (4, Point::new(6.0, 1.0), 4),
+            // Past two neighbours to the right, then to the left.
+            (1, Point::new(2.8, 0.0), 2),
+            (3, Point::new(0.5, 2.0), 1),
+        ];
+        for spline_type in TYPES {
+            for &(i, p, expected) in &moves {
+                let mut spline = Spline::new(&knots(), spline_type);
+                assert_eq!(spline.set_point(i, p), Ok(expected));
+                assert_eq!(spline.points()[expected], p);
+                assert_refit(&spline);
+            }
+        }
+    }
+
+    #[test]
+    fn insert_point_matches_a_fresh_fit() {
+        let inserts = [
+            (Point::new(1.5, 0.0), 2),
+            (Point::new(-1.0, 0.0), 0),
+            (Point::new(7.0, -3.0), 5),
+        ];
+        for spline_type in TYPES {
+            for &(p, expected) in &inserts {
+                let mut spline = Spline::new(&knots(), spline_type);
+                assert_eq!(spline.insert_point(p), Ok(expected));
+                assert_refit(&spline);
+            }
+        }
+    }
+
+    #[test]
+    fn rejected_edits_leave_the_spline_alone() {
+        for spline_type in TYPES {
+            let mut spline = Spline::new(&knots(), spline_type);
+            let before = spline.pieces().to_vec();
+            assert_eq!(spline.set_point(1, Point::new(3.0, 0.0)), Err(SplineError::DuplicateX(3.0)));
+            assert_eq!(spline.set_point(2, Point::new(f32::NAN, 0.0)), Err(SplineError::NonFinite(2)));
+            assert_eq!(spline.insert_point(Point::new(2.5, 7.0)), Err(SplineError::DuplicateX(2.5)));
+            assert_eq!(spline.insert_point(Point::new(1.0, f32::INFINITY)), Err(SplineError::NonFinite(0)));
+            assert_eq!(spline.points(), &knots()[..]);
+            assert_eq!(spline.pieces(), &before[..]);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use crate::Point;
+
+/// A natural cubic spline over a sliding window of the most recent samples,
+/// for data that arrives in increasing x (e.g. time) order.
+///
+/// Appending only re-solves the second derivatives of the last few knots,
+/// and once the window is full, of the first few, as the knot after the
+/// dropped one becomes the natural end. On a cubic spline the effect of a
+/// change at one knot decays by a factor of about `2 - √3` per knot, so the
+/// result matches a full solve over the window to within float noise while
+/// costing O(1) per push.
+pub struct StreamingSpline {
+    capacity: usize,
+    xs: VecDeque<f32>,
+    ys: VecDeque<f32>,
+    second_derivatives: VecDeque<f32>,
+}
+
+impl StreamingSpline {
+    const TAIL: usize = 12;
+
+    /// A spline keeping at most `capacity` knots.
+    pub fn new(capacity: usize) -> Self {
+        if capacity < 2 {
+            panic!("A streaming spline needs room for at least 2 points.");
+        }
+        StreamingSpline {
+            capacity,
+            xs: VecDeque::with_capacity(capacity + 1),
+            ys: VecDeque::with_capacity(capacity + 1),
+            second_derivatives: VecDeque::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Appends a sample, dropping the oldest one once the window is full.
+    /// Panics unless `p.x` is greater than the last appended x and both
+    /// coordinates are finite.
+    pub fn push(&mut self, p: Point) {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            panic!("Streaming spline points must have finite coordinates.");
+        }
+        if let Some(&last) = self.xs.back() {
+            if p.x <= last {
+                panic!("Streaming spline points must be appended in increasing x order.");
+            }
+        }
+
+        self.xs.push_back(p.x);
+        self.ys.push_back(p.y);
+        self.second_derivatives.push_back(0.0);
+        let evicted = self.xs.len() > self.capacity;
+        if evicted {
+            self.xs.pop_front();
+            self.ys.pop_front();
+            self.second_derivatives.pop_front();
+            self.second_derivatives[0] = 0.0;
+        }
+
+        let n = self.xs.len();
+        if n < 3 {
+            return;
+        }
+        if evicted {
+            self.refit(1, Self::TAIL.min(n - 2));
+        }
+        self.refit((n - 1).saturating_sub(Self::TAIL).max(1), n - 2);
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    pub fn domain(&self) -> Option<(f32, f32)> {
+        Some((*self.xs.front()?, *self.xs.back()?))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.xs.iter().zip(&self.ys).map(|(&x, &y)| Point::new(x, y))
+    }
+
+    /// Evaluates the spline, holding the end values outside the window.
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let n = self.xs.len();
+        match n {
+            0 => return 0.0,
+            1 => return self.ys[0],
+            _ => {}
+        }
+        if x <= self.xs[0] {
+            return self.ys[0];
+        }
+        if x >= self.xs[n - 1] {
+            return self.ys[n - 1];
+        }
+
+        let i = self.xs.partition_point(|&knot| knot <= x) - 1;
+        let (x0, x1) = (self.xs[i], self.xs[i + 1]);
+        let (y0, y1) = (self.ys[i], self.ys[i + 1]);
+        let (m0, m1) = (self.second_derivatives[i], self.second_derivatives[i + 1]);
+        let h = x1 - x0;
+        let (left, right) = (x1 - x, x - x0);
+
+        let cubic = (m0 * left.powi(3) + m1 * right.powi(3)) / (6.0 * h);
+        let linear = (y0 / h - m0 * h / 6.0) * left + (y1 / h - m1 * h / 6.0) * right;
+        cubic + linear
+    }
+
+    // Solves the tridiagonal system for the second derivatives of knots
+    // `first..=last`, holding the knots either side at their stored values.
+    fn refit(&mut self, first: usize, last: usize) {
+        let count = last + 1 - first;
+        let h = |i: usize| self.xs[i + 1] - self.xs[i];
+        let slope = |i: usize| (self.ys[i + 1] - self.ys[i]) / h(i);
+
+        let mut diagonal = vec![0.0; count];
+        let mut upper = vec![0.0; count];
+        let mut rhs = vec![0.0; count];
+        for k in 0..count {
+            let i = first + k;
+            diagonal[k] = 2.0 * (h(i - 1) + h(i));
+            upper[k] = h(i);
+            rhs[k] = 6.0 * (slope(i) - slope(i - 1));
+        }
+        rhs[0] -= h(first - 1) * self.second_derivatives[first - 1];
+        rhs[count - 1] -= h(last) * self.second_derivatives[last + 1];
+
+        for k in 1..count {
+            let factor = h(first + k - 1) / diagonal[k - 1];
+            diagonal[k] -= factor * upper[k - 1];
+            rhs[k] -= factor * rhs[k - 1];
+        }
+
+        let mut next = 0.0;
+        for k in (0..count).rev() {
+            next = (rhs[k] - upper[k] * next) / diagonal[k];
+            self.second_derivatives[first + k] = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Spline, SplineType};
+
+    // Checks the window against a full natural cubic through the same points
+    // after every push, across the window and at its leading edge.
+    fn assert_matches_full_solve(capacity: usize, pushes: usize) {
+        let mut streaming = StreamingSpline::new(capacity);
+        for i in 0..pushes {
+            let x = i as f32 * 0.37 + 0.05 * (i % 3) as f32;
+            streaming.push(Point::new(x, (x * 1.3).sin() + 0.4 * (x * 3.1).cos()));
+            if streaming.len() < 2 {
+                continue;
+            }
+
+            let points: Vec<Point> = streaming.points().collect();
+            let full = Spline::new(&points, SplineType::Cubic);
+            let (min, max) = streaming.domain().unwrap();
+            for k in 0..=200 {
+                let x = min + (max - min) * k as f32 / 200.0;
+                let error = (streaming.evaluate(x) - full.evaluate(x)).abs();
+                assert!(error < 1e-4, "push {} at {}: off by {}", i, x, error);
+            }
+        }
+    }
+
+    #[test]
+    fn short_window_matches_a_full_solve_before_and_after_eviction() {
+        assert_matches_full_solve(10, 40);
+    }
+
+    #[test]
+    fn long_window_matches_a_full_solve_before_and_after_eviction() {
+        assert_matches_full_solve(40, 120);
+    }
+}
@@ -0,0 +1,25 @@
+/// A key/value pair attached to a control point, such as `keyframe: hold`
+/// or `marker: beat-4`, for carrying meaning along with the coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointTag {
+    /// The control point, by index in ascending x order.
+    pub point: usize,
+    pub key: String,
+    pub value: String,
+}
+
+impl PointTag {
+    /// Parses `key:value`, trimming both parts. The key can't be empty.
+    pub fn parse(point: usize, text: &str) -> Option<PointTag> {
+        let (key, value) = text.split_once(':')?;
+        let key = key.trim();
+        if key.is_empty() {
+            return None;
+        }
+        Some(PointTag {
+            point,
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use crate::Spline;
+
+/// A value animated over time by a spline, optionally retimed by a second
+/// spline: with an easing curve set, the track at time `t` is
+/// `curve(ease(t))`, so the ease changes when the curve's values arrive
+/// without changing the values themselves.
+pub struct Track {
+    curve: Spline,
+    ease: Option<Spline>,
+}
+
+impl Track {
+    pub fn new(curve: Spline) -> Self {
+        Track { curve, ease: None }
+    }
+
+    pub fn curve(&self) -> &Spline {
+        &self.curve
+    }
+
+    pub fn set_curve(&mut self, curve: Spline) {
+        self.curve = curve;
+    }
+
+    pub fn ease(&self) -> Option<&Spline> {
+        self.ease.as_ref()
+    }
+
+    /// The ease maps track time to curve time, in the same units as the
+    /// curve's x. `None` removes it.
+    pub fn set_ease(&mut self, ease: Option<Spline>) {
+        self.ease = ease;
+    }
+
+    /// The time the curve is read at for track time `t`.
+    pub fn remap(&self, t: f32) -> f32 {
+        match &self.ease {
+            Some(ease) => ease.evaluate(t),
+            None => t,
+        }
+    }
+
+    pub fn evaluate(&self, t: f32) -> f32 {
+        self.curve.evaluate(self.remap(t))
+    }
+}